@@ -0,0 +1,305 @@
+//! # Schedulability Analysis
+//!
+//! Static analysis helpers over the created task set. These are pure
+//! functions of the TCB array and can be called before `kernel::start()`
+//! to catch an over-subscribed configuration at startup.
+//!
+//! ## Task Model
+//!
+//! Periodic tasks are those with both `deadline_ticks` and `wcet_ticks`
+//! set. The deadline doubles as the period (implicit-deadline model),
+//! matching how the scheduler resets `period_ticks` at each deadline.
+//! Best-effort tasks contribute no utilization.
+//!
+//! All arithmetic is integer-only. Utilization is accumulated in parts per
+//! million and reported as fixed-point ×100 (e.g., 75 = 75%).
+
+use crate::config::MAX_TASKS;
+use crate::task::TaskControlBlock;
+
+/// Liu–Layland bound `n(2^(1/n) − 1)` in parts per million, indexed by `n`.
+const LIU_LAYLAND_PPM: [u32; 9] = [
+    1_000_000, // n = 0 (unused)
+    1_000_000, // n = 1
+    828_427,
+    779_763,
+    756_828,
+    743_492,
+    734_772,
+    728_627,
+    724_062,
+];
+
+/// Asymptotic Liu–Layland bound (ln 2) in parts per million.
+const LIU_LAYLAND_LIMIT_PPM: u32 = 693_147;
+
+/// Check whether a task takes part in periodic utilization analysis.
+#[inline]
+fn is_periodic(task: &TaskControlBlock) -> bool {
+    task.active && task.config.deadline_ticks > 0 && task.config.wcet_ticks > 0
+}
+
+/// Check whether a task is part of the mandatory (anchor) load.
+#[inline]
+fn is_mandatory(task: &TaskControlBlock) -> bool {
+    is_periodic(task) && task.config.anchor
+}
+
+/// Sum `wcet / period` over all periodic tasks, in parts per million.
+fn utilization_ppm(tasks: &[TaskControlBlock; MAX_TASKS], task_count: usize) -> u64 {
+    filtered_utilization_ppm(tasks, task_count, is_periodic)
+}
+
+/// Sum `wcet / period` over the periodic tasks selected by `include`.
+fn filtered_utilization_ppm(
+    tasks: &[TaskControlBlock; MAX_TASKS],
+    task_count: usize,
+    include: fn(&TaskControlBlock) -> bool,
+) -> u64 {
+    let mut total: u64 = 0;
+    for task in tasks.iter().take(task_count).filter(|t| include(t)) {
+        let wcet = task.config.wcet_ticks as u64;
+        let period = task.config.deadline_ticks as u64;
+        total += (wcet * 1_000_000).div_ceil(period);
+    }
+    total
+}
+
+/// Total theoretical CPU utilization of the periodic task set (×100).
+///
+/// Rounded up, so a set reported at 100 or below never exceeds the CPU.
+pub fn total_utilization(tasks: &[TaskControlBlock; MAX_TASKS], task_count: usize) -> u32 {
+    let ppm = utilization_ppm(tasks, task_count);
+    ppm.div_ceil(10_000) as u32
+}
+
+/// Liu–Layland utilization bound for `n` tasks, in parts per million.
+pub fn liu_layland_bound_ppm(n: usize) -> u32 {
+    if n < LIU_LAYLAND_PPM.len() {
+        LIU_LAYLAND_PPM[n]
+    } else {
+        LIU_LAYLAND_LIMIT_PPM
+    }
+}
+
+/// Apply the Liu–Layland sufficient test for rate-monotonic scheduling.
+///
+/// Returns `true` if the periodic tasks' total utilization is within
+/// `n(2^(1/n) − 1)`. A `false` result does not prove the set is
+/// unschedulable — only that this bound cannot guarantee it.
+pub fn is_rm_schedulable(tasks: &[TaskControlBlock; MAX_TASKS], task_count: usize) -> bool {
+    let n = tasks[..task_count].iter().filter(|t| is_periodic(t)).count();
+    if n == 0 {
+        return true;
+    }
+    utilization_ppm(tasks, task_count) <= liu_layland_bound_ppm(n) as u64
+}
+
+/// Apply the Liu–Layland test to the anchor tasks alone.
+///
+/// Anchors are the load that must be met even when everything else is
+/// shed, so this is the guarantee that matters under overload. Returns
+/// `true` if there are no periodic anchors.
+pub fn is_mandatory_schedulable(tasks: &[TaskControlBlock; MAX_TASKS], task_count: usize) -> bool {
+    let n = tasks[..task_count].iter().filter(|t| is_mandatory(t)).count();
+    if n == 0 {
+        return true;
+    }
+    filtered_utilization_ppm(tasks, task_count, is_mandatory) <= liu_layland_bound_ppm(n) as u64
+}
+
+/// Startup go/no-go on the whole task set, from [`validate_schedulability`].
+///
+/// Offending tasks are reported as bitmasks of task ids (bit `i` = task
+/// `i`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchedulabilityReport {
+    /// Total utilization of the periodic tasks (×100).
+    pub utilization: u32,
+    /// Whether the periodic tasks pass the Liu–Layland bound. Deadlines
+    /// equal periods, so the rate- and deadline-monotonic tests coincide.
+    pub rm_schedulable: bool,
+    /// Tasks whose WCET exceeds their deadline: they miss every period.
+    pub wcet_over_deadline: u32,
+    /// Tasks whose affinity mask allows no core, so they never run.
+    pub zero_affinity: u32,
+    /// Periodic tasks whose base priorities contradict deadline-monotonic
+    /// order: one has a shorter deadline but a lower priority than the
+    /// other. Both tasks of each such pair are flagged.
+    pub priority_conflicts: u32,
+}
+
+impl SchedulabilityReport {
+    /// `true` if nothing was flagged and the Liu–Layland bound holds.
+    ///
+    /// Conservative: the bound is only sufficient, so a set that fails
+    /// it may still be schedulable.
+    pub fn is_ok(&self) -> bool {
+        self.rm_schedulable
+            && self.wcet_over_deadline == 0
+            && self.zero_affinity == 0
+            && self.priority_conflicts == 0
+    }
+}
+
+/// Run every static check over the created task set.
+pub fn validate_schedulability(
+    tasks: &[TaskControlBlock; MAX_TASKS],
+    task_count: usize,
+) -> SchedulabilityReport {
+    let mut report = SchedulabilityReport {
+        utilization: total_utilization(tasks, task_count),
+        rm_schedulable: is_rm_schedulable(tasks, task_count),
+        wcet_over_deadline: 0,
+        zero_affinity: 0,
+        priority_conflicts: 0,
+    };
+
+    for (i, task) in tasks[..task_count].iter().enumerate() {
+        if !task.active {
+            continue;
+        }
+        if task.config.affinity_mask == 0 {
+            report.zero_affinity |= 1 << i;
+        }
+        if is_periodic(task) && task.config.wcet_ticks > task.config.deadline_ticks {
+            report.wcet_over_deadline |= 1 << i;
+        }
+        if !is_periodic(task) {
+            continue;
+        }
+        for (j, other) in tasks[..task_count].iter().enumerate() {
+            if is_periodic(other)
+                && task.config.deadline_ticks < other.config.deadline_ticks
+                && task.config.priority < other.config.priority
+            {
+                report.priority_conflicts |= (1 << i) | (1 << j);
+            }
+        }
+    }
+    report
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Strategy, TaskConfig};
+
+    fn make_periodic(id: usize, wcet_ticks: u32, deadline_ticks: u32) -> TaskControlBlock {
+        let mut tcb = TaskControlBlock::empty();
+        let config = TaskConfig {
+            priority: 1,
+            deadline_ticks,
+            wcet_ticks,
+            ..TaskConfig::DEFAULT
+        };
+        tcb.init(id, config, Strategy::Cooperative);
+        tcb
+    }
+
+    #[test]
+    fn test_schedulable_task_set() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        tasks[0] = make_periodic(0, 10, 100); // 10%
+        tasks[1] = make_periodic(1, 20, 100); // 20%
+        tasks[2] = make_periodic(2, 15, 50);  // 30%
+
+        assert_eq!(total_utilization(&tasks, 3), 60);
+        assert!(is_rm_schedulable(&tasks, 3));
+    }
+
+    #[test]
+    fn test_over_utilized_task_set() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        tasks[0] = make_periodic(0, 50, 100); // 50%
+        tasks[1] = make_periodic(1, 40, 100); // 40%
+        tasks[2] = make_periodic(2, 30, 100); // 30%
+
+        assert_eq!(total_utilization(&tasks, 3), 120);
+        assert!(!is_rm_schedulable(&tasks, 3));
+    }
+
+    #[test]
+    fn test_mandatory_load_checked_alone() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        tasks[0] = make_periodic(0, 50, 100); // 50%, anchor
+        tasks[1] = make_periodic(1, 40, 100); // 40%
+        tasks[2] = make_periodic(2, 30, 100); // 30%
+        tasks[0].config.anchor = true;
+
+        // Overloaded as a whole, but the anchor alone fits
+        assert!(!is_rm_schedulable(&tasks, 3));
+        assert!(is_mandatory_schedulable(&tasks, 3));
+
+        // Two anchors at 90% exceed the two-task bound
+        tasks[1].config.anchor = true;
+        assert!(!is_mandatory_schedulable(&tasks, 3));
+    }
+
+    #[test]
+    fn test_best_effort_tasks_ignored() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        tasks[0] = make_periodic(0, 0, 0);
+        tasks[1] = make_periodic(1, 10, 0);
+
+        assert_eq!(total_utilization(&tasks, 2), 0);
+        assert!(is_rm_schedulable(&tasks, 2));
+    }
+
+    #[test]
+    fn test_validate_clean_task_set() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        tasks[0] = make_periodic(0, 10, 50);  // 20%
+        tasks[1] = make_periodic(1, 20, 100); // 20%
+        tasks[2] = make_periodic(2, 0, 0);    // best-effort
+        tasks[0].config.priority = 3;
+
+        let report = validate_schedulability(&tasks, 3);
+        assert_eq!(report.utilization, 40);
+        assert!(report.rm_schedulable);
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn test_validate_flags_each_problem() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        tasks[0] = make_periodic(0, 10, 50);
+        tasks[1] = make_periodic(1, 20, 100);
+        tasks[0].config.priority = 3;
+
+        // WCET longer than the deadline
+        tasks[1].config.wcet_ticks = 120;
+        let report = validate_schedulability(&tasks, 2);
+        assert_eq!(report.wcet_over_deadline, 1 << 1);
+        assert!(!report.rm_schedulable);
+        assert!(!report.is_ok());
+        tasks[1].config.wcet_ticks = 20;
+
+        // Allowed on no core
+        tasks[1].config.affinity_mask = 0;
+        let report = validate_schedulability(&tasks, 2);
+        assert_eq!(report.zero_affinity, 1 << 1);
+        assert!(!report.is_ok());
+        tasks[1].config.affinity_mask = 0x01;
+
+        // Shorter deadline at lower priority
+        tasks[1].config.priority = 5;
+        let report = validate_schedulability(&tasks, 2);
+        assert_eq!(report.priority_conflicts, (1 << 0) | (1 << 1));
+        assert!(report.rm_schedulable);
+        assert!(!report.is_ok());
+        tasks[1].config.priority = 1;
+
+        // Over the Liu–Layland bound
+        tasks[2] = make_periodic(2, 60, 100);
+        let report = validate_schedulability(&tasks, 3);
+        assert_eq!(report.utilization, 100);
+        assert!(!report.rm_schedulable);
+        assert_eq!(report.wcet_over_deadline | report.zero_affinity, 0);
+        assert!(!report.is_ok());
+    }
+}
@@ -0,0 +1,239 @@
+//! # Cortex-M0(+) Port Layer
+//!
+//! Port for ARMv6-M cores (Cortex-M0, M0+ and M1), selected by the
+//! `cortex-m0` feature. Build for `thumbv6m-none-eabi`.
+//!
+//! ## Context Switch Mechanism
+//!
+//! The split-stack model and exception frame are the same as on the
+//! Cortex-M4, but ARMv6-M has only a subset of Thumb-2: multi-register
+//! loads and stores reach R0–R7 only, and there are no IT blocks or
+//! `tst` with an immediate. PendSV therefore stores R4–R7 directly and
+//! moves R8–R11 through R4–R7 to save them above, and the SVCall handler
+//! tests EXC_RETURN through a register.
+//!
+//! The saved context keeps the Cortex-M4 layout, R4 lowest and R11 just
+//! below the hardware frame, so task stacks are built by the shared
+//! [`cortex_m4::init_task_frame`].
+//!
+//! ## Shared With the Cortex-M4 Port
+//!
+//! SysTick configuration, the ICSR and SHPR3 accesses, supervisor call
+//! decoding, the tick handler and the scheduler entry points called from
+//! PendSV are identical and live in [`cortex_m4`]; only their
+//! Thumb-2-only handlers are left out of `cortex-m0` builds.
+//!
+//! ARMv6-M has neither an FPU nor a DWT cycle counter, so `cortex-m0`
+//! excludes the `fpu` and `cycle-accounting` features.
+
+#[cfg(target_arch = "arm")]
+use core::arch::{asm, naked_asm};
+
+use super::cortex_m4;
+#[cfg(target_arch = "arm")]
+use super::cortex_m4::SW_FRAME_WORDS;
+use super::Arch;
+use crate::error::KernelError;
+use crate::syscall::Syscall;
+
+// ---------------------------------------------------------------------------
+// Arch implementation
+// ---------------------------------------------------------------------------
+
+/// The Cortex-M0(+) port.
+pub struct CortexM0;
+
+impl Arch for CortexM0 {
+    const INITIAL_FRAME_WORDS: usize = cortex_m4::INITIAL_FRAME_WORDS;
+
+    unsafe fn init_task_stack(stack_top: *mut u32, entry: usize, exit: usize) -> *mut u32 {
+        cortex_m4::init_task_frame(stack_top, entry, exit)
+    }
+
+    fn trigger_context_switch() {
+        cortex_m4::trigger_pendsv();
+    }
+
+    unsafe fn start_first_task(sp: *const u32) -> ! {
+        start_first_task(sp)
+    }
+
+    fn configure_tick(hz: u32) -> Result<(), KernelError> {
+        cortex_m4::reconfigure_systick(hz)
+    }
+
+    fn set_tick_enabled(enabled: bool) {
+        cortex_m4::set_systick_enabled(enabled);
+    }
+
+    fn set_priorities() {
+        cortex_m4::set_interrupt_priorities();
+    }
+
+    fn syscall(call: Syscall, arg: u32) {
+        cortex_m4::svc(call, arg);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// First task launch
+// ---------------------------------------------------------------------------
+
+/// Start the first task by switching to PSP and branching to Thread mode.
+///
+/// As on the Cortex-M4, but the hardware frame is popped into low
+/// registers only: ARMv6-M cannot `pop` into R12.
+///
+/// # Safety
+/// Must only be called once, with a valid stack pointer.
+#[cfg(target_arch = "arm")]
+pub unsafe fn start_first_task(psp: *const u32) -> ! {
+    // Skip the software-saved context (R4-R11)
+    let psp = psp.add(SW_FRAME_WORDS);
+    asm!(
+        "msr psp, r0",         // Set process stack pointer
+
+        // Switch to PSP for Thread mode (set CONTROL.SPSEL = 1)
+        "movs r0, #2",
+        "msr control, r0",
+        "isb",
+
+        // Pop the hardware frame manually since we're not really returning from an exception
+        "pop {{r0-r5}}",       // R0-R3, R12 (into r4), LR (into r5, discarded)
+        "pop {{r6, r7}}",      // PC (task entry point), xPSR (discarded)
+
+        // Branch to the task
+        "cpsie i",             // Enable interrupts
+        "bx r6",               // Jump to task entry
+
+        in("r0") psp,
+        options(noreturn)
+    );
+}
+
+/// Off-target builds have no Thread mode to enter.
+///
+/// # Safety
+/// Never returns.
+#[cfg(not(target_arch = "arm"))]
+pub unsafe fn start_first_task(_psp: *const u32) -> ! {
+    unreachable!("start_first_task() requires a Cortex-M target");
+}
+
+// ---------------------------------------------------------------------------
+// PendSV handler (context switch)
+// ---------------------------------------------------------------------------
+
+/// PendSV exception handler — performs the actual context switch.
+///
+/// ## Sequence
+/// 1. Reserve the software context on the current task's stack (PSP)
+/// 2. Store R4–R7, then R8–R11 copied through R4–R7 above them
+/// 3. Store the updated PSP into the current task's TCB
+/// 4. Call the scheduler to select the next task
+/// 5. Restore R8–R11 through R4–R7, then R4–R7 themselves
+/// 6. Return from exception (hardware restores R0–R3, R12, LR, PC, xPSR)
+///
+/// # Safety
+/// This is a naked function called directly by the NVIC. It must follow
+/// the exact ARMv6-M exception entry/exit convention.
+#[cfg(target_arch = "arm")]
+#[no_mangle]
+#[unsafe(naked)]
+pub unsafe extern "C" fn PendSV() {
+    naked_asm!(
+        // --- Save current context ---
+        "mrs r0, psp",             // Get current PSP
+        "subs r0, #32",            // Room for R4-R11
+        "stmia r0!, {{r4-r7}}",    // R4-R7 at the bottom
+        "mov r4, r8",
+        "mov r5, r9",
+        "mov r6, r10",
+        "mov r7, r11",
+        "stmia r0!, {{r4-r7}}",    // R8-R11 above them
+        "subs r0, #32",            // Back to the bottom of the saved context
+
+        // Store updated PSP into current TCB
+        "bl {save_context}",       // save_context(r0: *mut u32)
+
+        // --- Select next task ---
+        "bl {do_schedule}",        // Returns new PSP in r0
+
+        // --- Restore new context ---
+        "adds r0, #16",            // R8-R11 first
+        "ldmia r0!, {{r4-r7}}",
+        "mov r8, r4",
+        "mov r9, r5",
+        "mov r10, r6",
+        "mov r11, r7",
+        "msr psp, r0",             // PSP now just above the saved context
+        "subs r0, #32",
+        "ldmia r0!, {{r4-r7}}",    // R4-R7
+
+        // Return from exception using PSP (EXC_RETURN = 0xFFFFFFFD)
+        "ldr r0, =0xFFFFFFFD",
+        "bx r0",
+
+        save_context = sym cortex_m4::save_current_context,
+        do_schedule = sym cortex_m4::do_context_switch,
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Supervisor calls
+// ---------------------------------------------------------------------------
+
+/// SVCall exception handler — kernel entry for `svc #n`.
+///
+/// Passes the caller's exception frame (MSP if EXC_RETURN bit 2 is
+/// clear, PSP otherwise) to the shared `svc_dispatch`. EXC_RETURN is
+/// kept on the handler stack across the call and popped into PC to
+/// return to the caller.
+///
+/// # Safety
+/// Called directly by the NVIC on `svc`.
+#[cfg(target_arch = "arm")]
+#[no_mangle]
+#[unsafe(naked)]
+pub unsafe extern "C" fn SVCall() {
+    naked_asm!(
+        "movs r0, #4",
+        "mov r1, lr",
+        "tst r0, r1",
+        "beq 2f",
+        "mrs r0, psp",
+        "b 3f",
+        "2:",
+        "mrs r0, msp",
+        "3:",
+        "push {{lr}}",
+        "bl {dispatch}",
+        "pop {{pc}}",
+
+        dispatch = sym cortex_m4::svc_dispatch,
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_frame_matches_cortex_m4_layout() {
+        let mut stack = [0xAAAA_AAAAu32; 32];
+        let top = unsafe { stack.as_mut_ptr().add(stack.len()) };
+        let sp = unsafe { CortexM0::init_task_stack(top, 0x0800_0101, 0x0800_0201) };
+
+        assert_eq!(sp, unsafe { top.sub(16) });
+        let frame = &stack[16..];
+        // R4-R11, then R0-R3 and R12 all start zeroed
+        assert!(frame[..13].iter().all(|&word| word == 0));
+        assert_eq!(frame[13], 0x0800_0201); // LR
+        assert_eq!(frame[14], 0x0800_0101); // PC
+        assert_eq!(frame[15], 0x0100_0000); // xPSR
+    }
+}
@@ -14,6 +14,25 @@
 //! and xPSR onto the process stack. The PendSV handler manually saves and
 //! restores R4–R11, which completes the full context save/restore.
 //!
+//! ## Floating-Point Context (`fpu` feature)
+//!
+//! On a Cortex-M4F the hardware frame of a task that has used the FPU is
+//! extended with S0–S15 and FPSCR (lazily, per FPCCR), but S16–S31 are
+//! callee-saved and left to the OS. With the `fpu` feature PendSV also
+//! saves the task's EXC_RETURN, and when its bit 4 is clear (extended
+//! frame) pushes S16–S31 below R4–R11. Tasks that never touch the FPU keep
+//! the basic frame and pay nothing. Requires a hard-float target
+//! (`thumbv7em-none-eabihf`).
+//!
+//! ## Supervisor Calls
+//!
+//! Kernel entry from a task goes through `svc #n` (see
+//! [`crate::syscall`]). The `SVCall` handler finds the caller's exception
+//! frame on MSP or PSP from EXC_RETURN, reads the SVC immediate from the
+//! instruction just before the stacked PC, and dispatches with the stacked
+//! R0 as argument. SVC must not be issued with interrupts masked or from
+//! an ISR at or above SVCall priority: both escalate to HardFault.
+//!
 //! ## Interrupt Priorities
 //!
 //! - SysTick: Priority 0xFF (lowest) — can be preempted
@@ -23,10 +42,28 @@
 //! preempt other interrupt handlers, maintaining real-time guarantees.
 
 use cortex_m::peripheral::syst::SystClkSource;
-use cortex_m::register;
+#[cfg(target_arch = "arm")]
 use core::arch::asm;
+#[cfg(all(target_arch = "arm", not(feature = "cortex-m0")))]
+use core::arch::naked_asm;
 
+#[cfg(not(feature = "cortex-m0"))]
+use super::Arch;
 use crate::config::{SYSTEM_CLOCK_HZ, TICK_HZ};
+use crate::error::KernelError;
+use crate::syscall::Syscall;
+
+/// Words of context PendSV saves in software below the hardware frame:
+/// R4–R11, plus the task's EXC_RETURN with the `fpu` feature.
+#[cfg(not(feature = "fpu"))]
+pub const SW_FRAME_WORDS: usize = 8;
+/// Words of context PendSV saves in software below the hardware frame:
+/// R4–R11, plus the task's EXC_RETURN with the `fpu` feature.
+#[cfg(feature = "fpu")]
+pub const SW_FRAME_WORDS: usize = 9;
+
+/// EXC_RETURN value of a fresh task: Thread mode, PSP, basic frame.
+pub const EXC_RETURN_THREAD_PSP: u32 = 0xFFFF_FFFD;
 
 // ---------------------------------------------------------------------------
 // SysTick configuration
@@ -40,7 +77,40 @@ use crate::config::{SYSTEM_CLOCK_HZ, TICK_HZ};
 /// # Parameters
 /// - `syst`: Mutable reference to the SysTick peripheral
 pub fn configure_systick(syst: &mut cortex_m::peripheral::SYST) {
-    let reload = SYSTEM_CLOCK_HZ / TICK_HZ - 1;
+    configure_systick_hz(syst, TICK_HZ);
+}
+
+/// Start or stop the SysTick counter without touching its configuration.
+///
+/// Stopping it freezes the current count, so re-enabling resumes the
+/// interrupted period rather than starting a new one.
+pub fn set_systick_enabled(enabled: bool) {
+    // SYST_CSR address: 0xE000_E010, ENABLE = bit 0
+    const SYST_CSR: *mut u32 = 0xE000_E010 as *mut u32;
+    unsafe {
+        let csr = core::ptr::read_volatile(SYST_CSR);
+        let csr = if enabled { csr | 1 } else { csr & !1 };
+        core::ptr::write_volatile(SYST_CSR, csr);
+    }
+}
+
+/// Enable the DWT cycle counter used for `cycle-accounting`.
+#[cfg(feature = "cycle-accounting")]
+pub fn enable_cycle_counter() {
+    // DEMCR address: 0xE000_EDFC, TRCENA = bit 24
+    // DWT_CTRL address: 0xE000_1000, CYCCNTENA = bit 0
+    const DEMCR: *mut u32 = 0xE000_EDFC as *mut u32;
+    const DWT_CTRL: *mut u32 = 0xE000_1000 as *mut u32;
+    unsafe {
+        core::ptr::write_volatile(DEMCR, core::ptr::read_volatile(DEMCR) | (1 << 24));
+        core::ptr::write_volatile(DWT_CTRL, core::ptr::read_volatile(DWT_CTRL) | 1);
+    }
+}
+
+/// Configure SysTick to interrupt at `hz` instead of the compile-time
+/// `TICK_HZ`.
+pub fn configure_systick_hz(syst: &mut cortex_m::peripheral::SYST, hz: u32) {
+    let reload = SYSTEM_CLOCK_HZ / hz - 1;
     syst.set_reload(reload);
     syst.clear_current();
     syst.set_clock_source(SystClkSource::Core);
@@ -48,6 +118,52 @@ pub fn configure_systick(syst: &mut cortex_m::peripheral::SYST) {
     syst.enable_interrupt();
 }
 
+/// SysTick reload value for a tick rate of `hz`, or `None` if the period
+/// does not fit SysTick's 24-bit counter at `SYSTEM_CLOCK_HZ` (or is
+/// shorter than two clock cycles).
+pub const fn systick_reload(hz: u32) -> Option<u32> {
+    if hz == 0 {
+        return None;
+    }
+    let cycles = SYSTEM_CLOCK_HZ / hz;
+    if cycles < 2 || cycles - 1 > 0x00FF_FFFF {
+        return None;
+    }
+    Some(cycles - 1)
+}
+
+/// Switch a running SysTick to `hz`. The new period starts immediately;
+/// the enable and interrupt bits are left as they are.
+///
+/// # Returns
+/// `Err(KernelError::InvalidConfig)` if the reload for `hz` does not fit
+/// in 24 bits (see [`systick_reload`]); SysTick is then left untouched.
+pub fn reconfigure_systick(hz: u32) -> Result<(), KernelError> {
+    // SYST_RVR: 0xE000_E014, SYST_CVR: 0xE000_E018 (any write clears it)
+    const SYST_RVR: *mut u32 = 0xE000_E014 as *mut u32;
+    const SYST_CVR: *mut u32 = 0xE000_E018 as *mut u32;
+    let reload = systick_reload(hz).ok_or(KernelError::InvalidConfig)?;
+    unsafe {
+        core::ptr::write_volatile(SYST_RVR, reload);
+        core::ptr::write_volatile(SYST_CVR, 0);
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Core identification
+// ---------------------------------------------------------------------------
+
+/// Index of the core executing this code, for `Scheduler::enter_core()`.
+///
+/// The Cortex-M4 port is single-core, so this is always 0. A port for a
+/// multi-core part reads the core number from its CPUID or equivalent
+/// register here.
+#[inline]
+pub fn core_id() -> usize {
+    0
+}
+
 // ---------------------------------------------------------------------------
 // PendSV trigger
 // ---------------------------------------------------------------------------
@@ -101,10 +217,11 @@ pub fn set_interrupt_priorities() {
 ///
 /// # Safety
 /// Must only be called once, with a valid stack pointer.
-pub unsafe fn start_first_task(psp: *const u32) {
+#[cfg(all(target_arch = "arm", not(feature = "cortex-m0")))]
+pub unsafe fn start_first_task(psp: *const u32) -> ! {
+    // Skip the software-saved context (R4-R11, and EXC_RETURN with `fpu`)
+    let psp = psp.add(SW_FRAME_WORDS);
     asm!(
-        // Set PSP to the task's stack pointer (skip SW-saved R4-R11)
-        "adds r0, #32",        // Skip 8 SW registers (8×4 = 32 bytes)
         "msr psp, r0",         // Set process stack pointer
 
         // Switch to PSP for Thread mode (set CONTROL.SPSEL = 1)
@@ -127,6 +244,122 @@ pub unsafe fn start_first_task(psp: *const u32) {
     );
 }
 
+/// Off-target builds (the host simulator) have no Thread mode to enter;
+/// tasks are driven by `sim::Simulator` instead.
+///
+/// # Safety
+/// Never returns.
+#[cfg(not(target_arch = "arm"))]
+pub unsafe fn start_first_task(_psp: *const u32) -> ! {
+    unreachable!("start_first_task() requires a Cortex-M target");
+}
+
+// ---------------------------------------------------------------------------
+// Initial stack frame
+// ---------------------------------------------------------------------------
+
+/// Words of a fresh task's initial context: the hardware frame plus the
+/// software-saved context.
+pub const INITIAL_FRAME_WORDS: usize = 8 + SW_FRAME_WORDS;
+
+/// Pre-populate the frame the first PendSV "returns" through, below
+/// `stack_top`, and return the task's initial stack pointer. Shared with
+/// the Cortex-M0 port, whose layout is identical.
+///
+/// ## Stack Layout (top = high address, growing down)
+///
+/// ```text
+/// [Hardware stacked frame]   <- ends at stack_top
+///   xPSR  (Thumb bit set)
+///   PC    (entry)
+///   LR    (exit)
+///   R12   (0)
+///   R3    (0)
+///   R2    (0)
+///   R1    (0)
+///   R0    (0)
+/// [Software saved context]
+///   EXC_RETURN (basic frame; `fpu` feature only)
+///   R11   (0)
+///   R10   (0)
+///   R9    (0)
+///   R8    (0)
+///   R7    (0)
+///   R6    (0)
+///   R5    (0)
+///   R4    (0)              <- returned stack pointer
+/// ```
+///
+/// # Safety
+/// As [`Arch::init_task_stack`].
+pub unsafe fn init_task_frame(stack_top: *mut u32, entry: usize, exit: usize) -> *mut u32 {
+    let frame_ptr = stack_top.sub(INITIAL_FRAME_WORDS);
+
+    // Software-saved registers (R4–R11) — bottom of frame
+    for i in 0..8 {
+        *frame_ptr.add(i) = 0; // R4, R5, R6, R7, R8, R9, R10, R11
+    }
+    // A new task has no FPU state: resume it with a basic frame
+    #[cfg(feature = "fpu")]
+    {
+        *frame_ptr.add(8) = EXC_RETURN_THREAD_PSP;
+    }
+
+    // Hardware-stacked frame (R0–R3, R12, LR, PC, xPSR)
+    let hw = frame_ptr.add(SW_FRAME_WORDS);
+    *hw.add(0) = 0; // R0
+    *hw.add(1) = 0; // R1
+    *hw.add(2) = 0; // R2
+    *hw.add(3) = 0; // R3
+    *hw.add(4) = 0; // R12
+    *hw.add(5) = exit as u32;  // LR — return address if task returns
+    *hw.add(6) = entry as u32; // PC — task entry point
+    *hw.add(7) = 0x0100_0000;  // xPSR — Thumb bit set
+
+    frame_ptr
+}
+
+// ---------------------------------------------------------------------------
+// Arch implementation
+// ---------------------------------------------------------------------------
+
+/// The Cortex-M4 port.
+#[cfg(not(feature = "cortex-m0"))]
+pub struct CortexM4;
+
+#[cfg(not(feature = "cortex-m0"))]
+impl Arch for CortexM4 {
+    const INITIAL_FRAME_WORDS: usize = INITIAL_FRAME_WORDS;
+
+    unsafe fn init_task_stack(stack_top: *mut u32, entry: usize, exit: usize) -> *mut u32 {
+        init_task_frame(stack_top, entry, exit)
+    }
+
+    fn trigger_context_switch() {
+        trigger_pendsv();
+    }
+
+    unsafe fn start_first_task(sp: *const u32) -> ! {
+        start_first_task(sp)
+    }
+
+    fn configure_tick(hz: u32) -> Result<(), KernelError> {
+        reconfigure_systick(hz)
+    }
+
+    fn set_tick_enabled(enabled: bool) {
+        set_systick_enabled(enabled);
+    }
+
+    fn set_priorities() {
+        set_interrupt_priorities();
+    }
+
+    fn syscall(call: Syscall, arg: u32) {
+        svc(call, arg);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // PendSV handler (context switch)
 // ---------------------------------------------------------------------------
@@ -144,10 +377,11 @@ pub unsafe fn start_first_task(psp: *const u32) {
 /// # Safety
 /// This is a naked function called directly by the NVIC. It must follow
 /// the exact Cortex-M4 exception entry/exit convention.
+#[cfg(all(target_arch = "arm", not(feature = "fpu"), not(feature = "cortex-m0")))]
 #[no_mangle]
-#[naked]
+#[unsafe(naked)]
 pub unsafe extern "C" fn PendSV() {
-    asm!(
+    naked_asm!(
         // --- Save current context ---
         "mrs r0, psp",             // Get current PSP
         "stmdb r0!, {{r4-r11}}",   // Push R4-R11 onto task stack (decrement before store)
@@ -169,8 +403,62 @@ pub unsafe extern "C" fn PendSV() {
 
         save_context = sym save_current_context,
         do_schedule = sym do_context_switch,
-        options(noreturn)
-    );
+    )
+}
+
+/// PendSV exception handler with floating-point context save.
+///
+/// As the integer-only handler, but each task's EXC_RETURN is kept on its
+/// stack above R4–R11, and S16–S31 are saved/restored around them when
+/// EXC_RETURN bit 4 shows the task has an extended (FPU) frame. Lazy
+/// stacking is left to the hardware: the `vstmdb` triggers the deferred
+/// S0–S15 save only if the task actually used the FPU.
+///
+/// # Safety
+/// Same as the integer-only handler.
+#[cfg(all(target_arch = "arm", feature = "fpu"))]
+#[no_mangle]
+#[unsafe(naked)]
+pub unsafe extern "C" fn PendSV() {
+    naked_asm!(
+        // --- Save current context ---
+        "mrs r0, psp",             // Get current PSP
+        "tst lr, #0x10",           // EXC_RETURN bit 4 clear: FPU frame
+        "it eq",
+        "vstmdbeq r0!, {{s16-s31}}", // Push S16-S31 (triggers lazy S0-S15)
+        "stmdb r0!, {{r4-r11, lr}}", // Push R4-R11 and EXC_RETURN
+
+        "bl {save_context}",       // save_context(r0: *mut u32)
+
+        // --- Select next task ---
+        "bl {do_schedule}",        // Returns new PSP in r0
+
+        // --- Restore new context ---
+        "ldmia r0!, {{r4-r11, lr}}", // Pop R4-R11 and the task's EXC_RETURN
+        "tst lr, #0x10",
+        "it eq",
+        "vldmiaeq r0!, {{s16-s31}}", // Pop S16-S31 for an FPU frame
+        "msr psp, r0",             // Set PSP to new task's stack
+
+        // Return with the task's own frame type
+        "bx lr",
+
+        save_context = sym save_current_context,
+        do_schedule = sym do_context_switch,
+    )
+}
+
+/// Keep FPU state stacking automatic and lazy (FPCCR.ASPEN and LSPEN), so
+/// an exception taken by an FPU-using task only reserves frame space for
+/// S0–S15 and saves them if the handler itself uses the FPU.
+#[cfg(feature = "fpu")]
+pub fn enable_lazy_fpu_stacking() {
+    // FPCCR address: 0xE000_EF34, ASPEN = bit 31, LSPEN = bit 30
+    const FPCCR: *mut u32 = 0xE000_EF34 as *mut u32;
+    unsafe {
+        let fpccr = core::ptr::read_volatile(FPCCR);
+        core::ptr::write_volatile(FPCCR, fpccr | (1 << 31) | (1 << 30));
+    }
 }
 
 /// Save the current task's stack pointer. Called from PendSV.
@@ -178,10 +466,12 @@ pub unsafe extern "C" fn PendSV() {
 /// # Safety
 /// Called from assembly context with interrupts disabled.
 #[no_mangle]
-unsafe extern "C" fn save_current_context(psp: *mut u32) {
+pub(super) unsafe extern "C" fn save_current_context(psp: *mut u32) {
     let scheduler = &mut *crate::kernel::SCHEDULER_PTR;
-    let current = scheduler.current_task;
+    scheduler.enter_core(core_id());
+    let current = scheduler.current();
     if current < scheduler.task_count {
+        scheduler.enforce_stack(current, psp);
         scheduler.tasks[current].stack_pointer = psp;
     }
 }
@@ -192,10 +482,93 @@ unsafe extern "C" fn save_current_context(psp: *mut u32) {
 /// # Safety
 /// Called from assembly context.
 #[no_mangle]
-unsafe extern "C" fn do_context_switch() -> *mut u32 {
+pub(super) unsafe extern "C" fn do_context_switch() -> *mut u32 {
     let scheduler = &mut *crate::kernel::SCHEDULER_PTR;
-    let next = scheduler.schedule();
-    scheduler.tasks[next].stack_pointer
+    let next = scheduler.context_switch(core_id());
+
+    #[cfg(feature = "cycle-accounting")]
+    scheduler.account_cycles(cortex_m::peripheral::DWT::cycle_count());
+
+    let psp = scheduler.tasks[next].stack_pointer;
+    scheduler.enforce_stack(next, psp);
+
+    #[cfg(feature = "mpu")]
+    super::mpu::configure_task(&scheduler.tasks[next]);
+
+    psp
+}
+
+// ---------------------------------------------------------------------------
+// Supervisor calls
+// ---------------------------------------------------------------------------
+
+/// Enter the kernel with `svc #n` for `call`, passing `arg` in R0.
+///
+/// Returns once the call has been performed and, if it switched tasks,
+/// once the caller has been scheduled again. Host builds, which have no
+/// SVC, dispatch directly inside a critical section instead.
+#[inline]
+pub fn svc(call: Syscall, arg: u32) {
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        match call {
+            Syscall::Yield => asm!("svc #0", in("r0") arg),
+            Syscall::Delay => asm!("svc #1", in("r0") arg),
+            Syscall::YieldTo => asm!("svc #2", in("r0") arg),
+        }
+    }
+    #[cfg(not(target_arch = "arm"))]
+    {
+        let switch = crate::sync::critical_section(|_cs| unsafe {
+            crate::syscall::dispatch(&mut *crate::kernel::SCHEDULER_PTR, call.number(), arg)
+        });
+        if switch {
+            trigger_pendsv();
+        }
+    }
+}
+
+/// SVCall exception handler — kernel entry for `svc #n`.
+///
+/// Passes the caller's exception frame (MSP if EXC_RETURN bit 2 is
+/// clear, PSP otherwise) to `svc_dispatch`, which returns straight to
+/// the caller through the untouched EXC_RETURN in LR.
+///
+/// # Safety
+/// Called directly by the NVIC on `svc`.
+#[cfg(all(target_arch = "arm", not(feature = "cortex-m0")))]
+#[no_mangle]
+#[unsafe(naked)]
+pub unsafe extern "C" fn SVCall() {
+    naked_asm!(
+        "tst lr, #4",
+        "ite eq",
+        "mrseq r0, msp",
+        "mrsne r0, psp",
+        "b {dispatch}",
+
+        dispatch = sym svc_dispatch,
+    )
+}
+
+/// Decode and run the supervisor call whose exception frame is at `frame`.
+/// Called from SVCall.
+///
+/// # Safety
+/// `frame` must be the hardware-stacked frame of an `svc` instruction.
+#[cfg(target_arch = "arm")]
+#[no_mangle]
+pub(super) unsafe extern "C" fn svc_dispatch(frame: *const u32) {
+    // Stacked PC points past the 16-bit `svc #imm`; imm is its low byte
+    let pc = *frame.add(6) as *const u8;
+    let number = *pc.sub(2);
+    let arg = *frame;
+
+    let scheduler = &mut *crate::kernel::SCHEDULER_PTR;
+    scheduler.enter_core(core_id());
+    if crate::syscall::dispatch(scheduler, number, arg) {
+        trigger_pendsv();
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -206,12 +579,33 @@ unsafe extern "C" fn do_context_switch() -> *mut u32 {
 ///
 /// Called at `TICK_HZ` frequency. Updates scheduler state and triggers
 /// PendSV if a context switch is needed.
+///
+/// # Safety
+/// Called directly by the NVIC, after `kernel::init()`.
 #[no_mangle]
 pub unsafe extern "C" fn SysTick() {
+    scheduler_tick();
+}
+
+/// Advance the scheduler by one tick and pend a context switch if needed.
+///
+/// Shared by the SysTick handler and by alternative tick sources (see
+/// [`super::tick`]) whose interrupt handlers drive the scheduler instead.
+///
+/// # Safety
+/// Must be called from the tick interrupt, after `kernel::init()`.
+pub unsafe fn scheduler_tick() {
+    #[cfg(feature = "stall-watchdog")]
+    crate::watchdog::beat();
+
     let scheduler = &mut *crate::kernel::SCHEDULER_PTR;
+    scheduler.enter_core(core_id());
     scheduler.tick();
+    crate::kernel::publish_ticks(scheduler.tick_count);
+    crate::kernel::rearm_deadline_timer();
 
-    if scheduler.needs_reschedule {
+    // Deferred while preemption is locked; `preempt_enable()` pends it
+    if scheduler.needs_reschedule && !scheduler.preemption_locked() {
         trigger_pendsv();
     }
 }
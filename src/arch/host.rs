@@ -0,0 +1,49 @@
+//! # Host Port
+//!
+//! A no-op [`Arch`] for building the whole kernel off-target, selected by
+//! the `sim` feature. Nothing here touches hardware: task stacks get no
+//! exception frame, context switches are left to whoever drives the
+//! scheduler (see `sim::Simulator`), and there is no tick timer.
+
+use super::Arch;
+use crate::error::KernelError;
+use crate::syscall::Syscall;
+
+/// The host port.
+pub struct Host;
+
+impl Arch for Host {
+    /// Same room as on the target, so stack sizing and usage match.
+    const INITIAL_FRAME_WORDS: usize = super::cortex_m4::INITIAL_FRAME_WORDS;
+
+    /// Simulated tasks are never resumed from their stacks, so nothing is
+    /// written.
+    unsafe fn init_task_stack(stack_top: *mut u32, _entry: usize, _exit: usize) -> *mut u32 {
+        stack_top.sub(Self::INITIAL_FRAME_WORDS)
+    }
+
+    fn trigger_context_switch() {}
+
+    unsafe fn start_first_task(_sp: *const u32) -> ! {
+        unreachable!("the host port has no Thread mode; drive tasks with sim::Simulator");
+    }
+
+    fn configure_tick(hz: u32) -> Result<(), KernelError> {
+        if hz == 0 {
+            return Err(KernelError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    fn set_tick_enabled(_enabled: bool) {}
+
+    fn set_priorities() {}
+
+    /// Dispatched directly inside a critical section; any switch it calls
+    /// for is left pending in `Scheduler::needs_reschedule`.
+    fn syscall(call: Syscall, arg: u32) {
+        crate::sync::critical_section(|_cs| unsafe {
+            crate::syscall::dispatch(&mut *crate::kernel::SCHEDULER_PTR, call.number(), arg)
+        });
+    }
+}
@@ -1,7 +1,92 @@
 //! # Architecture Abstraction Layer
 //!
-//! Provides a hardware abstraction boundary for the scheduler.
-//! Currently implements the Cortex-M4 port; extensible to other
-//! architectures by adding sibling modules.
+//! Provides a hardware abstraction boundary for the scheduler. The kernel
+//! and scheduler reach the processor only through the [`Arch`] trait, and
+//! [`Port`] names the implementation selected at build time:
+//!
+//! | Feature     | Port                        |
+//! |-------------|-----------------------------|
+//! | *(default)* | `cortex_m4::CortexM4`       |
+//! | `cortex-m0` | `cortex_m0::CortexM0`       |
+//! | `sim`       | `host::Host` (no hardware)  |
+//!
+//! `sim` takes precedence, so the simulator builds with any port feature.
+//! A port also supplies the exception handlers the hardware enters
+//! directly (context switch, supervisor call, tick); those are not part
+//! of the trait. Other architectures are added as sibling modules.
+
+use crate::error::KernelError;
+use crate::syscall::Syscall;
 
 pub mod cortex_m4;
+pub mod tick;
+
+#[cfg(feature = "cortex-m0")]
+pub mod cortex_m0;
+
+#[cfg(feature = "sim")]
+pub mod host;
+
+#[cfg(feature = "mpu")]
+pub mod mpu;
+
+#[cfg(all(feature = "cortex-m0", any(feature = "fpu", feature = "cycle-accounting")))]
+compile_error!("ARMv6-M has no FPU or DWT cycle counter: `cortex-m0` excludes `fpu` and `cycle-accounting`");
+
+/// The port the kernel is built for.
+#[cfg(not(any(feature = "sim", feature = "cortex-m0")))]
+pub type Port = cortex_m4::CortexM4;
+
+/// The port the kernel is built for.
+#[cfg(all(feature = "cortex-m0", not(feature = "sim")))]
+pub type Port = cortex_m0::CortexM0;
+
+/// The port the kernel is built for.
+#[cfg(feature = "sim")]
+pub type Port = host::Host;
+
+/// What the kernel needs from a processor port.
+pub trait Arch {
+    /// Words a fresh task's initial context occupies below its stack top.
+    const INITIAL_FRAME_WORDS: usize;
+
+    /// Build a fresh task's initial context below `stack_top` so that the
+    /// first switch to it starts executing at `entry`, returning into
+    /// `exit` should the entry ever return.
+    ///
+    /// # Returns
+    /// The task's initial saved stack pointer.
+    ///
+    /// # Safety
+    /// `stack_top` must be 8-byte aligned, with `INITIAL_FRAME_WORDS`
+    /// writable words below it.
+    unsafe fn init_task_stack(stack_top: *mut u32, entry: usize, exit: usize) -> *mut u32;
+
+    /// Pend a context switch, taken once no other handler is active.
+    fn trigger_context_switch();
+
+    /// Launch the first task from its saved stack pointer `sp`.
+    ///
+    /// # Safety
+    /// Must only be called once, from `kernel::start()`, with a stack
+    /// pointer built by [`Arch::init_task_stack`].
+    unsafe fn start_first_task(sp: *const u32) -> !;
+
+    /// Switch the running tick timer to `hz`. The new period starts
+    /// immediately; whether the timer is enabled is left as it is.
+    ///
+    /// # Returns
+    /// `Err(KernelError::InvalidConfig)`, touching nothing, if the timer
+    /// cannot run at `hz`.
+    fn configure_tick(hz: u32) -> Result<(), KernelError>;
+
+    /// Start or stop the tick timer without touching its configuration.
+    fn set_tick_enabled(enabled: bool);
+
+    /// Give the context-switch and tick interrupts their priorities, below
+    /// every application interrupt.
+    fn set_priorities();
+
+    /// Enter the kernel from a task to perform `call` with `arg`.
+    fn syscall(call: Syscall, arg: u32);
+}
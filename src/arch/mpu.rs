@@ -0,0 +1,253 @@
+//! # Memory Protection Unit (ARMv7-M)
+//!
+//! Optional per-task memory isolation, enabled with the `mpu` feature.
+//! On every context switch the incoming task's stack is programmed into a
+//! dedicated MPU region, so a stray write from one task into another
+//! task's stack or TCB raises a MemManage fault instead of silently
+//! corrupting it.
+//!
+//! ## Region Layout
+//!
+//! | Region | Use |
+//! |--------|-----|
+//! | 0–5    | Application shared regions (`set_shared_region`) |
+//! | 6      | Reserved |
+//! | 7      | Running task's stack (reprogrammed per switch) |
+//!
+//! Higher-numbered regions take precedence, so the stack grant overrides
+//! any broader shared region it overlaps.
+//!
+//! ## Stack Alignment
+//!
+//! ARMv7-M regions are power-of-two sized and size-aligned, while task
+//! stacks live inline in the TCB at arbitrary 8-byte alignment. Each
+//! region is split into 8 subregions, and only subregions lying entirely
+//! inside the stack are enabled. Access is therefore never granted beyond
+//! the stack, at the cost of possibly leaving its edges uncovered. Aligning
+//! stacks to their size gives exact coverage.
+//!
+//! ## Privilege
+//!
+//! The background map (`PRIVDEFENA`) stays enabled for privileged code so
+//! the kernel and ISRs keep full access. Tasks currently start privileged;
+//! the stack regions only constrain them once they run unprivileged.
+
+use crate::task::TaskControlBlock;
+
+/// MPU Control Register.
+const MPU_CTRL: *mut u32 = 0xE000_ED94 as *mut u32;
+/// MPU Region Base Address Register.
+const MPU_RBAR: *mut u32 = 0xE000_ED9C as *mut u32;
+/// MPU Region Attribute and Size Register.
+const MPU_RASR: *mut u32 = 0xE000_EDA0 as *mut u32;
+/// System Handler Control and State Register (MEMFAULTENA = bit 16).
+const SHCSR: *mut u32 = 0xE000_ED24 as *mut u32;
+
+const CTRL_ENABLE: u32 = 1 << 0;
+const CTRL_PRIVDEFENA: u32 = 1 << 2;
+
+const RBAR_VALID: u32 = 1 << 4;
+
+const RASR_ENABLE: u32 = 1 << 0;
+const RASR_XN: u32 = 1 << 28;
+/// AP = 0b011: full read/write access, privileged and unprivileged.
+const RASR_AP_FULL: u32 = 0b011 << 24;
+/// Normal, shareable, write-through memory (TEX=000, S=1, C=1, B=0).
+const RASR_SRAM: u32 = (1 << 18) | (1 << 17);
+
+/// Region number holding the running task's stack.
+pub const STACK_REGION: u32 = 7;
+
+/// Number of regions available for application shared memory.
+pub const SHARED_REGIONS: u32 = 6;
+
+/// Smallest region size that supports subregions.
+const MIN_SUBREGION_SIZE: usize = 256;
+
+/// A programmed MPU region: the raw `RBAR`/`RASR` register values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionDescriptor {
+    /// Region base address, `VALID` bit and region number.
+    pub rbar: u32,
+    /// Attributes, access permissions, subregion disables and size.
+    pub rasr: u32,
+}
+
+impl RegionDescriptor {
+    /// Region size in bytes, decoded from `RASR.SIZE`.
+    pub fn size(&self) -> usize {
+        1usize << (((self.rasr >> 1) & 0x1F) + 1)
+    }
+
+    /// Region base address, decoded from `RBAR.ADDR`.
+    pub fn base(&self) -> usize {
+        (self.rbar & !(self.size() as u32 - 1)) as usize
+    }
+
+    /// Whether subregion `i` (0–7) is enabled.
+    pub fn subregion_enabled(&self, i: u32) -> bool {
+        (self.rasr >> 8) & (1 << i) == 0
+    }
+}
+
+/// Compute the region descriptor granting a task read/write access to its
+/// stack `[base, base + size)`.
+///
+/// Tries every power-of-two region size from 256 bytes up to four times the
+/// stack, aligned down from `base`, and enables only the subregions wholly
+/// inside the stack. The candidate covering the most bytes wins (smallest
+/// region on ties). Returns `None` if no subregion fits inside the stack.
+pub fn stack_region(base: usize, size: usize, region: u32) -> Option<RegionDescriptor> {
+    let end = base + size;
+    let limit = (size.next_power_of_two() * 4).max(MIN_SUBREGION_SIZE);
+
+    let mut best: Option<(usize, RegionDescriptor)> = None;
+    let mut region_size = MIN_SUBREGION_SIZE;
+    while region_size <= limit {
+        let region_base = base & !(region_size - 1);
+        let sub_size = region_size / 8;
+
+        let mut disabled: u32 = 0;
+        let mut covered = 0;
+        for i in 0..8 {
+            let sub_start = region_base + i * sub_size;
+            let sub_end = sub_start + sub_size;
+            if sub_start >= base && sub_end <= end {
+                covered += sub_size;
+            } else {
+                disabled |= 1 << i;
+            }
+        }
+
+        if covered > 0 && best.is_none_or(|(c, _)| covered > c) {
+            let size_field = region_size.trailing_zeros() - 1;
+            let desc = RegionDescriptor {
+                rbar: region_base as u32 | RBAR_VALID | (region & 0xF),
+                rasr: RASR_ENABLE
+                    | (size_field << 1)
+                    | (disabled << 8)
+                    | RASR_SRAM
+                    | RASR_AP_FULL
+                    | RASR_XN,
+            };
+            best = Some((covered, desc));
+        }
+        region_size *= 2;
+    }
+
+    best.map(|(_, desc)| desc)
+}
+
+/// Write a region descriptor to the MPU.
+///
+/// # Safety
+/// Reprograms memory permissions; must be called with interrupts disabled
+/// or from the PendSV handler.
+pub unsafe fn program_region(desc: RegionDescriptor) {
+    core::ptr::write_volatile(MPU_RBAR, desc.rbar);
+    core::ptr::write_volatile(MPU_RASR, desc.rasr);
+}
+
+/// Program an application shared region (0–5) with full access.
+///
+/// Returns `false` if the region number is reserved or no subregion of
+/// the range can be covered.
+///
+/// # Safety
+/// See `program_region`.
+pub unsafe fn set_shared_region(region: u32, base: usize, size: usize) -> bool {
+    if region >= SHARED_REGIONS {
+        return false;
+    }
+    match stack_region(base, size, region) {
+        Some(desc) => {
+            program_region(desc);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Grant the incoming task access to its own stack. Called from
+/// `do_context_switch` before the task's context is restored.
+///
+/// # Safety
+/// See `program_region`.
+pub unsafe fn configure_task(tcb: &TaskControlBlock) {
+    if let Some(desc) = stack_region(tcb.stack_base() as usize, tcb.stack_size(), STACK_REGION) {
+        program_region(desc);
+    }
+}
+
+/// Enable the MPU with the privileged background map and MemManage faults.
+///
+/// # Safety
+/// Must be called once during `kernel::start()`, before the first task.
+pub unsafe fn enable() {
+    let shcsr = core::ptr::read_volatile(SHCSR);
+    core::ptr::write_volatile(SHCSR, shcsr | (1 << 16));
+    core::ptr::write_volatile(MPU_CTRL, CTRL_ENABLE | CTRL_PRIVDEFENA);
+    cortex_m::asm::dsb();
+    cortex_m::asm::isb();
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assert every enabled subregion lies inside `[base, base + size)`
+    /// and return the number of bytes covered.
+    fn check_within(desc: &RegionDescriptor, base: usize, size: usize) -> usize {
+        let sub_size = desc.size() / 8;
+        let mut covered = 0;
+        for i in 0..8 {
+            if desc.subregion_enabled(i) {
+                let start = desc.base() + i as usize * sub_size;
+                assert!(start >= base && start + sub_size <= base + size,
+                    "subregion {} escapes the stack", i);
+                covered += sub_size;
+            }
+        }
+        covered
+    }
+
+    #[test]
+    fn test_aligned_stack_exact_region() {
+        let desc = stack_region(0x2000_0400, 1024, STACK_REGION).unwrap();
+        assert_eq!(desc.size(), 1024);
+        assert_eq!(desc.base(), 0x2000_0400);
+        assert_eq!(desc.rbar & 0xF, STACK_REGION);
+        assert_ne!(desc.rbar & RBAR_VALID, 0);
+        // SIZE field = log2(1024) - 1, all subregions enabled
+        assert_eq!((desc.rasr >> 1) & 0x1F, 9);
+        assert_eq!((desc.rasr >> 8) & 0xFF, 0);
+        assert_eq!(check_within(&desc, 0x2000_0400, 1024), 1024);
+    }
+
+    #[test]
+    fn test_unaligned_stack_never_exceeds_bounds() {
+        let base = 0x2000_0108;
+        let desc = stack_region(base, 1024, STACK_REGION).unwrap();
+        let covered = check_within(&desc, base, 1024);
+        assert_eq!(covered, 768);
+        assert_eq!(desc.size(), 2048);
+    }
+
+    #[test]
+    fn test_region_attributes() {
+        let desc = stack_region(0x2000_1000, 4096, 3).unwrap();
+        assert_ne!(desc.rasr & RASR_ENABLE, 0);
+        assert_ne!(desc.rasr & RASR_XN, 0);
+        assert_eq!(desc.rasr & (0b111 << 24), RASR_AP_FULL);
+        assert_eq!(desc.rbar & 0xF, 3);
+    }
+
+    #[test]
+    fn test_tiny_range_rejected() {
+        assert!(stack_region(0x2000_0004, 16, STACK_REGION).is_none());
+    }
+}
@@ -0,0 +1,153 @@
+//! # Tick Sources
+//!
+//! The scheduler only needs a periodic interrupt that calls
+//! [`cortex_m4::scheduler_tick`](super::cortex_m4::scheduler_tick).
+//! SysTick is the default, but boards that reserve SysTick or want a
+//! low-power timer can drive the kernel from any other peripheral.
+//!
+//! With the `embedded-hal` feature, any periodic
+//! `embedded_hal::timer::CountDown` implementation can be wrapped in
+//! [`CountDownTick`] and passed to `kernel::start_with_tick_source()`.
+//!
+//! Separately, a one-shot [`CompareTimer`] can be installed with
+//! `kernel::set_deadline_timer()` to enforce hard deadlines at their exact
+//! instant instead of at the next tick.
+
+use cortex_m::peripheral::SYST;
+
+/// A periodic interrupt source that drives the scheduler tick.
+pub trait TickSource {
+    /// Start generating interrupts at `hz`. The source's interrupt handler
+    /// must call [`super::cortex_m4::scheduler_tick`] on every period.
+    fn start(&mut self, hz: u32);
+}
+
+impl TickSource for SYST {
+    fn start(&mut self, hz: u32) {
+        super::cortex_m4::configure_systick_hz(self, hz);
+    }
+}
+
+/// A one-shot hardware compare interrupt, e.g. a free-running timer's
+/// output-compare channel or the DWT cycle counter with a comparator.
+///
+/// Times are absolute scheduler ticks; the implementation converts them to
+/// its own counter (typically `at * cycles_per_tick` plus the counter value
+/// at tick zero). Its interrupt handler must call
+/// `kernel::deadline_timer_interrupt()` and run at the same priority as
+/// the tick interrupt.
+pub trait CompareTimer {
+    /// Fire the interrupt once at absolute tick `at`, replacing any
+    /// previous arming. A compare that already fired but has not been
+    /// handled yet must be cleared, so it cannot stand in for the new one.
+    fn arm(&mut self, at: u64);
+
+    /// Cancel a pending compare, including one that already fired but
+    /// has not been handled yet.
+    fn disarm(&mut self);
+}
+
+#[cfg(feature = "embedded-hal")]
+pub use self::count_down::CountDownTick;
+
+#[cfg(feature = "embedded-hal")]
+mod count_down {
+    use super::TickSource;
+    use embedded_hal::timer::{CountDown, Periodic};
+
+    /// Adapts an `embedded-hal` periodic [`CountDown`] timer into a
+    /// [`TickSource`].
+    ///
+    /// `CountDown::Time` is HAL-specific, so the caller supplies the
+    /// conversion from a tick rate in Hz. The timer's own interrupt must be
+    /// enabled by the HAL; its handler should call [`Self::on_interrupt`].
+    pub struct CountDownTick<T: CountDown + Periodic> {
+        timer: T,
+        to_time: fn(u32) -> T::Time,
+    }
+
+    impl<T: CountDown + Periodic> CountDownTick<T> {
+        /// Wrap `timer`, using `to_time` to turn a rate in Hz into the
+        /// timer's period type.
+        pub fn new(timer: T, to_time: fn(u32) -> T::Time) -> Self {
+            Self { timer, to_time }
+        }
+
+        /// Returns `true` if the timer's period has elapsed, clearing the
+        /// pending update.
+        pub fn expired(&mut self) -> bool {
+            self.timer.wait().is_ok()
+        }
+
+        /// Call from the timer's interrupt handler. Acknowledges the timer
+        /// and advances the scheduler if a period has elapsed.
+        ///
+        /// # Safety
+        /// Must only be called after `kernel::init()`, from the timer ISR.
+        pub unsafe fn on_interrupt(&mut self) {
+            if self.expired() {
+                super::super::cortex_m4::scheduler_tick();
+            }
+        }
+
+        /// Release the underlying timer.
+        pub fn free(self) -> T {
+            self.timer
+        }
+    }
+
+    impl<T: CountDown + Periodic> TickSource for CountDownTick<T> {
+        fn start(&mut self, hz: u32) {
+            let period = (self.to_time)(hz);
+            self.timer.start(period);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        struct MockTimer {
+            period: Option<u32>,
+            pending: bool,
+        }
+
+        impl CountDown for MockTimer {
+            type Time = u32;
+
+            fn start<T: Into<u32>>(&mut self, count: T) {
+                self.period = Some(count.into());
+            }
+
+            fn wait(&mut self) -> nb::Result<(), void::Void> {
+                if core::mem::take(&mut self.pending) {
+                    Ok(())
+                } else {
+                    Err(nb::Error::WouldBlock)
+                }
+            }
+        }
+
+        impl Periodic for MockTimer {}
+
+        fn hz_to_us(hz: u32) -> u32 {
+            1_000_000 / hz
+        }
+
+        #[test]
+        fn test_count_down_tick_starts_with_converted_period() {
+            let timer = MockTimer { period: None, pending: false };
+            let mut source = CountDownTick::new(timer, hz_to_us);
+            source.start(1000);
+            assert_eq!(source.free().period, Some(1000));
+        }
+
+        #[test]
+        fn test_count_down_tick_expired_clears_pending() {
+            let timer = MockTimer { period: None, pending: true };
+            let mut source = CountDownTick::new(timer, hz_to_us);
+            assert!(source.expired());
+            assert!(!source.expired());
+        }
+    }
+}
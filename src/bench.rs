@@ -0,0 +1,204 @@
+//! # Convergence Benchmark
+//!
+//! Runs a synthetic workload through the scheduler on the host for a fixed
+//! number of ticks and reports how the game converged, so a task set can be
+//! tuned before it is flashed. Each task is modelled only by how long it
+//! works before yielding; no task code runs.
+//!
+//! ```ignore
+//! let report = bench::run(&[
+//!     BenchTask { config: hog_config, strategy: Strategy::Selfish, burst: 0 },
+//!     BenchTask { config: sensor_config, strategy: Strategy::Cooperative, burst: 5 },
+//! ], 10_000)?;
+//! println!("equilibrium after {:?} windows", report.windows_to_equilibrium);
+//! ```
+
+use crate::config::{EVAL_FREQUENCY, MAX_TASKS};
+use crate::error::KernelError;
+use crate::scheduler::Scheduler;
+use crate::task::{Strategy, TaskConfig, TaskEntry, TaskState};
+
+/// One synthetic task in a benchmark workload.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchTask {
+    /// Configuration passed to `create_task()`.
+    pub config: TaskConfig,
+    /// Initial strategy.
+    pub strategy: Strategy,
+    /// Ticks of work before the task completes its job and yields.
+    /// `0` models a CPU hog that never yields.
+    pub burst: u32,
+}
+
+/// Results of a benchmark run.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchReport {
+    /// Ticks simulated.
+    pub ticks: u64,
+    /// Evaluation windows elapsed when the debounced equilibrium flag first
+    /// became set, or `None` if it never did.
+    pub windows_to_equilibrium: Option<u64>,
+    /// Strategy changes across all tasks.
+    pub strategy_switches: u32,
+    /// Context switches to a different task.
+    pub context_switches: u64,
+    /// Per-task percentage of evaluated deadlines that were met. `None`
+    /// for tasks without a deadline, or with none evaluated yet.
+    pub deadline_hit_rate: [Option<u32>; MAX_TASKS],
+}
+
+impl BenchReport {
+    /// Average context switches per tick, in fixed-point ×100.
+    pub fn switches_per_tick_x100(&self) -> u32 {
+        if self.ticks == 0 {
+            return 0;
+        }
+        (self.context_switches * 100 / self.ticks) as u32
+    }
+}
+
+extern "C" fn bench_entry() -> ! {
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Simulate `tasks` for `ticks` scheduler ticks.
+///
+/// # Returns
+/// - `Ok(report)` — convergence and overhead metrics
+/// - `Err(KernelError::TooManyTasks)` or `InvalidPriority` — the
+///   workload cannot be created
+pub fn run(tasks: &[BenchTask], ticks: u64) -> Result<BenchReport, KernelError> {
+    let mut sched = Scheduler::new();
+    for task in tasks {
+        sched.create_task(TaskEntry::new(bench_entry), task.config, task.strategy)?;
+    }
+
+    let mut report = BenchReport {
+        ticks,
+        windows_to_equilibrium: None,
+        strategy_switches: 0,
+        context_switches: 0,
+        deadline_hit_rate: [None; MAX_TASKS],
+    };
+    let mut work = [0u32; MAX_TASKS];
+    let mut strategies = [Strategy::Cooperative; MAX_TASKS];
+    for (id, strategy) in strategies[..sched.task_count].iter_mut().enumerate() {
+        *strategy = sched.tasks[id].strategy;
+    }
+
+    let mut current = sched.schedule();
+    for _ in 0..ticks {
+        sched.tick();
+
+        // The running task does one tick of work
+        if sched.tasks[current].state == TaskState::Running {
+            let burst = tasks[current].burst;
+            work[current] += 1;
+            if burst > 0 && work[current] >= burst {
+                work[current] = 0;
+                sched.job_complete();
+                sched.yield_current();
+            }
+        }
+
+        if sched.needs_reschedule {
+            let next = sched.schedule();
+            if next != current {
+                report.context_switches += 1;
+                current = next;
+            }
+        }
+
+        for (id, strategy) in strategies[..sched.task_count].iter_mut().enumerate() {
+            if sched.tasks[id].strategy != *strategy {
+                *strategy = sched.tasks[id].strategy;
+                report.strategy_switches += 1;
+            }
+        }
+        if report.windows_to_equilibrium.is_none() && sched.in_equilibrium {
+            report.windows_to_equilibrium = Some(sched.tick_count / EVAL_FREQUENCY as u64);
+        }
+    }
+
+    for (id, rate) in report.deadline_hit_rate[..sched.task_count].iter_mut().enumerate() {
+        let payoff = &sched.tasks[id].payoff;
+        let evaluated = payoff.deadlines_met + payoff.deadlines_missed;
+        *rate = (payoff.deadlines_met * 100).checked_div(evaluated);
+    }
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TICK_HZ;
+    use core::time::Duration;
+
+    /// The task set of the example firmware in `main.rs`.
+    fn example_task_set() -> [BenchTask; 4] {
+        [
+            BenchTask {
+                config: TaskConfig {
+                    priority: 2,
+                    wcet_ticks: 15,
+                    time_slice: 10,
+                    ..TaskConfig::DEFAULT
+                },
+                strategy: Strategy::Selfish,
+                burst: 0,
+            },
+            BenchTask {
+                config: TaskConfig::builder()
+                    .priority(3)
+                    .period(Duration::from_millis(100))
+                    .wcet(Duration::from_millis(5))
+                    .time_slice(Duration::from_millis(10))
+                    .build(TICK_HZ),
+                strategy: Strategy::Cooperative,
+                burst: 5,
+            },
+            BenchTask {
+                config: TaskConfig { priority: 1, time_slice: 10, ..TaskConfig::DEFAULT },
+                strategy: Strategy::Cooperative,
+                burst: 1,
+            },
+            BenchTask {
+                config: TaskConfig::builder()
+                    .priority(5)
+                    .deadline(Duration::from_millis(50))
+                    .wcet(Duration::from_millis(3))
+                    .time_slice(Duration::from_millis(5))
+                    .build(TICK_HZ),
+                strategy: Strategy::Cooperative,
+                burst: 2,
+            },
+        ]
+    }
+
+    #[test]
+    #[cfg_attr(feature = "stack-pool", ignore = "run() has no stack pool to carve from")]
+    fn test_example_task_set_converges() {
+        let report = run(&example_task_set(), 5000).unwrap();
+        assert_eq!(report.ticks, 5000);
+        assert!(report.windows_to_equilibrium.is_some());
+        assert!(report.context_switches > 0);
+        assert!(report.switches_per_tick_x100() <= 100);
+        assert!(report.deadline_hit_rate[1].is_some());
+        assert!(report.deadline_hit_rate[3].is_some());
+        assert_eq!(report.deadline_hit_rate[0], None);
+        assert_eq!(report.deadline_hit_rate[2], None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "stack-pool", ignore = "run() has no stack pool to carve from")]
+    fn test_rejects_oversized_workload() {
+        let task = BenchTask { config: TaskConfig::DEFAULT, strategy: Strategy::Cooperative, burst: 1 };
+        assert_eq!(run(&[task; MAX_TASKS + 1], 10).unwrap_err(), KernelError::TooManyTasks);
+    }
+}
@@ -8,39 +8,159 @@
 /// consumes `STACK_SIZE` bytes of RAM.
 pub const MAX_TASKS: usize = 8;
 
+/// Number of base priority levels. Task priorities must lie in
+/// `0..PRIORITY_LEVELS`; `create_task` rejects anything above.
+/// Bounding the priority space keeps per-level scheduler structures small.
+pub const PRIORITY_LEVELS: usize = 32;
+
 /// SysTick frequency in Hz. Determines scheduler tick granularity.
 /// Higher values give finer scheduling precision at the cost of
 /// increased interrupt overhead.
 pub const TICK_HZ: u32 = 1000;
 
+/// Maximum number of deadline bundles (task groups sharing one
+/// end-to-end deadline).
+pub const MAX_BUNDLES: usize = 4;
+
+/// Number of software timer slots (see the `timer` module).
+pub const MAX_TIMERS: usize = 8;
+
 /// Default time slice in ticks. A task runs for this many ticks
 /// before the scheduler re-evaluates. The game engine may adjust
 /// effective slices via payoff weighting.
 pub const DEFAULT_TIME_SLICE: u32 = 10;
 
+/// Longest time slice in ticks `create_task` accepts. Longer slices let a
+/// task hold the CPU beyond what preemptive time-sharing is meant for.
+pub const MAX_TIME_SLICE: u32 = 1000;
+
+/// Payoff points that lengthen a task's time slice by one percent (or,
+/// for negative payoff, shorten it), so well-behaved tasks earn longer
+/// quanta.
+pub const QUANTUM_PAYOFF_STEP: i32 = 20;
+
+/// Default shortest payoff-scaled time slice, in percent of the task's
+/// configured slice.
+pub const QUANTUM_SCALE_MIN: u32 = 50;
+
+/// Default longest payoff-scaled time slice, in percent of the task's
+/// configured slice.
+pub const QUANTUM_SCALE_MAX: u32 = 200;
+
 /// Per-task stack size in bytes. Must be large enough for the
 /// deepest call chain plus the hardware exception frame (32 bytes)
 /// and the software-saved context (32 bytes for R4–R11).
 pub const STACK_SIZE: usize = 1024;
 
-/// Number of processor cores. Set to 1 for Cortex-M4 (single-core).
-/// The architecture is designed to be extensible to multi-core systems
-/// by increasing this value and implementing per-core scheduling.
+/// Stack size in bytes of the idle task with the `stack-pool` feature.
+/// The idle task only runs the idle hook and `wfi`, so it needs far less
+/// than `STACK_SIZE`; without `stack-pool` every TCB has a full stack.
+pub const IDLE_STACK_SIZE: usize = 256;
+
+/// Sentinel word written at the lowest address of every task stack.
+/// If it is ever overwritten, the task has overflowed its stack; with
+/// `StackCheck::CanaryCheck` this is verified on every context switch.
+pub const STACK_CANARY: u32 = 0xDEAD_BEEF;
+
+/// Number of processor cores the scheduler manages: 1 for the Cortex-M4
+/// port, 2 with the `dual-core` feature. Each core runs its own current
+/// task, chosen by `Scheduler::schedule_for_core()`.
+#[cfg(not(feature = "dual-core"))]
 pub const MAX_CORES: usize = 1;
+/// Number of processor cores the scheduler manages: 1 for the Cortex-M4
+/// port, 2 with the `dual-core` feature. Each core runs its own current
+/// task, chosen by `Scheduler::schedule_for_core()`.
+#[cfg(feature = "dual-core")]
+pub const MAX_CORES: usize = 2;
 
 /// Number of ticks a task can receive zero CPU before the starvation
-/// prevention mechanism triggers a priority boost.
+/// prevention mechanism triggers a priority boost. This is the value at
+/// `STARVATION_BASE_TASKS` active tasks; the scheduler scales it with load.
 pub const STARVATION_THRESHOLD: u32 = 50;
 
+/// Active-task count at which the effective starvation threshold equals
+/// `STARVATION_THRESHOLD`. More tasks scale the threshold up proportionally.
+pub const STARVATION_BASE_TASKS: u32 = 4;
+
+/// Lower bound on the load-scaled starvation threshold.
+pub const STARVATION_THRESHOLD_MIN: u32 = 20;
+
+/// Upper bound on the load-scaled starvation threshold.
+pub const STARVATION_THRESHOLD_MAX: u32 = 200;
+
+/// Default ceiling on the schedule-time starvation boost, in priority
+/// levels: enough to lift a starving task over any base priority, but not
+/// to grow without bound.
+pub const STARVATION_BOOST_MAX: u32 = PRIORITY_LEVELS as u32;
+
+/// Number of ticks a task may hold the CPU without a voluntary yield
+/// before it is flagged as non-yielding (a pure CPU hog).
+pub const NON_YIELD_THRESHOLD: u32 = 100;
+
 /// Number of consecutive evaluation windows with declining payoff
 /// required before a task switches strategy. Provides hysteresis
 /// to avoid oscillation.
 pub const STRATEGY_HYSTERESIS: u32 = 3;
 
+/// A task that switches strategy more than `OSCILLATION_SWITCH_LIMIT` times
+/// within `OSCILLATION_WINDOW` strategy updates is oscillating, and its
+/// strategy is locked for `STRATEGY_LOCK_COOLDOWN` updates.
+pub const OSCILLATION_SWITCH_LIMIT: u32 = 2;
+
+/// Length, in strategy updates, of the window over which switches are
+/// counted for oscillation detection.
+pub const OSCILLATION_WINDOW: u32 = 12;
+
+/// Number of strategy updates an oscillating task's strategy stays locked
+/// before it may adapt again.
+pub const STRATEGY_LOCK_COOLDOWN: u32 = 20;
+
+/// A task declared `Cooperative` that used more than
+/// `RECLASSIFY_CPU_FACTOR` times its fair share of an evaluation window
+/// without yielding once is reclassified as `Selfish`.
+pub const RECLASSIFY_CPU_FACTOR: u64 = 2;
+
+/// A task declared `Selfish` that yielded at least `RECLASSIFY_YIELD_COUNT`
+/// times in an evaluation window while staying within its fair share is
+/// reclassified as `Cooperative`.
+pub const RECLASSIFY_YIELD_COUNT: u32 = 4;
+
+/// Number of consecutive evaluations that must agree before the reported
+/// equilibrium status changes. Debounces single-window noise.
+pub const EQUILIBRIUM_DEBOUNCE: u32 = 3;
+
+/// Global cooperation ratio (×100) at or below which the system counts as
+/// stuck in mutual defection.
+pub const DEFECTION_RATIO_THRESHOLD: u32 = 10;
+
+/// Consecutive evaluation windows of mutual defection after which the
+/// circuit-breaker forces the lowest-payoff selfish task to cooperate.
+pub const DEFECTION_RECOVERY_WINDOWS: u32 = 5;
+
+/// Consecutive deadline misses after which a periodic task counts as
+/// overloaded (its execution time has reached its period) and the
+/// scheduler's degradation policy engages.
+pub const OVERLOAD_PERIODS: u32 = 3;
+
+/// Number of evaluation windows of `global_cooperation_ratio` history
+/// kept for `cooperation_trend()`.
+pub const COOPERATION_TREND_LEN: usize = 16;
+
 /// Game evaluation frequency divisor. The full equilibrium check
 /// runs every `EVAL_FREQUENCY` ticks to bound overhead.
 /// Payoff incremental updates still occur every tick.
 pub const EVAL_FREQUENCY: u32 = 10;
 
+/// Payoff memory: the deadline and yield counts the payoff is computed
+/// from are shifted right by this much after every evaluation, so each
+/// evaluation window counts for half of the one after it.
+pub const PAYOFF_DECAY_SHIFT: u32 = 1;
+
+/// Fair-share memory: after every evaluation the CPU usage the fairness
+/// term compares loses this fraction (a right shift) of itself, so each
+/// evaluation window counts for three quarters of the one after it. A
+/// single window is too short to judge a task's share on.
+pub const FAIRNESS_DECAY_SHIFT: u32 = 2;
+
 /// System clock frequency in Hz (default for STM32F4 at 16 MHz HSI).
 pub const SYSTEM_CLOCK_HZ: u32 = 16_000_000;
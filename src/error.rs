@@ -0,0 +1,39 @@
+//! # Kernel Errors
+//!
+//! Error type returned by fallible kernel and scheduler APIs.
+
+/// Errors returned by kernel and scheduler operations.
+///
+/// Non-exhaustive: new failure modes may be added, so matches need a
+/// wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KernelError {
+    /// A kernel call was made before `kernel::init()`.
+    NotInitialized,
+    /// The task array is full (`MAX_TASKS` reached).
+    TooManyTasks,
+    /// Every deadline bundle slot is in use (`MAX_BUNDLES` reached).
+    TooManyBundles,
+    /// Every software timer slot is in use (`MAX_TIMERS` reached).
+    TooManyTimers,
+    /// The external stack pool cannot fit another task stack.
+    StackPoolExhausted,
+    /// The configured priority is outside `0..PRIORITY_LEVELS`.
+    InvalidPriority,
+    /// The task configuration is inconsistent: a time slice longer than
+    /// `MAX_TIME_SLICE`, a WCET beyond the deadline, an affinity mask
+    /// naming no core, or a stack too small for the initial frame.
+    InvalidConfig,
+    /// The task id does not refer to an allocated task.
+    InvalidTaskId,
+    /// The timer id does not refer to an allocated software timer.
+    InvalidTimerId,
+    /// The task is not in a state that permits the operation.
+    InvalidState,
+    /// The change would push periodic utilization above 100%.
+    Unschedulable,
+    /// A snapshot buffer is truncated, has the wrong magic or version, or
+    /// contains an out-of-range field.
+    InvalidSnapshot,
+}
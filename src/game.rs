@@ -12,12 +12,15 @@
 //! | **Cooperate**   | Both: high payoff | A: penalized, B: moderate |
 //! | **Defect**      | A: moderate, B: penalized | Both: low payoff |
 //!
-//! The payoff function evaluates each task independently based on:
+//! The payoff function evaluates each task independently based on the
+//! following, with weights from a [`PayoffConfig`] (defaults shown):
 //! - Deadline compliance (+100 per met, -200 per missed)
 //! - Voluntary yields (+50 each)
 //! - Consecutive overruns (-150 penalty)
-//! - CPU fairness (bonus/penalty based on deviation from fair share)
-//! - Cooperation multiplier (1.5× for cooperative tasks)
+//! - CPU fairness (bonus/penalty based on deviation from fair share, as
+//!   defined by a pluggable [`FairSharePolicy`])
+//! - Cooperation multiplier (1.5× for cooperating tasks, including
+//!   tit-for-tat tasks whose current move is to cooperate)
 //! - Global cooperation ratio (collective defection penalty)
 //!
 //! ## Equilibrium Approximation
@@ -29,7 +32,10 @@
 //! 3. If no task benefits from switching → system is in Nash equilibrium
 //! 4. Strategy changes require sustained payoff decline (hysteresis)
 
-use crate::config::{MAX_TASKS, STRATEGY_HYSTERESIS};
+use crate::config::{
+    MAX_TASKS, STRATEGY_HYSTERESIS, OSCILLATION_SWITCH_LIMIT, OSCILLATION_WINDOW,
+    STRATEGY_LOCK_COOLDOWN, RECLASSIFY_CPU_FACTOR, RECLASSIFY_YIELD_COUNT, QUANTUM_PAYOFF_STEP,
+};
 use crate::task::{TaskControlBlock, Strategy};
 
 // ---------------------------------------------------------------------------
@@ -51,6 +57,14 @@ pub struct SystemMetrics {
     pub global_cooperation_ratio: u32,
     /// True if the system is in overload (more tasks than can be served).
     pub overload: bool,
+    /// Total processor cycles elapsed across all run segments, including
+    /// idle time. Only accumulated with the `cycle-accounting` feature.
+    pub total_cycles: u64,
+    /// Ticks elapsed, decayed at every game evaluation by
+    /// `FAIRNESS_DECAY_SHIFT`: the span recent CPU usage is judged over.
+    pub recent_ticks: u64,
+    /// Like `recent_ticks`, but in processor cycles.
+    pub recent_cycles: u64,
 }
 
 impl SystemMetrics {
@@ -60,10 +74,95 @@ impl SystemMetrics {
             active_tasks: 0,
             global_cooperation_ratio: 100,
             overload: false,
+            total_cycles: 0,
+            recent_ticks: 0,
+            recent_cycles: 0,
         }
     }
 }
 
+impl Default for SystemMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Fair-share policy
+// ---------------------------------------------------------------------------
+
+/// Computes a task's fair CPU allotment, which the payoff's fairness term
+/// compares the task's actual usage against.
+///
+/// The allotment is in the unit fairness is accounted in: ticks, or
+/// processor cycles with the `cycle-accounting` feature (the totals to
+/// divide are `metrics.total_ticks` and `metrics.total_cycles`
+/// respectively). The payoff scales the allotment down to the recent,
+/// decayed span it judges usage over. Returning `0` exempts the task from
+/// the fairness term.
+pub type FairSharePolicy = fn(&TaskControlBlock, &SystemMetrics) -> u64;
+
+/// Default policy: total time divided equally among the active tasks.
+pub fn equal_fair_share(_task: &TaskControlBlock, metrics: &SystemMetrics) -> u64 {
+    #[cfg(feature = "cycle-accounting")]
+    let total = metrics.total_cycles;
+    #[cfg(not(feature = "cycle-accounting"))]
+    let total = metrics.total_ticks;
+
+    if metrics.active_tasks == 0 {
+        return 0;
+    }
+    total / metrics.active_tasks as u64
+}
+
+// ---------------------------------------------------------------------------
+// Payoff weights
+// ---------------------------------------------------------------------------
+
+/// Weights of the payoff components, so a workload can trade them off
+/// (e.g. a hard real-time system lets misses dominate, a throughput system
+/// weighs fairness more). Penalties are given as positive magnitudes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayoffConfig {
+    /// Reward per deadline met.
+    pub deadline_met: i32,
+    /// Penalty per deadline missed.
+    pub deadline_missed: i32,
+    /// Reward per voluntary yield.
+    pub voluntary_yield: i32,
+    /// Penalty per consecutive WCET overrun.
+    pub overrun: i32,
+    /// Penalty per percentage point of CPU use above 2× fair share.
+    pub fairness_penalty: i32,
+    /// Reward per percentage point of CPU use below half the fair share.
+    pub modesty_bonus: i32,
+    /// Multiplier on a cooperative task's positive payoff, in percent.
+    pub cooperation_multiplier: i32,
+    /// Penalty applied to everyone while fewer than half the tasks
+    /// cooperate.
+    pub defection_penalty: i32,
+}
+
+impl PayoffConfig {
+    /// The built-in weights.
+    pub const DEFAULT: Self = Self {
+        deadline_met: 100,
+        deadline_missed: 200,
+        voluntary_yield: 50,
+        overrun: 150,
+        fairness_penalty: 2,
+        modesty_bonus: 1,
+        cooperation_multiplier: 150,
+        defection_penalty: 100,
+    };
+}
+
+impl Default for PayoffConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Payoff computation
 // ---------------------------------------------------------------------------
@@ -75,62 +174,85 @@ impl SystemMetrics {
 ///
 /// ## Payoff Components
 ///
+/// Values are the [`PayoffConfig::DEFAULT`] weights.
+///
 /// | Component | Value | Rationale |
 /// |-----------|-------|-----------|
 /// | Deadline met | +100 | Reward timely completion |
 /// | Deadline missed | -200 | Heavily penalize lateness |
 /// | Voluntary yield | +50 | Reward cooperation |
 /// | Consecutive overrun | -150 × count | Escalating penalty for hogging |
-/// | Fair-share deviation | ±penalty | Penalize CPU usage > 2× fair share |
+/// | Fair-share deviation | ±penalty | Penalize recent CPU usage > 2× fair share |
 /// | Cooperation multiplier | ×1.5 | Bonus for cooperative strategy |
 /// | Global defection penalty | -100 | Applied when <50% tasks cooperate |
 ///
+/// Deadlines and yields are counted over recent windows only: the
+/// scheduler halves the counts after each evaluation (see
+/// [`PAYOFF_DECAY_SHIFT`](crate::config::PAYOFF_DECAY_SHIFT)), so old good
+/// behaviour fades instead of propping up a task that turned selfish.
+///
 /// All arithmetic is integer-only. The final payoff is in fixed-point ×100.
-pub fn compute_payoff(task: &TaskControlBlock, metrics: &SystemMetrics) -> i32 {
-    let mut payoff: i32 = 0;
-
-    // --- Deadline compliance ---
-    payoff += task.payoff.deadlines_met as i32 * 100;
-    payoff -= task.payoff.deadlines_missed as i32 * 200;
+/// Fair share is the equal split of [`equal_fair_share`]; use
+/// [`compute_payoff_with`] for another definition.
+pub fn compute_payoff(task: &TaskControlBlock, metrics: &SystemMetrics, weights: &PayoffConfig) -> i32 {
+    compute_payoff_with(task, metrics, weights, equal_fair_share)
+}
 
-    // --- Voluntary yields ---
-    payoff += task.payoff.voluntary_yields as i32 * 50;
+/// `share` of `total` applied to `span`: `share * span / total`, widened
+/// so that long uptimes cannot overflow the product. `0` when `total` is.
+fn scale_share(share: u64, span: u64, total: u64) -> u64 {
+    let scaled = (share as u128 * span as u128).checked_div(total as u128).unwrap_or(0);
+    scaled.min(u64::MAX as u128) as u64
+}
 
-    // --- Consecutive overrun penalty (escalating) ---
-    let overrun_count = task.payoff.consecutive_overruns as i32;
-    payoff -= overrun_count * 150;
+/// Compute the payoff score for a single task, measuring CPU fairness
+/// against the allotment returned by `fair_share`.
+pub fn compute_payoff_with(
+    task: &TaskControlBlock,
+    metrics: &SystemMetrics,
+    weights: &PayoffConfig,
+    fair_share: FairSharePolicy,
+) -> i32 {
+    let mut payoff = base_payoff(task, weights);
 
     // --- CPU fairness ---
-    // Fair share = total_ticks / active_tasks
-    if metrics.active_tasks > 0 && metrics.total_ticks > 0 {
-        let fair_share = (metrics.total_ticks / metrics.active_tasks as u64) as u32;
-        let actual = task.payoff.cpu_ticks_used;
-
-        if fair_share > 0 {
-            // Ratio of actual/fair × 100
-            let usage_ratio = (actual as u64 * 100 / fair_share as u64) as i32;
-
-            if usage_ratio > 200 {
-                // Using more than 2× fair share → penalty
-                payoff -= (usage_ratio - 200) * 2;
-            } else if usage_ratio < 50 {
-                // Using less than half fair share → small bonus (being modest)
-                payoff += (50 - usage_ratio);
-            }
+    // Recent usage against the policy's allotment of the same decayed span,
+    // in cycles when measured. Older windows fade out, so a reformed hog
+    // recovers within a few windows, and history from before a task was
+    // created soon stops counting against it. A single 10-tick window on
+    // its own is too noisy: one full time slice is already 4× the share of
+    // four tasks.
+    #[cfg(feature = "cycle-accounting")]
+    let (actual, total, recent) = (task.recent_cycles_used, metrics.total_cycles, metrics.recent_cycles);
+    #[cfg(not(feature = "cycle-accounting"))]
+    let (actual, total, recent) = (task.recent_ticks_used as u64, metrics.total_ticks, metrics.recent_ticks);
+
+    let fair_share = scale_share(fair_share(task, metrics), recent, total);
+    // Ratio of actual/fair × 100
+    if let Some(usage_ratio) = (actual * 100).checked_div(fair_share) {
+        let usage_ratio = usage_ratio as i32;
+
+        if usage_ratio > 200 {
+            // Using more than 2× fair share → penalty
+            payoff -= (usage_ratio - 200) * weights.fairness_penalty;
+        } else if usage_ratio < 50 {
+            // Using less than half fair share → small bonus (being modest)
+            payoff += (50 - usage_ratio) * weights.modesty_bonus;
         }
     }
 
     // --- Cooperation multiplier ---
-    // Cooperative tasks get a 1.5× multiplier on positive payoff
-    if task.strategy == Strategy::Cooperative && payoff > 0 {
-        payoff = payoff * 3 / 2;
+    // Cooperating tasks get a multiplier (1.5× by default) on positive
+    // payoff; tit-for-tat only in windows where it cooperates
+    if task.is_cooperating() && payoff > 0 {
+        payoff = payoff * weights.cooperation_multiplier / 100;
     }
 
     // --- Global cooperation penalty ---
     // If fewer than 50% of tasks are cooperating, everyone gets penalized
     // (Prisoner's Dilemma: mutual defection is collectively worse)
     if metrics.global_cooperation_ratio < 50 {
-        payoff -= 100;
+        payoff -= weights.defection_penalty;
     }
 
     // --- Cooperation score integration ---
@@ -140,6 +262,31 @@ pub fn compute_payoff(task: &TaskControlBlock, metrics: &SystemMetrics) -> i32 {
     payoff
 }
 
+/// Strategy-independent payoff components: deadlines, yields and overruns.
+fn base_payoff(task: &TaskControlBlock, weights: &PayoffConfig) -> i32 {
+    let mut payoff: i32 = 0;
+
+    // --- Deadline compliance ---
+    payoff += task.payoff.recent_deadlines_met as i32 * weights.deadline_met;
+    payoff -= task.payoff.recent_deadlines_missed as i32 * weights.deadline_missed;
+
+    // --- Voluntary yields ---
+    payoff += task.payoff.recent_yields as i32 * weights.voluntary_yield;
+
+    // --- Consecutive overrun penalty (escalating) ---
+    payoff -= task.payoff.consecutive_overruns as i32 * weights.overrun;
+
+    payoff
+}
+
+/// Scale, in percent, applied to a task's time slice for `payoff`: one
+/// percent per `QUANTUM_PAYOFF_STEP` points above or below 100%, clamped
+/// to `min..=max`.
+pub fn quantum_scale(payoff: i32, min: u32, max: u32) -> u32 {
+    let percent = 100 + (payoff / QUANTUM_PAYOFF_STEP) as i64;
+    percent.clamp(min as i64, max as i64) as u32
+}
+
 // ---------------------------------------------------------------------------
 // Equilibrium detection
 // ---------------------------------------------------------------------------
@@ -147,23 +294,31 @@ pub fn compute_payoff(task: &TaskControlBlock, metrics: &SystemMetrics) -> i32 {
 /// Check whether the system is currently in Nash equilibrium.
 ///
 /// The system is in equilibrium if no task would improve its payoff by
-/// unilaterally switching its strategy (cooperative ↔ selfish).
+/// unilaterally switching its strategy (cooperative ↔ selfish, or
+/// flipping a tit-for-tat task's current move).
 ///
 /// This is an approximation: we compute the hypothetical payoff for each
 /// task under the alternative strategy and check if any task would benefit.
 ///
+/// Alternatives are scored with the same `weights` as the actual payoff.
+///
 /// # Returns
 /// `true` if no task benefits from switching strategy.
-pub fn is_in_equilibrium(tasks: &[TaskControlBlock; MAX_TASKS], task_count: usize, metrics: &SystemMetrics) -> bool {
-    for i in 0..task_count {
-        if !tasks[i].active {
+pub fn is_in_equilibrium(
+    tasks: &[TaskControlBlock; MAX_TASKS],
+    task_count: usize,
+    metrics: &SystemMetrics,
+    weights: &PayoffConfig,
+) -> bool {
+    for task in &tasks[..task_count] {
+        if !task.is_player() {
             continue;
         }
 
-        let current_payoff = tasks[i].payoff.payoff;
+        let current_payoff = task.payoff.payoff;
 
         // Estimate payoff under alternative strategy
-        let alt_payoff = estimate_alternative_payoff(&tasks[i], metrics);
+        let alt_payoff = estimate_alternative_payoff(task, metrics, weights);
 
         // If switching would improve payoff by more than a threshold, not in equilibrium
         if alt_payoff > current_payoff + 50 {
@@ -177,31 +332,18 @@ pub fn is_in_equilibrium(tasks: &[TaskControlBlock; MAX_TASKS], task_count: usiz
 ///
 /// This is a lightweight approximation — we don't fully re-simulate,
 /// but apply the strategy-dependent modifiers to the current base score.
-fn estimate_alternative_payoff(task: &TaskControlBlock, metrics: &SystemMetrics) -> i32 {
-    let mut payoff: i32 = 0;
-
+fn estimate_alternative_payoff(task: &TaskControlBlock, metrics: &SystemMetrics, weights: &PayoffConfig) -> i32 {
     // Same base components
-    payoff += task.payoff.deadlines_met as i32 * 100;
-    payoff -= task.payoff.deadlines_missed as i32 * 200;
-    payoff += task.payoff.voluntary_yields as i32 * 50;
-    payoff -= task.payoff.consecutive_overruns as i32 * 150;
-
-    // Flip the cooperation multiplier
-    match task.strategy {
-        Strategy::Cooperative => {
-            // If currently cooperative, switching to selfish removes the multiplier
-            // No multiplier applied
-        }
-        Strategy::Selfish => {
-            // If currently selfish, switching to cooperative adds the multiplier
-            if payoff > 0 {
-                payoff = payoff * 3 / 2;
-            }
-        }
+    let mut payoff = base_payoff(task, weights);
+
+    // Flip the cooperation multiplier: a cooperating task would lose it,
+    // a defecting one would gain it
+    if !task.is_cooperating() && payoff > 0 {
+        payoff = payoff * weights.cooperation_multiplier / 100;
     }
 
     if metrics.global_cooperation_ratio < 50 {
-        payoff -= 100;
+        payoff -= weights.defection_penalty;
     }
 
     payoff += task.payoff.cooperation_score / 2;
@@ -225,33 +367,165 @@ fn estimate_alternative_payoff(task: &TaskControlBlock, metrics: &SystemMetrics)
 /// In practice, the payoff function is designed so that sustained cooperation
 /// yields higher payoff, creating a natural attractor toward cooperative
 /// equilibrium.
+///
+/// Hysteresis alone cannot stop a task whose payoff keeps declining under
+/// both strategies from flipping every few windows. A task that switches
+/// more than `OSCILLATION_SWITCH_LIMIT` times within `OSCILLATION_WINDOW`
+/// updates has its strategy locked for `STRATEGY_LOCK_COOLDOWN` updates.
+///
+/// Tit-for-tat tasks sit outside this hysteresis: their strategy never
+/// switches, and their move follows the cooperation ratio every window
+/// (see [`play_tit_for_tat`]) rather than their own payoff trend. Tasks
+/// with `strategy_locked` are skipped entirely.
 pub fn update_strategies(tasks: &mut [TaskControlBlock; MAX_TASKS], task_count: usize, _metrics: &SystemMetrics) {
-    for i in 0..task_count {
-        if !tasks[i].active {
+    for task in &mut tasks[..task_count] {
+        if !task.is_player() || task.strategy_locked || task.strategy == Strategy::TitForTat {
             continue;
         }
 
-        let current = tasks[i].payoff.payoff;
-        let previous = tasks[i].payoff.previous_payoff;
+        let current = task.payoff.payoff;
+        let previous = task.payoff.previous_payoff;
+
+        // Locked after oscillating: hold the strategy until the cooldown ends
+        if task.payoff.strategy_lock > 0 {
+            task.payoff.strategy_lock -= 1;
+            task.payoff.decline_streak = 0;
+            task.payoff.previous_payoff = current;
+            continue;
+        }
 
         if current < previous {
-            tasks[i].payoff.decline_streak += 1;
+            task.payoff.decline_streak += 1;
         } else {
-            tasks[i].payoff.decline_streak = 0;
+            task.payoff.decline_streak = 0;
         }
 
         // Switch strategy after sustained decline
-        if tasks[i].payoff.decline_streak >= STRATEGY_HYSTERESIS {
-            tasks[i].strategy = match tasks[i].strategy {
+        if task.payoff.decline_streak >= STRATEGY_HYSTERESIS {
+            task.strategy = match task.strategy {
                 Strategy::Cooperative => Strategy::Selfish,
                 Strategy::Selfish => Strategy::Cooperative,
+                Strategy::TitForTat => Strategy::TitForTat,
             };
-            tasks[i].payoff.decline_streak = 0;
+            task.payoff.decline_streak = 0;
+            task.payoff.recent_switches += 1;
+        }
+
+        // Oscillation detection over fixed windows of updates
+        task.payoff.switch_window += 1;
+        if task.payoff.recent_switches > OSCILLATION_SWITCH_LIMIT {
+            task.payoff.strategy_lock = STRATEGY_LOCK_COOLDOWN;
+            task.payoff.recent_switches = 0;
+            task.payoff.switch_window = 0;
+        } else if task.payoff.switch_window >= OSCILLATION_WINDOW {
+            task.payoff.recent_switches = 0;
+            task.payoff.switch_window = 0;
         }
 
         // Store current as previous for next evaluation
-        tasks[i].payoff.previous_payoff = current;
+        task.payoff.previous_payoff = current;
+    }
+}
+
+/// Reconcile declared strategies with behaviour over the evaluation
+/// window just ended, `window` ticks long.
+///
+/// A `Cooperative` task that never yielded in the window yet used more
+/// than `RECLASSIFY_CPU_FACTOR` times its fair share of it becomes
+/// `Selfish`; a `Selfish` task that yielded at least
+/// `RECLASSIFY_YIELD_COUNT` times without exceeding its fair share becomes
+/// `Cooperative`. The fair share is the fraction of all time
+/// `fair_share` allots the task, applied to the window. Tit-for-tat tasks,
+/// pinned tasks (`strategy_locked`) and tasks the policy exempts are left
+/// alone.
+///
+/// # Returns
+/// A bitmask of the reclassified task ids.
+pub fn reclassify_strategies(
+    tasks: &mut [TaskControlBlock; MAX_TASKS],
+    task_count: usize,
+    metrics: &SystemMetrics,
+    fair_share: FairSharePolicy,
+    window: u64,
+) -> u32 {
+    #[cfg(feature = "cycle-accounting")]
+    let total = metrics.total_cycles;
+    #[cfg(not(feature = "cycle-accounting"))]
+    let total = metrics.total_ticks;
+    if total == 0 || window == 0 {
+        return 0;
+    }
+
+    let mut reclassified = 0;
+    for (i, tcb) in tasks[..task_count].iter_mut().enumerate() {
+        if !tcb.is_player() || tcb.strategy_locked {
+            continue;
+        }
+        let share = scale_share(fair_share(tcb, metrics), window, total);
+        if share == 0 {
+            continue;
+        }
+        let used = tcb.window_ticks_used as u64;
+        let observed = match tcb.strategy {
+            Strategy::Cooperative if tcb.window_yields == 0 && used > RECLASSIFY_CPU_FACTOR * share => {
+                Strategy::Selfish
+            }
+            Strategy::Selfish if tcb.window_yields >= RECLASSIFY_YIELD_COUNT && used <= share => {
+                Strategy::Cooperative
+            }
+            _ => continue,
+        };
+        tcb.strategy = observed;
+        tcb.payoff.decline_streak = 0;
+        tcb.payoff.reclassifications += 1;
+        reclassified |= 1 << i;
+    }
+    reclassified
+}
+
+/// Choose each tit-for-tat task's move for the next window by mirroring
+/// the window just evaluated: cooperate if at least half the tasks
+/// cooperated (the same line below which the defection penalty applies),
+/// defect otherwise.
+///
+/// Unlike `update_strategies`, this runs every evaluation, so a
+/// tit-for-tat task answers a shift in the system within one window.
+pub fn play_tit_for_tat(tasks: &mut [TaskControlBlock; MAX_TASKS], task_count: usize, metrics: &SystemMetrics) {
+    let cooperate = metrics.global_cooperation_ratio >= 50;
+    for tcb in tasks[..task_count].iter_mut() {
+        if tcb.is_player() && tcb.strategy == Strategy::TitForTat {
+            tcb.tft_cooperating = cooperate;
+        }
+    }
+}
+
+/// Force the lowest-payoff selfish task back to cooperation.
+///
+/// This is the mutual-defection circuit-breaker: an external intervention
+/// that breaks the all-defect corner of the Prisoner's Dilemma, which the
+/// payoff dynamics alone may never leave. Resets the chosen task's
+/// decline streak so hysteresis does not immediately undo the nudge.
+///
+/// # Returns
+/// The id of the task switched, or `None` if no active task is selfish
+/// and unpinned.
+pub fn nudge_to_cooperation(tasks: &mut [TaskControlBlock; MAX_TASKS], task_count: usize) -> Option<usize> {
+    let mut target: Option<usize> = None;
+    for i in 0..task_count {
+        if !tasks[i].is_player() || tasks[i].strategy_locked || tasks[i].strategy != Strategy::Selfish {
+            continue;
+        }
+        match target {
+            Some(t) if tasks[t].payoff.payoff <= tasks[i].payoff.payoff => {}
+            _ => target = Some(i),
+        }
     }
+
+    if let Some(t) = target {
+        tasks[t].strategy = Strategy::Cooperative;
+        tasks[t].payoff.decline_streak = 0;
+    }
+    target
 }
 
 // ---------------------------------------------------------------------------
@@ -269,13 +543,16 @@ pub fn update_strategies(tasks: &mut [TaskControlBlock; MAX_TASKS], task_count:
 /// | Cooperate | (3, 3)    | (0, 5) |
 /// | Defect    | (5, 0)    | (1, 1) |
 ///
+/// Tit-for-tat plays its opening move, cooperate.
+///
 /// Returns `(payoff_a, payoff_b)` scaled by 100.
 pub fn prisoners_dilemma_payoff(a: Strategy, b: Strategy) -> (i32, i32) {
-    match (a, b) {
-        (Strategy::Cooperative, Strategy::Cooperative) => (300, 300),
-        (Strategy::Cooperative, Strategy::Selfish) => (0, 500),
-        (Strategy::Selfish, Strategy::Cooperative) => (500, 0),
-        (Strategy::Selfish, Strategy::Selfish) => (100, 100),
+    let cooperates = |s: Strategy| s != Strategy::Selfish;
+    match (cooperates(a), cooperates(b)) {
+        (true, true) => (300, 300),
+        (true, false) => (0, 500),
+        (false, true) => (500, 0),
+        (false, false) => (100, 100),
     }
 }
 
@@ -286,7 +563,7 @@ pub fn prisoners_dilemma_payoff(a: Strategy, b: Strategy) -> (i32, i32) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::task::{TaskConfig, TaskState};
+    use crate::task::TaskConfig;
 
     fn make_test_task(id: usize, strategy: Strategy, priority: u8) -> TaskControlBlock {
         let mut tcb = TaskControlBlock::empty();
@@ -296,6 +573,7 @@ mod tests {
             wcet_ticks: 20,
             affinity_mask: 0x01,
             time_slice: 10,
+            ..TaskConfig::DEFAULT
         };
         tcb.init(id, config, strategy);
         tcb
@@ -307,16 +585,19 @@ mod tests {
             active_tasks: 4,
             global_cooperation_ratio: 75,
             overload: false,
+            total_cycles: 0,
+            recent_ticks: 1000,
+            recent_cycles: 0,
         }
     }
 
     #[test]
     fn test_payoff_deadline_met() {
         let mut task = make_test_task(0, Strategy::Cooperative, 3);
-        task.payoff.deadlines_met = 5;
+        task.payoff.recent_deadlines_met = 5;
         let metrics = default_metrics();
 
-        let payoff = compute_payoff(&task, &metrics);
+        let payoff = compute_payoff(&task, &metrics, &PayoffConfig::DEFAULT);
         // Should include 5×100 = 500 for deadlines, plus cooperation multiplier, plus coop score
         assert!(payoff > 500, "Payoff should include deadline bonus: {}", payoff);
     }
@@ -324,10 +605,10 @@ mod tests {
     #[test]
     fn test_payoff_deadline_missed_penalty() {
         let mut task = make_test_task(0, Strategy::Cooperative, 3);
-        task.payoff.deadlines_missed = 3;
+        task.payoff.recent_deadlines_missed = 3;
         let metrics = default_metrics();
 
-        let payoff = compute_payoff(&task, &metrics);
+        let payoff = compute_payoff(&task, &metrics, &PayoffConfig::DEFAULT);
         // -600 from misses, mitigated by coop score
         assert!(payoff < 0, "Payoff should be negative for missed deadlines: {}", payoff);
     }
@@ -338,14 +619,87 @@ mod tests {
         task.payoff.consecutive_overruns = 5;
         let metrics = default_metrics();
 
-        let payoff = compute_payoff(&task, &metrics);
+        let payoff = compute_payoff(&task, &metrics, &PayoffConfig::DEFAULT);
         // -750 from overruns
         assert!(payoff < -500, "Overrun penalty should be severe: {}", payoff);
     }
 
+    #[test]
+    fn test_payoff_uses_configured_weights() {
+        let mut task = make_test_task(0, Strategy::Selfish, 3);
+        task.payoff.recent_deadlines_missed = 2;
+        task.payoff.recent_yields = 4;
+        let metrics = default_metrics();
+        let default = compute_payoff(&task, &metrics, &PayoffConfig::default());
+
+        let strict = PayoffConfig { deadline_missed: 1000, voluntary_yield: 0, ..PayoffConfig::DEFAULT };
+        let payoff = compute_payoff(&task, &metrics, &strict);
+        assert_eq!(payoff - default, -2 * (1000 - 200) - 4 * 50);
+    }
+
+    /// Weights the equal share by base priority (priority 0 counts as 1).
+    fn priority_weighted_share(task: &TaskControlBlock, metrics: &SystemMetrics) -> u64 {
+        equal_fair_share(task, metrics) * task.config.priority.max(1) as u64
+    }
+
+    #[test]
+    fn test_quantum_scale_clamps_at_extremes() {
+        assert_eq!(quantum_scale(0, 50, 200), 100);
+        assert_eq!(quantum_scale(10 * QUANTUM_PAYOFF_STEP, 50, 200), 110);
+        assert_eq!(quantum_scale(-10 * QUANTUM_PAYOFF_STEP, 50, 200), 90);
+        assert_eq!(quantum_scale(i32::MAX, 50, 200), 200);
+        assert_eq!(quantum_scale(i32::MIN, 50, 200), 50);
+        // Equal bounds pin the scale
+        assert_eq!(quantum_scale(i32::MAX, 100, 100), 100);
+        assert_eq!(quantum_scale(i32::MIN, 100, 100), 100);
+    }
+
+    #[test]
+    fn test_payoff_respects_custom_fair_share() {
+        let mut metrics = default_metrics();
+        metrics.total_cycles = 1000;
+        metrics.recent_cycles = 1000;
+        // Equal share is 250; this task uses 3× that
+        let mut task = make_test_task(0, Strategy::Cooperative, 3);
+        task.recent_ticks_used = 750;
+        task.recent_cycles_used = 750;
+
+        let equal = compute_payoff(&task, &metrics, &PayoffConfig::DEFAULT);
+        assert_eq!(equal, compute_payoff_with(&task, &metrics, &PayoffConfig::DEFAULT, equal_fair_share));
+
+        // Weighted by priority 3 the allotment is 750: no hog penalty
+        let weighted = compute_payoff_with(&task, &metrics, &PayoffConfig::DEFAULT, priority_weighted_share);
+        assert_eq!(weighted - equal, (300 - 200) * 2);
+
+        // A low-priority task at the same usage stays penalized
+        task.config.priority = 1;
+        let low = compute_payoff_with(&task, &metrics, &PayoffConfig::DEFAULT, priority_weighted_share);
+        assert_eq!(low, compute_payoff(&task, &metrics, &PayoffConfig::DEFAULT));
+    }
+
+    #[test]
+    fn test_payoff_fair_share_survives_long_uptime() {
+        // The same 25% of an equal share, at a small and at a huge scale
+        let at_scale = |shift: u32| {
+            let total = 1u64 << shift;
+            let metrics = SystemMetrics {
+                total_ticks: total,
+                total_cycles: total,
+                recent_ticks: total,
+                recent_cycles: total,
+                ..default_metrics()
+            };
+            let mut task = make_test_task(0, Strategy::Selfish, 3);
+            task.recent_ticks_used = 1 << (shift - 4);
+            task.recent_cycles_used = 1 << (shift - 4);
+            compute_payoff(&task, &metrics, &PayoffConfig::DEFAULT)
+        };
+        assert_eq!(at_scale(33), at_scale(12));
+    }
+
     #[test]
     fn test_equilibrium_detection() {
-        let mut tasks = [TaskControlBlock::empty(); MAX_TASKS];
+        let mut tasks = [TaskControlBlock::EMPTY; MAX_TASKS];
         let metrics = default_metrics();
 
         // Two cooperative tasks with similar payoffs
@@ -356,14 +710,14 @@ mod tests {
 
         // When payoffs are similar, should be in equilibrium
         // (switching strategy wouldn't significantly improve either)
-        let eq = is_in_equilibrium(&tasks, 2, &metrics);
+        let eq = is_in_equilibrium(&tasks, 2, &metrics, &PayoffConfig::DEFAULT);
         // This depends on the estimate — just verify it runs without panic
         let _ = eq;
     }
 
     #[test]
     fn test_strategy_update_hysteresis() {
-        let mut tasks = [TaskControlBlock::empty(); MAX_TASKS];
+        let mut tasks = [TaskControlBlock::EMPTY; MAX_TASKS];
         let metrics = default_metrics();
         tasks[0] = make_test_task(0, Strategy::Selfish, 3);
 
@@ -379,6 +733,182 @@ mod tests {
             "Task should switch from Selfish to Cooperative after sustained decline");
     }
 
+    #[test]
+    fn test_oscillating_strategy_locked_for_cooldown() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        let metrics = default_metrics();
+        tasks[0] = make_test_task(0, Strategy::Selfish, 3);
+
+        // Payoff declines under either strategy, so hysteresis lets the
+        // task flip every STRATEGY_HYSTERESIS updates
+        let declining_update = |tasks: &mut [TaskControlBlock; MAX_TASKS]| {
+            tasks[0].payoff.payoff -= 10;
+            update_strategies(tasks, 1, &metrics);
+        };
+
+        for _ in 0..STRATEGY_HYSTERESIS * (OSCILLATION_SWITCH_LIMIT + 1) {
+            declining_update(&mut tasks);
+        }
+        assert_eq!(tasks[0].payoff.strategy_lock, STRATEGY_LOCK_COOLDOWN);
+        let locked = tasks[0].strategy;
+
+        // Pinned for the whole cooldown despite continued decline
+        for _ in 0..STRATEGY_LOCK_COOLDOWN {
+            declining_update(&mut tasks);
+            assert_eq!(tasks[0].strategy, locked);
+        }
+        assert_eq!(tasks[0].payoff.strategy_lock, 0);
+
+        // Then adapts again under normal hysteresis
+        for _ in 0..STRATEGY_HYSTERESIS - 1 {
+            declining_update(&mut tasks);
+        }
+        assert_eq!(tasks[0].strategy, locked);
+        declining_update(&mut tasks);
+        assert_ne!(tasks[0].strategy, locked);
+    }
+
+    /// Metrics under which each of 4 tasks' fair share of a 100-tick
+    /// window is 25 ticks.
+    fn window_metrics() -> SystemMetrics {
+        SystemMetrics { total_cycles: 1000, ..default_metrics() }
+    }
+
+    #[test]
+    fn test_silent_cpu_hog_reclassified_selfish() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        for (id, tcb) in tasks.iter_mut().enumerate().take(4) {
+            *tcb = make_test_task(id, Strategy::Cooperative, 3);
+        }
+        // Over twice the share without a yield
+        tasks[0].window_ticks_used = 51;
+        // As much CPU, but it yielded
+        tasks[1].window_ticks_used = 60;
+        tasks[1].window_yields = 1;
+        // Silent, but within twice the share
+        tasks[2].window_ticks_used = 50;
+        // Pinned
+        tasks[3].window_ticks_used = 90;
+        tasks[3].strategy_locked = true;
+
+        let reclassified = reclassify_strategies(&mut tasks, 4, &window_metrics(), equal_fair_share, 100);
+        assert_eq!(reclassified, 1 << 0);
+        assert_eq!(tasks[0].strategy, Strategy::Selfish);
+        assert_eq!(tasks[0].payoff.reclassifications, 1);
+        for tcb in &tasks[1..4] {
+            assert_eq!(tcb.strategy, Strategy::Cooperative);
+            assert_eq!(tcb.payoff.reclassifications, 0);
+        }
+    }
+
+    #[test]
+    fn test_reclassification_survives_long_uptime() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        for (id, tcb) in tasks.iter_mut().enumerate().take(4) {
+            *tcb = make_test_task(id, Strategy::Cooperative, 3);
+        }
+        tasks[0].window_ticks_used = 51;
+        tasks[1].window_ticks_used = 50;
+        let metrics = SystemMetrics { total_ticks: 1 << 62, total_cycles: 1 << 62, ..default_metrics() };
+
+        // The window's share is still 25 ticks
+        let reclassified = reclassify_strategies(&mut tasks, 4, &metrics, equal_fair_share, 100);
+        assert_eq!(reclassified, 1 << 0);
+    }
+
+    #[test]
+    fn test_frequent_yielder_reclassified_cooperative() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        for (id, tcb) in tasks.iter_mut().enumerate().take(4) {
+            *tcb = make_test_task(id, Strategy::Selfish, 3);
+        }
+        tasks[0].window_yields = RECLASSIFY_YIELD_COUNT;
+        tasks[0].window_ticks_used = 25;
+        // Yields often but still takes more than its share
+        tasks[1].window_yields = RECLASSIFY_YIELD_COUNT;
+        tasks[1].window_ticks_used = 26;
+        // Within its share, but rarely yields
+        tasks[2].window_yields = RECLASSIFY_YIELD_COUNT - 1;
+        tasks[2].window_ticks_used = 5;
+        tasks[3] = make_test_task(3, Strategy::TitForTat, 3);
+        tasks[3].window_yields = 10;
+
+        let reclassified = reclassify_strategies(&mut tasks, 4, &window_metrics(), equal_fair_share, 100);
+        assert_eq!(reclassified, 1 << 0);
+        assert_eq!(tasks[0].strategy, Strategy::Cooperative);
+        assert_eq!(tasks[1].strategy, Strategy::Selfish);
+        assert_eq!(tasks[2].strategy, Strategy::Selfish);
+        assert_eq!(tasks[3].strategy, Strategy::TitForTat);
+    }
+
+    #[test]
+    fn test_nudge_picks_lowest_payoff_selfish() {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        tasks[0] = make_test_task(0, Strategy::Selfish, 3);
+        tasks[0].payoff.payoff = -50;
+        tasks[1] = make_test_task(1, Strategy::Selfish, 3);
+        tasks[1].payoff.payoff = -300;
+        tasks[2] = make_test_task(2, Strategy::Cooperative, 3);
+        tasks[2].payoff.payoff = -900;
+
+        assert_eq!(nudge_to_cooperation(&mut tasks, 3), Some(1));
+        assert_eq!(tasks[1].strategy, Strategy::Cooperative);
+        assert_eq!(tasks[0].strategy, Strategy::Selfish);
+    }
+
+    /// A tit-for-tat task at index 0 facing three tasks of `others`,
+    /// after one window in which it cooperated.
+    fn tit_for_tat_against(others: Strategy) -> ([TaskControlBlock; MAX_TASKS], SystemMetrics) {
+        let mut tasks: [TaskControlBlock; MAX_TASKS] = core::array::from_fn(|_| TaskControlBlock::empty());
+        tasks[0] = make_test_task(0, Strategy::TitForTat, 3);
+        for (i, tcb) in tasks.iter_mut().enumerate().take(4).skip(1) {
+            *tcb = make_test_task(i, others, 3);
+        }
+        let cooperating = tasks[..4].iter().filter(|t| t.is_cooperating()).count() as u32;
+        let metrics = SystemMetrics { global_cooperation_ratio: cooperating * 100 / 4, ..default_metrics() };
+        (tasks, metrics)
+    }
+
+    #[test]
+    fn test_tit_for_tat_defects_against_defectors() {
+        let (mut tasks, metrics) = tit_for_tat_against(Strategy::Selfish);
+        tasks[0].payoff.recent_deadlines_met = 3;
+        assert!(tasks[0].is_cooperating(), "tit-for-tat opens by cooperating");
+        let opening = compute_payoff(&tasks[0], &metrics, &PayoffConfig::DEFAULT);
+
+        play_tit_for_tat(&mut tasks, 4, &metrics);
+        assert!(!tasks[0].is_cooperating());
+        assert_eq!(tasks[0].strategy, Strategy::TitForTat);
+
+        // Defecting forfeits the cooperation multiplier
+        let defecting = compute_payoff(&tasks[0], &metrics, &PayoffConfig::DEFAULT);
+        assert!(defecting < opening, "{} vs {}", defecting, opening);
+
+        // Hysteresis never switches the strategy itself
+        for i in 0..STRATEGY_HYSTERESIS {
+            tasks[0].payoff.payoff = -100 * i as i32;
+            update_strategies(&mut tasks, 4, &metrics);
+        }
+        assert_eq!(tasks[0].strategy, Strategy::TitForTat);
+    }
+
+    #[test]
+    fn test_tit_for_tat_cooperates_with_cooperators() {
+        let (mut tasks, metrics) = tit_for_tat_against(Strategy::Cooperative);
+        tasks[0].tft_cooperating = false;
+
+        play_tit_for_tat(&mut tasks, 4, &metrics);
+        assert!(tasks[0].is_cooperating());
+
+        // Cooperating, it earns the same as a cooperative task
+        tasks[0].payoff.recent_deadlines_met = 3;
+        tasks[1].payoff.recent_deadlines_met = 3;
+        assert_eq!(
+            compute_payoff(&tasks[0], &metrics, &PayoffConfig::DEFAULT),
+            compute_payoff(&tasks[1], &metrics, &PayoffConfig::DEFAULT)
+        );
+    }
+
     #[test]
     fn test_prisoners_dilemma_encoding() {
         let (a, b) = prisoners_dilemma_payoff(Strategy::Cooperative, Strategy::Cooperative);
@@ -402,16 +932,16 @@ mod tests {
         let metrics = default_metrics();
 
         let mut coop_task = make_test_task(0, Strategy::Cooperative, 3);
-        coop_task.payoff.deadlines_met = 3;
-        coop_task.payoff.voluntary_yields = 5;
+        coop_task.payoff.recent_deadlines_met = 3;
+        coop_task.payoff.recent_yields = 5;
 
         let mut selfish_task = make_test_task(1, Strategy::Selfish, 3);
-        selfish_task.payoff.deadlines_met = 3;
-        selfish_task.payoff.voluntary_yields = 0;
+        selfish_task.payoff.recent_deadlines_met = 3;
+        selfish_task.payoff.recent_yields = 0;
         selfish_task.payoff.consecutive_overruns = 2;
 
-        let coop_payoff = compute_payoff(&coop_task, &metrics);
-        let selfish_payoff = compute_payoff(&selfish_task, &metrics);
+        let coop_payoff = compute_payoff(&coop_task, &metrics, &PayoffConfig::DEFAULT);
+        let selfish_payoff = compute_payoff(&selfish_task, &metrics, &PayoffConfig::DEFAULT);
 
         assert!(coop_payoff > selfish_payoff,
             "Cooperative task should have higher payoff than selfish: {} vs {}",
@@ -0,0 +1,172 @@
+//! # Scheduler Invariants
+//!
+//! Internal consistency checks for development builds. With the
+//! `debug-assertions` feature the scheduler verifies these after every
+//! `tick()` and `schedule()` and halts on the first violation; without it
+//! the checks are never called and compile out.
+//!
+//! On a violation the failing invariant's code is stored in
+//! [`LAST_VIOLATION`] before panicking, so it can be read from a debugger
+//! even with `panic-halt`.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::config::{MAX_CORES, MAX_TASKS};
+use crate::scheduler::Scheduler;
+use crate::task::{StackCheck, TaskState};
+
+/// Bounds of `PayoffMetrics::cooperation_score`.
+const COOPERATION_SCORE_MAX: i32 = 500;
+
+/// A violated scheduler invariant. The discriminant is the recorded code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Invariant {
+    /// `task_count` exceeds `MAX_TASKS`.
+    TaskCountOutOfRange = 1,
+    /// More tasks are in the Running state than there are cores.
+    MultipleRunning = 2,
+    /// A task that is no core's `current_task` is Running.
+    RunningNotCurrent = 3,
+    /// An active task's id does not match its slot.
+    TaskTableInconsistent = 4,
+    /// A cooperation score left its `0..=500` range.
+    PayoffOutOfBounds = 5,
+    /// A task's saved stack pointer lies outside its stack.
+    StackPointerOutOfBounds = 6,
+}
+
+/// Code of the last invariant violation, or 0 if none has occurred.
+pub static LAST_VIOLATION: AtomicU8 = AtomicU8::new(0);
+
+/// Check every invariant, returning the first one violated.
+pub fn check(sched: &Scheduler) -> Result<(), Invariant> {
+    if sched.task_count > MAX_TASKS {
+        return Err(Invariant::TaskCountOutOfRange);
+    }
+
+    let mut running = 0;
+    for (id, tcb) in sched.tasks[..sched.task_count].iter().enumerate() {
+        // Slots freed by delete_task() may sit between live ones
+        if !tcb.active {
+            continue;
+        }
+        if tcb.id != id {
+            return Err(Invariant::TaskTableInconsistent);
+        }
+
+        if tcb.state == TaskState::Running {
+            running += 1;
+            if running > MAX_CORES {
+                return Err(Invariant::MultipleRunning);
+            }
+            if !sched.current_task.contains(&id) {
+                return Err(Invariant::RunningNotCurrent);
+            }
+        }
+
+        let score = tcb.payoff.cooperation_score;
+        if !(0..=COOPERATION_SCORE_MAX).contains(&score) {
+            return Err(Invariant::PayoffOutOfBounds);
+        }
+
+        if !tcb.stack_pointer.is_null()
+            && tcb.check_stack(tcb.stack_pointer, StackCheck::BoundsCheck).is_err()
+        {
+            return Err(Invariant::StackPointerOutOfBounds);
+        }
+    }
+    Ok(())
+}
+
+/// Check every invariant and halt on a violation, recording its code in
+/// [`LAST_VIOLATION`].
+pub fn enforce(sched: &Scheduler) {
+    if let Err(violation) = check(sched) {
+        LAST_VIOLATION.store(violation as u8, Ordering::Relaxed);
+        panic!("scheduler invariant violated: {:?}", violation);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Strategy, TaskConfig, TaskEntry};
+    #[cfg(feature = "stack-pool")]
+    use crate::config::STACK_SIZE;
+    #[cfg(feature = "stack-pool")]
+    use crate::stack_pool::tests::leaked_region;
+
+    extern "C" fn spin() -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    const TEST_ENTRY: TaskEntry = TaskEntry::new(spin);
+
+    /// Create two equal tasks and start one. Tasks are created in place:
+    /// moving a scheduler moves their stacks.
+    fn start_two_tasks(sched: &mut Scheduler) {
+        #[cfg(feature = "stack-pool")]
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        let config = TaskConfig { priority: 3, ..TaskConfig::DEFAULT };
+        sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.schedule();
+    }
+
+    #[test]
+    fn test_consistent_scheduler_passes() {
+        let mut sched = Scheduler::new();
+        start_two_tasks(&mut sched);
+        for _ in 0..50 {
+            sched.tick();
+            if sched.needs_reschedule {
+                sched.schedule();
+            }
+            assert_eq!(check(&sched), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_violations_detected() {
+        let mut sched = Scheduler::new();
+        start_two_tasks(&mut sched);
+        let other = 1 - sched.current();
+
+        sched.tasks[other].state = TaskState::Running;
+        // Two Running tasks fit on two cores
+        #[cfg(not(feature = "dual-core"))]
+        assert_eq!(check(&sched), Err(Invariant::MultipleRunning));
+        sched.tasks[sched.current()].state = TaskState::Ready;
+        assert_eq!(check(&sched), Err(Invariant::RunningNotCurrent));
+        sched.tasks[other].state = TaskState::Ready;
+
+        sched.tasks[other].payoff.cooperation_score = 501;
+        assert_eq!(check(&sched), Err(Invariant::PayoffOutOfBounds));
+        sched.tasks[other].payoff.cooperation_score = 100;
+
+        let sp = sched.tasks[other].stack_pointer;
+        sched.tasks[other].stack_pointer = sched.tasks[sched.current()].stack_pointer;
+        assert_eq!(check(&sched), Err(Invariant::StackPointerOutOfBounds));
+        sched.tasks[other].stack_pointer = sp;
+
+        assert_eq!(check(&sched), Ok(()));
+    }
+
+    #[cfg(feature = "debug-assertions")]
+    #[test]
+    #[should_panic(expected = "scheduler invariant violated")]
+    fn test_assertion_fires_in_debug_configuration() {
+        let mut sched = Scheduler::new();
+        start_two_tasks(&mut sched);
+        let other = 1 - sched.current();
+        sched.tasks[other].state = TaskState::Running;
+        sched.tick();
+    }
+}
@@ -11,18 +11,34 @@
 //! ```text
 //! reset_handler (cortex-m-rt)
 //!   └─► main()
-//!         ├─► kernel::init()        ← Configure peripherals
+//!         ├─► kernel::init()        ← Configure peripherals, idle task
 //!         ├─► kernel::create_task() ← Register tasks (×N)
 //!         └─► kernel::start()       ← Launch scheduler (no return)
-//!               ├─► Configure SysTick
+//!               ├─► Configure SysTick (or a custom TickSource)
 //!               ├─► Set interrupt priorities
-//!               └─► Start first task via arch::start_first_task()
+//!               └─► Start first task via Arch::start_first_task()
 //! ```
 
-use crate::arch::cortex_m4;
-use crate::scheduler::Scheduler;
-use crate::task::{TaskConfig, Strategy};
-use crate::sync;
+use core::time::Duration;
+
+use crate::analysis;
+use crate::game::{FairSharePolicy, PayoffConfig};
+use crate::snapshot;
+use crate::arch::{Arch, Port};
+use crate::arch::tick::{CompareTimer, TickSource};
+use crate::scheduler::{
+    Scheduler, SchedPolicy, TieBreak, OverrunPolicy, DegradePolicy, SwitchCause, BoostCurve,
+    PriorityBreakdown, SystemStats, TaskInfo,
+};
+use crate::task::{
+    duration_to_ticks, TaskConfig, TaskControlBlock, TaskEntry, Strategy, WakeReason, WaitResult,
+    StackCheck, StackFault, BlockReason, PeriodicRelease,
+};
+use crate::sync::{self, EventGroup};
+use crate::syscall::Syscall;
+use crate::error::KernelError;
+#[cfg(feature = "stack-pool")]
+use crate::config::IDLE_STACK_SIZE;
 
 // ---------------------------------------------------------------------------
 // Global scheduler instance
@@ -44,6 +60,37 @@ static mut SCHEDULER: Scheduler = Scheduler::new();
 #[no_mangle]
 pub static mut SCHEDULER_PTR: *mut Scheduler = core::ptr::null_mut();
 
+/// Compare timer used to enforce hard deadlines, set by
+/// `set_deadline_timer()`.
+///
+/// # Safety
+/// Only touched from the tick and compare interrupts (same priority) or
+/// inside a critical section.
+static mut DEADLINE_TIMER: Option<&'static mut dyn CompareTimer> = None;
+
+/// Absolute tick `DEADLINE_TIMER` is currently armed for.
+static mut DEADLINE_ARMED_AT: Option<u64> = None;
+
+/// Copy of the scheduler's `tick_count`, published after every change so
+/// [`ticks`] can read it from any context without a critical section.
+static TICKS: sync::SeqU64 = sync::SeqU64::new(0);
+
+/// Stack of the idle task when stacks come from the pool. `u64` elements
+/// keep it 8-byte aligned.
+#[cfg(feature = "stack-pool")]
+static mut IDLE_STACK: [u64; IDLE_STACK_SIZE / 8] = [0; IDLE_STACK_SIZE / 8];
+
+/// The global scheduler, or `KernelError::NotInitialized` before `init()`.
+///
+/// # Safety
+/// Call inside a critical section, like any access through `SCHEDULER_PTR`.
+unsafe fn scheduler() -> Result<&'static mut Scheduler, KernelError> {
+    if SCHEDULER_PTR.is_null() {
+        return Err(KernelError::NotInitialized);
+    }
+    Ok(&mut *SCHEDULER_PTR)
+}
+
 // ---------------------------------------------------------------------------
 // Kernel API
 // ---------------------------------------------------------------------------
@@ -51,7 +98,8 @@ pub static mut SCHEDULER_PTR: *mut Scheduler = core::ptr::null_mut();
 /// Initialize the EqOS kernel.
 ///
 /// Must be called before any other kernel function. Sets up the global
-/// scheduler and its pointer for ISR access.
+/// scheduler and its pointer for ISR access, and creates the idle task
+/// (so application task ids start at 1).
 ///
 /// # Safety
 /// Must be called exactly once, from the main thread, before starting
@@ -59,20 +107,46 @@ pub static mut SCHEDULER_PTR: *mut Scheduler = core::ptr::null_mut();
 pub fn init() {
     unsafe {
         SCHEDULER = Scheduler::new();
-        SCHEDULER_PTR = &mut SCHEDULER as *mut Scheduler;
+        SCHEDULER_PTR = core::ptr::addr_of_mut!(SCHEDULER);
+
+        // The idle task takes slot 0, which keeps this stack instead of
+        // carving one from the (not yet provided) pool
+        #[cfg(feature = "stack-pool")]
+        {
+            (*SCHEDULER_PTR).tasks[0].stack = (core::ptr::addr_of_mut!(IDLE_STACK) as *mut u8, IDLE_STACK_SIZE);
+        }
+        (*SCHEDULER_PTR)
+            .create_idle_task(TaskEntry::new(idle_task))
+            .expect("idle task creation cannot fail on an empty scheduler");
+    }
+}
+
+/// Body of the idle task: run the idle hook, then sleep until the next
+/// interrupt.
+extern "C" fn idle_task() -> ! {
+    loop {
+        let hook = sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).idle_hook });
+        if let Some(hook) = hook {
+            hook();
+        }
+        cortex_m::asm::wfi();
     }
 }
 
 /// Create a new task and register it with the scheduler.
 ///
 /// # Parameters
-/// - `entry`: Task entry function. Must be `extern "C" fn() -> !` (never returns).
+/// - `entry`: Task entry point, built with `task_entry!` from an
+///   `extern "C" fn() -> !` (never returns).
 /// - `config`: Static task configuration (priority, deadline, WCET, etc.).
 /// - `strategy`: Initial game-theory strategy (Cooperative or Selfish).
 ///
 /// # Returns
 /// - `Ok(task_id)`: The task's index in the scheduler array.
-/// - `Err(())`: The task array is full (`MAX_TASKS` reached).
+/// - `Err(KernelError::NotInitialized)`: `init()` has not been called.
+/// - `Err(KernelError::TooManyTasks)`: The task array is full (`MAX_TASKS` reached).
+/// - `Err(KernelError::InvalidPriority)`: `config.priority` is not below `PRIORITY_LEVELS`.
+/// - `Err(KernelError::InvalidConfig)`: `config` fails `TaskConfig::validate()`.
 ///
 /// # Example
 /// ```ignore
@@ -82,16 +156,191 @@ pub fn init() {
 ///     wcet_ticks: 20,
 ///     affinity_mask: 0x01,
 ///     time_slice: 10,
+///     ..TaskConfig::DEFAULT
 /// };
-/// kernel::create_task(my_task_fn, config, Strategy::Cooperative).unwrap();
+/// kernel::create_task(task_entry!(my_task_fn), config, Strategy::Cooperative).unwrap();
 /// ```
 pub fn create_task(
-    entry: extern "C" fn() -> !,
+    entry: TaskEntry,
     config: TaskConfig,
     strategy: Strategy,
-) -> Result<usize, ()> {
+) -> Result<usize, KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.create_task(entry, config, strategy)
+    })
+}
+
+/// Create a batch of tasks atomically, within a single critical section.
+///
+/// Either every task is created and their ids are returned in order, or
+/// none are (tasks created before a failure are rolled back).
+///
+/// # Example
+/// ```ignore
+/// let [producer, consumer] = kernel::create_tasks(&[
+///     (task_entry!(producer_fn), producer_cfg, Strategy::Cooperative),
+///     (task_entry!(consumer_fn), consumer_cfg, Strategy::Cooperative),
+/// ])?;
+/// ```
+pub fn create_tasks<const N: usize>(
+    specs: &[(TaskEntry, TaskConfig, Strategy); N],
+) -> Result<[usize; N], KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.create_tasks(specs)
+    })
+}
+
+/// Build the spec array `create_tasks()` takes from a concise per-task
+/// list: the entry function, its initial strategy, and any `TaskConfig`
+/// fields to set. Omitted fields come from `TaskConfig::DEFAULT`.
+///
+/// Fails to compile if the list names more than `MAX_TASKS` tasks.
+///
+/// ```ignore
+/// let specs = task_specs! {
+///     sensor_task: Cooperative { priority: 3, deadline_ticks: 100 },
+///     logger_task: Selfish { priority: 1 },
+/// };
+/// ```
+#[macro_export]
+macro_rules! task_specs {
+    ($($entry:path : $strategy:ident { $($field:ident : $value:expr),* $(,)? }),+ $(,)?) => {{
+        const _: () = assert!(
+            [$(stringify!($entry)),+].len() <= $crate::config::MAX_TASKS,
+            "more tasks than MAX_TASKS",
+        );
+        [$((
+            $crate::task::TaskEntry::new($entry),
+            $crate::task::TaskConfig { $($field: $value,)* ..$crate::task::TaskConfig::DEFAULT },
+            $crate::task::Strategy::$strategy,
+        )),+]
+    }};
+}
+
+/// Declare and create a set of tasks in one batch; see [`task_specs!`]
+/// for the syntax. Expands to a `create_tasks()` call, so the tasks are
+/// created atomically and their ids returned in order.
+///
+/// ```ignore
+/// let [sensor, logger] = define_tasks! {
+///     sensor_task: Cooperative { priority: 3, deadline_ticks: 100 },
+///     logger_task: Selfish { priority: 1 },
+/// }
+/// .expect("Failed to create tasks");
+/// ```
+///
+/// Use `create_task()` directly when a task's configuration is computed
+/// at runtime or tasks must be created one at a time.
+#[macro_export]
+macro_rules! define_tasks {
+    ($($spec:tt)+) => {
+        $crate::kernel::create_tasks(&$crate::task_specs!($($spec)+))
+    };
+}
+
+/// Delete task `id`, freeing its slot for a later `create_task()`.
+///
+/// A task may delete itself: the call then switches away and never
+/// returns into the deleted context.
+pub fn delete_task(id: usize) -> Result<(), KernelError> {
+    let deleted_self = sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        let deleted_self = id == scheduler.current();
+        scheduler.delete_task(id).map(|()| deleted_self)
+    })?;
+    if deleted_self {
+        Port::trigger_context_switch();
+        // PendSV switches away before this is reached
+        loop {
+            cortex_m::asm::wfi();
+        }
+    }
+    Ok(())
+}
+
+/// Replace a task's entry function for hot-reload.
+///
+/// The new entry only takes effect on the next `restart_task()`; it does
+/// not redirect code that is already executing.
+pub fn set_entry(id: usize, entry: TaskEntry) -> Result<(), KernelError> {
     sync::critical_section(|_cs| unsafe {
-        (*SCHEDULER_PTR).create_task(entry, config, strategy)
+        scheduler()?.set_entry(id, entry)
+    })
+}
+
+/// Change one task's base priority, rescheduling immediately.
+pub fn set_priority(id: usize, priority: u8) -> Result<(), KernelError> {
+    set_priorities(&[(id, priority)])
+}
+
+/// Change several task priorities at once, e.g. for a mode change.
+///
+/// All changes are applied inside one critical section, so the scheduler
+/// never runs on a half-applied set, followed by a single reschedule. If
+/// any entry is invalid, nothing changes.
+pub fn set_priorities(changes: &[(usize, u8)]) -> Result<(), KernelError> {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        scheduler.set_priorities(changes).map(|()| scheduler.needs_reschedule)
+    })?;
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+    Ok(())
+}
+
+/// Restart a task from its entry function with fresh metrics and stack.
+///
+/// Must be called from a different task (or an ISR) than the one being
+/// restarted.
+pub fn restart_task(id: usize) -> Result<(), KernelError> {
+    let result = sync::critical_section(|_cs| unsafe {
+        scheduler()?.restart_task(id)
+    });
+    if result.is_ok() {
+        Port::trigger_context_switch();
+    }
+    result
+}
+
+/// Total theoretical CPU utilization of the created periodic tasks (×100).
+///
+/// Sums `wcet_ticks / deadline_ticks` over every task with both set.
+/// Callable before `start()` to check a configuration.
+pub fn total_utilization() -> u32 {
+    sync::critical_section(|_cs| unsafe {
+        let scheduler = &*SCHEDULER_PTR;
+        analysis::total_utilization(&scheduler.tasks, scheduler.task_count)
+    })
+}
+
+/// Check the created periodic tasks against the Liu–Layland bound for
+/// rate-monotonic scheduling.
+pub fn is_rm_schedulable() -> bool {
+    sync::critical_section(|_cs| unsafe {
+        let scheduler = &*SCHEDULER_PTR;
+        analysis::is_rm_schedulable(&scheduler.tasks, scheduler.task_count)
+    })
+}
+
+/// Check the anchor tasks alone against the Liu–Layland bound: the load
+/// that must be met even when everything else is shed.
+pub fn is_mandatory_schedulable() -> bool {
+    sync::critical_section(|_cs| unsafe {
+        let scheduler = &*SCHEDULER_PTR;
+        analysis::is_mandatory_schedulable(&scheduler.tasks, scheduler.task_count)
+    })
+}
+
+/// Check the whole created task set at once: utilization, the
+/// Liu–Layland verdict, tasks whose WCET exceeds their deadline, tasks
+/// with no allowed core, and priorities that contradict deadline order.
+/// Call after creating every task and before `start()`; see
+/// `SchedulabilityReport::is_ok()` for a single go/no-go.
+pub fn validate_schedulability() -> analysis::SchedulabilityReport {
+    sync::critical_section(|_cs| unsafe {
+        let scheduler = &*SCHEDULER_PTR;
+        analysis::validate_schedulability(&scheduler.tasks, scheduler.task_count)
     })
 }
 
@@ -103,50 +352,1196 @@ pub fn create_task(
 ///
 /// # Safety
 /// - `init()` must have been called.
-/// - At least one task must have been created.
 /// - Must be called from the main thread (not from an ISR).
 ///
-/// # Panics
-/// Loops forever if no tasks have been created (does not panic,
-/// as panic infrastructure is minimal in no_std).
+/// If no task has been created, the no-tasks hook runs instead (see
+/// [`set_no_tasks_hook`]); by default it halts at a breakpoint.
 pub fn start(mut core_peripherals: cortex_m::Peripherals) -> ! {
-    // Configure SysTick timer
-    cortex_m4::configure_systick(&mut core_peripherals.SYST);
+    start_with_tick_source(&mut core_peripherals.SYST)
+}
+
+/// Start the EqOS scheduler driven by `source` instead of SysTick.
+/// **Does not return.**
+///
+/// The source is started at the scheduler's configured tick rate. Its
+/// interrupt handler must call `arch::cortex_m4::scheduler_tick()` (or
+/// `CountDownTick::on_interrupt()` for `embedded-hal` timers).
+///
+/// Same preconditions as [`start`].
+pub fn start_with_tick_source<T: TickSource>(source: &mut T) -> ! {
+    // Configure the tick timer
+    let tick_hz = sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).tick_hz });
+    source.start(tick_hz);
 
     // Set PendSV and SysTick to lowest priority
-    cortex_m4::set_interrupt_priorities();
+    Port::set_priorities();
+
+    #[cfg(feature = "mpu")]
+    unsafe {
+        crate::arch::mpu::enable();
+    }
+
+    #[cfg(feature = "cycle-accounting")]
+    crate::arch::cortex_m4::enable_cycle_counter();
+
+    #[cfg(feature = "fpu")]
+    crate::arch::cortex_m4::enable_lazy_fpu_stacking();
 
     // Get the first task's stack pointer and launch
     let first_sp = sync::critical_section(|_cs| unsafe {
         let scheduler = &mut *SCHEDULER_PTR;
-        if scheduler.task_count == 0 {
-            // No tasks — spin forever
-            loop {
-                cortex_m::asm::wfi();
-            }
-        }
-        // Schedule the first task
-        let first = scheduler.schedule();
+        // Schedule the first task (diverges into the no-tasks hook if none)
+        let first = scheduler.start_first();
+
+        #[cfg(feature = "cycle-accounting")]
+        scheduler.account_cycles(cortex_m::peripheral::DWT::cycle_count());
+
+        #[cfg(feature = "mpu")]
+        crate::arch::mpu::configure_task(&scheduler.tasks[first]);
+
         scheduler.tasks[first].stack_pointer as *const u32
     });
 
     unsafe {
-        cortex_m4::start_first_task(first_sp);
+        Port::start_first_task(first_sp);
     }
 }
 
+/// Replace what happens when a context switch finds a task's stack
+/// corrupted (see [`set_stack_check`]). The hook receives the task id and
+/// fault and must not return; the default panics. It runs in PendSV with
+/// the scheduler borrowed, so it may log or reset but not call the kernel.
+pub fn set_stack_overflow_hook(hook: fn(usize, StackFault) -> !) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_stack_overflow_hook(hook);
+    });
+}
+
+/// Replace what `start()` does when no task has been created. The default
+/// executes a breakpoint (visible in an attached debugger) and then
+/// sleeps forever; a hook might instead blink an error LED or reset.
+/// Call before `start()`.
+pub fn set_no_tasks_hook(hook: fn() -> !) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_no_tasks_hook(hook);
+    });
+}
+
 /// Voluntarily yield the CPU from the current task.
 ///
 /// This is the primary cooperative mechanism. Calling this function:
 /// 1. Records a voluntary yield in the task's payoff metrics
-/// 2. Resets the task's time slice
-/// 3. Triggers a reschedule via PendSV
+/// 2. Completes the current job of a task with a deadline (see
+///    [`job_complete`]), so a yield inside the window counts as met
+/// 3. Resets the task's time slice
+/// 4. Triggers a reschedule via PendSV
+///
+/// The scheduler is entered through `svc #0`, so this must be called from
+/// a task with interrupts enabled, never from an ISR or a critical section.
 ///
 /// Tasks that yield frequently receive cooperation bonuses, improving
-/// their effective scheduling priority over time.
+/// their effective scheduling priority over time. Under
+/// [`preempt_disable`] the yield is deferred to the final
+/// [`preempt_enable`].
 pub fn yield_task() {
+    Port::syscall(Syscall::Yield, 0);
+}
+
+/// Yield to task `id`, which runs next regardless of priority or payoff
+/// if it is Ready and may run on this core; otherwise this is a plain
+/// [`yield_task`]. Only this one switch is affected. Like `yield_task()`
+/// it counts as a voluntary yield and enters the kernel through `svc #2`.
+///
+/// Returns `KernelError::InvalidTaskId`, without yielding, if `id` is not
+/// an allocated task.
+pub fn yield_to(id: usize) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.task(id).map(|_| ()).ok_or(KernelError::InvalidTaskId)
+    })?;
+    Port::syscall(Syscall::YieldTo, id as u32);
+    Ok(())
+}
+
+/// Prevent context switches without disabling interrupts, for a short
+/// sequence that must not be preempted by another task. Ticks, timers
+/// and ISRs keep running; any reschedule they request (or a
+/// `yield_task()`) is deferred until the matching `preempt_enable()`.
+/// Calls nest.
+///
+/// The current task must not block while holding the lock.
+pub fn preempt_disable() {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).preempt_disable();
+    });
+}
+
+/// Release one level of `preempt_disable()`. Releasing the last one
+/// performs any reschedule deferred in the meantime.
+pub fn preempt_enable() {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).preempt_enable()
+    });
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+}
+
+/// Collect the ids of tasks that have run for more than
+/// `NON_YIELD_THRESHOLD` ticks without a voluntary yield.
+///
+/// Fills `out` and returns the number of ids written. Useful for spotting
+/// CPU hogs directly rather than inferring them from payoff.
+pub fn non_yielding_tasks(out: &mut [usize]) -> usize {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).non_yielding_tasks(out)
+    })
+}
+
+/// Collect the ids of all tasks currently blocked for `reason`, e.g. every
+/// task waiting on its predecessor.
+///
+/// Fills `out` and returns the number of ids written. Gives a
+/// resource-centric view of contention for diagnosing deadlocks and
+/// missing signals.
+pub fn waiters_on(reason: BlockReason, out: &mut [usize]) -> usize {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).waiters_on(reason, out)
+    })
+}
+
+/// Ticks left in the current task's time slice.
+///
+/// Lets a cooperative task decide whether to start another unit of work
+/// or yield now rather than risk preemption mid-operation.
+pub fn remaining_slice() -> u32 {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).remaining_slice()
+    })
+}
+
+/// Recompute payoffs and check equilibrium on every tick for the next
+/// `windows` evaluation windows, then revert to the normal
+/// `EVAL_FREQUENCY` interval; strategy updates and window history stay on
+/// the normal interval. A bounded
+/// burst of responsiveness after a transient (task set or mode change,
+/// deadline-miss storm).
+pub fn request_dense_evaluation(windows: u32) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).request_dense_evaluation(windows);
+    });
+}
+
+/// Run the game evaluation now instead of at the next `EVAL_FREQUENCY`
+/// boundary. Never preempts the caller under `cooperative-only`.
+pub fn evaluate_now() {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = &mut *SCHEDULER_PTR;
+        scheduler.evaluate_now();
+        scheduler.needs_reschedule
+    });
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+}
+
+/// Report that the calling periodic task has finished this period's job.
+///
+/// Records the deadline as met and the completion time for jitter
+/// tracking. Jobs still running at their deadline are counted as missed.
+/// Yielding or sleeping until the next release with `delay_until` also
+/// completes the job.
+pub fn job_complete() {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).job_complete();
+    });
+}
+
+/// Earliest and latest job completion relative to the deadline, in ticks
+/// (negative = early, positive = late), since the last reset. `None` for
+/// an invalid id or before any job completed.
+pub fn deadline_jitter(id: usize) -> Option<(i32, i32)> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).deadline_jitter(id)
+    })
+}
+
+/// Change a periodic task's period, deadline and WCET at runtime.
+///
+/// The deadline doubles as the period: `deadline_ticks` is used if
+/// non-zero, otherwise `period_ticks`. The task's next period starts now,
+/// without recording a miss for the one in progress, and a task held by
+/// deadline enforcement is released.
+///
+/// # Returns
+/// - `Err(KernelError::InvalidTaskId)` — `id` is not an allocated task
+/// - `Err(KernelError::InvalidConfig)` — a period different from a
+///   non-zero deadline, neither given, or a WCET over the deadline;
+///   nothing is changed
+/// - `Err(KernelError::Unschedulable)` — total periodic utilization would
+///   exceed 100%; nothing is changed
+pub fn set_timing(
+    id: usize,
+    period_ticks: u32,
+    deadline_ticks: u32,
+    wcet_ticks: u32,
+) -> Result<(), KernelError> {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        scheduler
+            .set_timing(id, period_ticks, deadline_ticks, wcet_ticks)
+            .map(|()| scheduler.needs_reschedule)
+    })?;
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+    Ok(())
+}
+
+/// Absolute tick of a task's upcoming deadline, or `None` for a task
+/// without one.
+pub fn next_deadline(id: usize) -> Option<u64> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).next_deadline(id)
+    })
+}
+
+/// Clear a task's recorded jitter range to start a new measurement window.
+pub fn reset_deadline_jitter(id: usize) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.reset_deadline_jitter(id)
+    })
+}
+
+/// Freeze the whole system for debugging: stops SysTick and drops any
+/// further scheduler ticks. Tasks, metrics, and timeouts stay exactly as
+/// they are until `resume()`.
+pub fn pause() {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).pause();
+    });
+    Port::set_tick_enabled(false);
+}
+
+/// Resume ticking after `pause()`.
+pub fn resume() {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).resume();
+    });
+    Port::set_tick_enabled(true);
+}
+
+/// Advance the scheduler by exactly one tick while paused, switching
+/// tasks if that tick calls for it.
+pub fn single_tick() {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = &mut *SCHEDULER_PTR;
+        scheduler.single_tick();
+        publish_ticks(scheduler.tick_count);
+        scheduler.needs_reschedule
+    });
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+}
+
+/// Change the scheduler tick rate at runtime, e.g. to 100 Hz while the
+/// system is quiet and back up under load.
+///
+/// Reprograms SysTick and translates pending delays, deadlines, time
+/// slices and timers so they keep their wall-clock length (see
+/// `Scheduler::set_tick_hz`). Values read afterwards, such as
+/// [`tick_count`] deltas, are in ticks of the new rate. A custom
+/// `TickSource` passed to [`start_with_tick_source`] must be restarted at
+/// `hz` by the caller instead.
+///
+/// Returns `KernelError::InvalidConfig`, changing nothing, if the SysTick
+/// reload for `hz` does not fit in 24 bits at `SYSTEM_CLOCK_HZ`.
+pub fn set_tick_hz(hz: u32) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        Port::configure_tick(hz)?;
+        scheduler.set_tick_hz(hz)?;
+        rearm_deadline_timer();
+        Ok(())
+    })
+}
+
+/// Hand deferred work from an ISR to bottom-half task `id`.
+///
+/// Keeps the ISR minimal: a handler blocked in `wait_for_deferral()` is
+/// made Ready and, if it outranks the interrupted task, switched to as
+/// soon as the ISR returns. Deferrals made while the handler is busy are
+/// counted, not lost.
+pub fn defer_to(id: usize) -> Result<(), KernelError> {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        scheduler.defer_to(id).map(|()| scheduler.needs_reschedule)
+    })?;
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+    Ok(())
+}
+
+/// Wake task `id` from an ISR and switch to it on ISR exit if it is then
+/// the highest-priority runnable task, e.g. a DMA-complete handler
+/// waking the consumer of the buffer.
+///
+/// Callable from ISR context: it only marks the task Ready and, when the
+/// task outranks the interrupted one, pends PendSV, which runs as soon as
+/// the ISR (and any other pending interrupt) returns.
+pub fn isr_wake_and_switch(id: usize) -> Result<(), KernelError> {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        scheduler.wake_and_switch(id).map(|()| scheduler.needs_reschedule)
+    })?;
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+    Ok(())
+}
+
+/// Block the calling handler task until deferred work arrives via
+/// `defer_to()`, or for at most `timeout` ticks. Returns immediately if a
+/// deferral is already pending.
+///
+/// Returns `WaitResult::TimedOut` if the timeout expired first; each such
+/// timeout is counted in [`timeout_count`].
+pub fn wait_for_deferral(timeout: Option<u32>) -> WaitResult {
+    let ready = sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).wait_for_deferral(timeout)
+    });
+    if ready {
+        return WaitResult::Ok;
+    }
+    Port::trigger_context_switch();
+
+    // Resumed here once woken
+    let reason = sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).current_tcb().wake_reason
+    });
+    match reason {
+        WakeReason::Timeout => WaitResult::TimedOut,
+        _ => WaitResult::Ok,
+    }
+}
+
+/// Notify task `id` from an ISR, e.g. a UART RX handler waking the task
+/// that drains the receive buffer. A task blocked in
+/// [`wait_notification`] is made Ready and, if it outranks the
+/// interrupted task, switched to as soon as the ISR returns.
+///
+/// Needs no closure critical section: ISRs are already serialized by
+/// priority, and the short `sync::enter()` guard taken here only matters
+/// when the calling ISR can preempt SysTick or PendSV. Either ordering
+/// works:
+/// - notify before wait: the notification is stored and the next
+///   `wait_notification()` returns it without blocking;
+/// - wait before notify: the task is blocked, and this call wakes it.
+///
+/// Data the ISR writes before this call is visible to the task once its
+/// `wait_notification()` returns.
+pub fn notify_from_isr(id: usize) -> Result<(), KernelError> {
+    let _guard = sync::enter();
+    let scheduler = unsafe { scheduler()? };
+    let result = scheduler.notify(id);
+    if scheduler.needs_reschedule {
+        Port::trigger_context_switch();
+    }
+    result
+}
+
+/// Block the calling task until it is notified with [`notify_from_isr`],
+/// or for at most `timeout` ticks, returning at once if a notification is
+/// already pending.
+///
+/// Returns `WaitResult::Ok` and the number of notifications received
+/// since the last call (at least 1), clearing it, so a burst of
+/// interrupts is taken in one go. Lighter than a queue when no payload is
+/// needed. Returns `WaitResult::TimedOut` and 0 if the timeout expired
+/// first; each such timeout is counted in [`timeout_count`].
+pub fn wait_notification(timeout: Option<u32>) -> (WaitResult, u32) {
+    loop {
+        let taken = sync::critical_section(|_cs| unsafe {
+            (*SCHEDULER_PTR).wait_notification(timeout)
+        });
+        if let Some(count) = taken {
+            return (WaitResult::Ok, count);
+        }
+        // Blocked: runs again here once notified or timed out
+        Port::trigger_context_switch();
+        let reason = sync::critical_section(|_cs| unsafe {
+            (*SCHEDULER_PTR).current_tcb().wake_reason
+        });
+        if reason == WakeReason::Timeout {
+            return (WaitResult::TimedOut, 0);
+        }
+    }
+}
+
+/// Set the bits in `mask` in `group`, waking every task whose
+/// [`wait_event_bits`] is now satisfied; if one outranks the running
+/// task, it is switched to as soon as the caller (task or ISR) lets
+/// PendSV run. Safe from an ISR, like [`notify_from_isr`].
+///
+/// Returns the group's bits afterwards, or `KernelError::NotInitialized`
+/// before `init()`.
+pub fn set_event_bits(group: &EventGroup, mask: u32) -> Result<u32, KernelError> {
+    let _guard = sync::enter();
+    let scheduler = unsafe { scheduler()? };
+    let bits = group.set_bits(scheduler, mask);
+    if scheduler.needs_reschedule {
+        Port::trigger_context_switch();
+    }
+    Ok(bits)
+}
+
+/// Block the calling task until any bit in `mask` is set in `group`, or
+/// with `wait_all` every bit in it, for at most `timeout` ticks, returning
+/// at once if that already holds. With `clear_on_exit` the bits in `mask`
+/// are cleared as the wait completes.
+///
+/// Returns `WaitResult::Ok` and the group's bits at the moment the wait
+/// was satisfied, or `WaitResult::TimedOut` and the bits then if the
+/// timeout expired first; each such timeout is counted in
+/// [`timeout_count`].
+pub fn wait_event_bits(
+    group: &EventGroup,
+    mask: u32,
+    wait_all: bool,
+    clear_on_exit: bool,
+    timeout: Option<u32>,
+) -> (WaitResult, u32) {
+    loop {
+        let outcome = sync::critical_section(|_cs| unsafe {
+            group.wait_bits(&mut *SCHEDULER_PTR, mask, wait_all, clear_on_exit, timeout)
+        });
+        if let Some(outcome) = outcome {
+            return outcome;
+        }
+        // Blocked: runs again here once the bits are set
+        Port::trigger_context_switch();
+    }
+}
+
+/// Number of event waits by task `id` that timed out, or `None` for an
+/// invalid id. A climbing count usually points at a protocol bug: the
+/// signal the task waits for is not being sent.
+pub fn timeout_count(id: usize) -> Option<u32> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).timeout_count(id)
+    })
+}
+
+/// Lend up to `amount` priority levels from the calling task to task
+/// `to`, e.g. so a low-priority producer can finish the work the caller
+/// is waiting on.
+///
+/// The boost is capped at the caller's base priority and lasts `duration`
+/// ticks (`0` = until `revoke_donation()`). Reschedules immediately.
+pub fn donate_priority(to: usize, amount: u8, duration: u32) -> Result<(), KernelError> {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        scheduler.donate_priority(to, amount, duration).map(|()| scheduler.needs_reschedule)
+    })?;
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+    Ok(())
+}
+
+/// Withdraw every priority donation made by the calling task.
+pub fn revoke_donation() -> Result<(), KernelError> {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        scheduler.revoke_donation().map(|()| scheduler.needs_reschedule)
+    })?;
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+    Ok(())
+}
+
+/// Serialize the complete scheduler state into `buf` for hibernation or
+/// checkpointing.
+///
+/// Returns the number of bytes written, or 0 if `buf` is smaller than
+/// `snapshot::snapshot_size(task_count)`.
+pub fn save_snapshot(buf: &mut [u8]) -> usize {
+    sync::critical_section(|_cs| unsafe {
+        snapshot::save(&*SCHEDULER_PTR, buf)
+    })
+}
+
+/// Restore scheduler state saved by `save_snapshot()`.
+///
+/// Call after re-creating the same tasks in the same order and before
+/// `start()`: every task is restarted from its entry function, with its
+/// strategy, metrics, and timing state taken from the snapshot. The
+/// snapshot is fully validated first; on error nothing changes.
+pub fn restore_snapshot(buf: &[u8]) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        snapshot::restore(scheduler, buf)?;
+        publish_ticks(scheduler.tick_count);
+        Ok(())
+    })
+}
+
+/// Cumulative ticks during which no task was running.
+pub fn idle_ticks() -> u64 {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).idle_ticks })
+}
+
+/// Overall CPU utilization in percent since startup (`1 - idle/total`).
+///
+/// Complements the per-task metrics with a single "how busy is the
+/// system" figure for duty-cycle and power analysis.
+pub fn load_percent() -> u32 {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).load_percent() })
+}
+
+/// Per-mille of all ticks since startup that task `id` spent running, or
+/// `None` for an invalid id. Pass `idle_task_id()` to read the headroom.
+pub fn cpu_utilization(id: usize) -> Option<u32> {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).cpu_utilization(id) })
+}
+
+/// Per-mille of the ticks since the last game evaluation (at most
+/// `EVAL_FREQUENCY`) that task `id` spent running, or `None` for an
+/// invalid id.
+pub fn window_cpu_utilization(id: usize) -> Option<u32> {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).window_cpu_utilization(id) })
+}
+
+/// Block the current task for `ticks` scheduler ticks.
+///
+/// Returns `WakeReason::Timeout` when the delay ran its course, or
+/// `WakeReason::Cancelled` if another task woke it early with
+/// `cancel_delay()`. A delay of 0 just yields, like `yield_task()`.
+///
+/// A sleeping task is Blocked, so it accrues no starvation while it
+/// waits. Like `yield_task()` this enters the kernel through `svc #1`.
+pub fn delay_ticks(ticks: u32) -> WakeReason {
+    if ticks == 0 {
+        yield_task();
+        return WakeReason::Timeout;
+    }
+    Port::syscall(Syscall::Delay, ticks);
+
+    // Resumed here once woken
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).current_tcb().wake_reason
+    })
+}
+
+/// Block the current task for at least `ms` milliseconds, converted at
+/// the scheduler's tick rate and rounded up to whole ticks. See
+/// [`delay_ticks`].
+pub fn delay_ms(ms: u32) -> WakeReason {
+    let tick_hz = sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).tick_hz });
+    delay_ticks(duration_to_ticks(Duration::from_millis(ms as u64), tick_hz))
+}
+
+/// Block the current task until absolute tick `next`, for drift-free
+/// periodic loops. Returns `WakeReason::Timeout` at once, without
+/// blocking, if `next` has already been reached.
+pub fn delay_until(next: u64) -> WakeReason {
+    let blocked = sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).delay_until(next)
+    });
+    if !blocked {
+        return WakeReason::Timeout;
+    }
+    Port::trigger_context_switch();
+
+    // Resumed here once woken
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).current_tcb().wake_reason
+    })
+}
+
+/// Advance `release` to its next period and block until then.
+///
+/// Returns how many releases were already due, i.e. how far the task has
+/// fallen behind (0 when on time); see [`PeriodicRelease::advance`].
+pub fn delay_until_next(release: &mut PeriodicRelease) -> u32 {
+    let missed = release.advance(tick_count());
+    delay_until(release.next_wake());
+    missed
+}
+
+/// Scheduler ticks since start. Same as [`ticks`].
+pub fn tick_count() -> u64 {
+    ticks()
+}
+
+/// The monotonic tick counter, for timestamping events.
+///
+/// Tear-free without masking interrupts: the tick ISR publishes the
+/// 64-bit count through a seqlock, so the two halves read on a 32-bit
+/// core always belong together. Callable from tasks and ISRs alike.
+pub fn ticks() -> u64 {
+    TICKS.load()
+}
+
+/// Milliseconds since start, rounded down. Accounts each tick at the
+/// rate in force when it was taken, so [`set_tick_hz`] does not make the
+/// uptime jump.
+pub fn uptime_ms() -> u64 {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).uptime_ms() })
+}
+
+/// Publish the scheduler's tick count for [`ticks`]. Called by the tick
+/// path after every change to `tick_count`.
+pub(crate) fn publish_ticks(tick_count: u64) {
+    TICKS.store(tick_count);
+}
+
+/// Wake a task blocked in `delay_ticks()` before its delay expires.
+///
+/// The woken task sees `WakeReason::Cancelled`. Returns
+/// `KernelError::InvalidState` if the task is not in a timed wait.
+pub fn cancel_delay(id: usize) -> Result<(), KernelError> {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = scheduler()?;
+        scheduler.cancel_delay(id).map(|()| scheduler.needs_reschedule)
+    })?;
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+    Ok(())
+}
+
+/// Longest run of consecutive ticks the task held the CPU in the current
+/// evaluation window, or `None` for an invalid id.
+pub fn max_run_streak(id: usize) -> Option<u32> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).task(id).map(|t| t.payoff.max_run_streak)
+    })
+}
+
+/// Get the active scheduling policy.
+pub fn sched_policy() -> SchedPolicy {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).sched_policy()
+    })
+}
+
+/// Why the last context switch happened (slice expiry, yield, block,
+/// preemption, starvation boost, or deadline miss), or `None` before any.
+pub fn last_switch_cause() -> Option<SwitchCause> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).last_switch_cause()
+    })
+}
+
+/// Get the tie-break ordering for tasks of equal priority.
+pub fn tie_break() -> TieBreak {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).tie_break()
+    })
+}
+
+/// Shape the priority boost `schedule()` gives starving tasks: stepped or
+/// linear growth, capped at `max` priority levels so starvation prevention
+/// stays a nudge rather than a takeover.
+pub fn set_starvation_boost(curve: BoostCurve, max: u32) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_starvation_boost(curve, max);
+    });
+}
+
+/// Bound the time slice a task's payoff earns to `min..=max` percent of
+/// its configured slice (default 50–200%); `(100, 100)` turns payoff
+/// scaling off. Applies from each task's next slice.
+///
+/// # Errors
+/// `InvalidConfig` if `min` is 0 or above `max`.
+pub fn set_quantum_scale(min: u32, max: u32) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.set_quantum_scale(min, max)
+    })
+}
+
+/// Choose how tasks of equal priority are ordered (lowest id, round-robin,
+/// cooperation, deadline, or least recently run, the default). Usually set once after
+/// `init()`; takes effect at the next reschedule.
+pub fn set_tie_break(tie_break: TieBreak) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_tie_break(tie_break);
+    });
+}
+
+/// Create a deadline bundle: a group of tasks that must all finish their
+/// per-period work (reported with `job_complete()`) within `deadline_ticks`.
+///
+/// # Returns
+/// - `Ok(bundle_id)` — add members with `set_bundle()`
+/// - `Err(KernelError::InvalidState)` — `deadline_ticks` is 0
+/// - `Err(KernelError::TooManyBundles)` — `MAX_BUNDLES` already exist
+pub fn create_bundle(deadline_ticks: u32) -> Result<usize, KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.create_bundle(deadline_ticks)
+    })
+}
+
+/// Add task `id` to a deadline bundle, or remove it with `None`.
+pub fn set_bundle(id: usize, bundle: Option<usize>) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.set_bundle(id, bundle)
+    })
+}
+
+/// The deadline bundle task `id` belongs to, if any.
+pub fn bundle_of(id: usize) -> Option<usize> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).bundle_of(id)
+    })
+}
+
+/// Create a software timer that calls `callback` after `period` ticks,
+/// once or (`repeating`) every period. The timer starts stopped; see
+/// [`start_timer`].
+///
+/// The callback runs in SysTick context: keep it short, never block, and
+/// do not call kernel functions from it.
+///
+/// # Returns
+/// - `Ok(timer_id)` — the new timer
+/// - `Err(KernelError::InvalidState)` — `period` is 0
+/// - `Err(KernelError::TooManyTimers)` — `MAX_TIMERS` already exist
+pub fn create_timer(period: u32, repeating: bool, callback: fn()) -> Result<usize, KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.create_timer(period, repeating, callback)
+    })
+}
+
+/// Start timer `id`, or restart it, counting a full period from now.
+pub fn start_timer(id: usize) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.start_timer(id)
+    })
+}
+
+/// Stop timer `id` without firing it.
+pub fn stop_timer(id: usize) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.stop_timer(id)
+    })
+}
+
+/// Get the WCET overrun policy.
+pub fn overrun_policy() -> OverrunPolicy {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).overrun_policy()
+    })
+}
+
+/// Choose whether WCET overruns on jobs that still meet their deadline
+/// are penalized in full (`Strict`) or only counted (`SoftIfMet`).
+pub fn set_overrun_policy(policy: OverrunPolicy) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_overrun_policy(policy);
+    });
+}
+
+/// Copy the recent history of the global cooperation ratio (percent, one
+/// entry per evaluation window) into `out`, oldest first.
+///
+/// # Returns
+/// Number of entries written: at most `COOPERATION_TREND_LEN`.
+pub fn cooperation_trend(out: &mut [u8]) -> usize {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).cooperation_trend(out)
+    })
+}
+
+/// Begin a new fairness epoch at an operational phase boundary: every
+/// task's CPU accounting and the fair-share baseline restart from zero,
+/// atomically. With `reset_payoff`, payoff trends and cooperation scores
+/// also return to neutral. Strategy assignments are kept.
+pub fn begin_fairness_epoch(reset_payoff: bool) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).begin_fairness_epoch(reset_payoff);
+    });
+}
+
+/// Discard the recorded cooperation trend, e.g. after a mode change.
+pub fn reset_cooperation_trend() {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).reset_cooperation_trend();
+    });
+}
+
+/// Get the overload degradation policy.
+pub fn degrade_policy() -> DegradePolicy {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).degrade_policy()
+    })
+}
+
+/// Choose what happens to a periodic task whose execution time has grown
+/// to its period: keep missing (`None`), run at a reduced rate
+/// (`SkipReleases`), widen its period (`WidenPeriod`), or call the overload
+/// hook (`Alarm`).
+pub fn set_degrade_policy(policy: DegradePolicy) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_degrade_policy(policy);
+    });
+}
+
+/// Install a hook called with the task id when an overloaded task trips
+/// `DegradePolicy::Alarm`. Runs in SysTick context: keep it short and
+/// non-blocking.
+pub fn set_overload_hook(hook: fn(usize)) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_overload_hook(Some(hook));
+    });
+}
+
+/// Switch the scheduling policy, e.g. to `SchedPolicy::EarliestDeadlineFirst`
+/// to schedule purely by deadline urgency. The game keeps running for
+/// metrics either way. Takes effect at the next reschedule, which is
+/// triggered immediately.
+pub fn set_sched_policy(policy: SchedPolicy) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_sched_policy(policy);
+    });
+    Port::trigger_context_switch();
+}
+
+/// Make task `id` depend on `predecessor`: each period, `id` stays Blocked
+/// until the predecessor calls `signal_done()`. `None` removes the
+/// constraint.
+pub fn set_predecessor(id: usize, predecessor: Option<usize>) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.set_predecessor(id, predecessor)
+    })
+}
+
+/// Signal that the current task has finished its work for this period,
+/// releasing any tasks that depend on it.
+pub fn signal_done() {
+    let reschedule = sync::critical_section(|_cs| unsafe {
+        let scheduler = &mut *SCHEDULER_PTR;
+        scheduler.signal_done();
+        scheduler.needs_reschedule
+    });
+    if reschedule {
+        Port::trigger_context_switch();
+    }
+}
+
+/// How many times task `id` forced a running task off the CPU by becoming
+/// runnable or being boosted, or `None` for an invalid id. Compare
+/// across tasks to find the most disruptive ones.
+pub fn preemptions_caused(id: usize) -> Option<u32> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).preemptions_caused(id)
+    })
+}
+
+/// Whether the most recent game evaluation found the system in
+/// equilibrium: no task would gain by switching strategy. This is the raw
+/// result of that one check; `stats_snapshot().in_equilibrium` is the
+/// debounced status.
+pub fn in_equilibrium() -> bool {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).last_equilibrium })
+}
+
+/// Evaluation windows since the game engine last switched any task's
+/// strategy. A supervisor can wait for this to pass a threshold before
+/// treating the schedule as settled.
+pub fn evaluations_since_change() -> u32 {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).evaluations_since_change })
+}
+
+/// Capture tick count, cooperation ratio, equilibrium state, context
+/// switches, and each task's strategy, priority, payoff and CPU use in
+/// one critical section, so all fields describe the same instant.
+/// Read-only: scheduling is not affected.
+pub fn stats_snapshot() -> SystemStats {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).stats() })
+}
+
+/// Call `f` with every allocated task's TCB, in id order, all inside one
+/// critical section so the tasks are seen at the same instant and none
+/// can change underneath `f`.
+///
+/// Interrupts stay masked for the whole walk, so `f`'s running time adds
+/// straight to interrupt latency, once per task: keep it to reading a few
+/// fields. For anything longer, copy the state out with
+/// `collect_task_info()` and process the copy.
+pub fn with_tasks(f: impl FnMut(&TaskControlBlock)) {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).for_each_task(f) })
+}
+
+/// Copy every allocated task's state into `out`, in id order, within one
+/// short critical section; processing the copies afterwards keeps
+/// interrupts unmasked. Tasks beyond `out.len()` are left out.
+///
+/// # Returns
+/// The number of entries written.
+pub fn collect_task_info(out: &mut [TaskInfo]) -> usize {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).collect_task_info(out) })
+}
+
+/// Task `id`'s debug name from its `TaskConfig`, or `None` for an
+/// invalid id.
+pub fn task_name(id: usize) -> Option<&'static str> {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).task_name(id) })
+}
+
+/// Split task `id`'s priority into its base, donated priority, payoff
+/// adjustment and starvation boost alongside the resulting effective
+/// priority, to see why it did (or did not) run. `None` for an invalid id.
+pub fn priority_breakdown(id: usize) -> Option<PriorityBreakdown> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).priority_breakdown(id)
+    })
+}
+
+/// Read a task's strategy-switch decline streak, or `None` for an
+/// invalid id.
+pub fn decline_streak(id: usize) -> Option<u32> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).decline_streak(id)
+    })
+}
+
+/// Reset a task's decline streak to zero.
+pub fn reset_decline_streak(id: usize) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.reset_decline_streak(id)
+    })
+}
+
+/// Pin a task's strategy, e.g. keep a safety-critical task Cooperative.
+/// The game engine stops reclassifying it until `unlock_strategy()`.
+pub fn set_strategy(id: usize, strategy: Strategy) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.set_strategy(id, strategy)
+    })
+}
+
+/// Hand a pinned task's strategy back to the game engine.
+pub fn unlock_strategy(id: usize) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.unlock_strategy(id)
+    })
+}
+
+/// Read a task's current strategy.
+pub fn get_strategy(id: usize) -> Result<Strategy, KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.strategy(id)
+    })
+}
+
+/// Enable or disable the mutual-defection circuit-breaker, which nudges
+/// the lowest-payoff selfish task back to cooperation when the whole
+/// system has been defecting for several evaluation windows.
+pub fn set_defection_recovery(enabled: bool) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_defection_recovery(enabled);
+    });
+}
+
+/// Set how strictly task stack pointers are validated on each context
+/// switch. A failed check runs the stack overflow hook rather than
+/// resuming a task on a corrupted stack.
+pub fn set_stack_check(level: StackCheck) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_stack_check(level);
+    });
+}
+
+/// Provide the region task stacks are carved from. Call after `init()`
+/// and before creating any task.
+#[cfg(feature = "stack-pool")]
+pub fn set_stack_pool(mem: &'static mut [u8]) {
     sync::critical_section(|_cs| unsafe {
-        (*SCHEDULER_PTR).yield_current();
+        (*SCHEDULER_PTR).set_stack_pool(mem);
     });
-    cortex_m4::trigger_pendsv();
+}
+
+/// Mark the arrival of an event to be handled by task `id`, starting its
+/// response-time measurement. Callable from an ISR.
+pub fn event_arrived(id: usize) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.event_arrived(id)
+    })
+}
+
+/// Mark the calling task's pending event as handled. Returns the response
+/// time in ticks, or `None` if no event was pending.
+pub fn event_handled() -> Option<u32> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).event_handled()
+    })
+}
+
+/// Last and maximum event response times of a task, in ticks.
+pub fn response_times(id: usize) -> Option<(u32, u32)> {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).response_times(id)
+    })
+}
+
+/// Raise the response alarm when task `id` takes longer than `ticks` to
+/// handle an event. `0` disables the alarm.
+pub fn set_response_threshold(id: usize, ticks: u32) -> Result<(), KernelError> {
+    sync::critical_section(|_cs| unsafe {
+        scheduler()?.set_response_threshold(id, ticks)
+    })
+}
+
+/// Install a hook called with the task id and response time when a task
+/// handles an event later than its response threshold. Runs in the
+/// handling task's context inside a critical section: keep it short.
+pub fn set_response_alarm_hook(hook: fn(usize, u32)) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_response_alarm_hook(Some(hook));
+    });
+}
+
+/// Install a hook called with the task id and new strategy whenever the
+/// game engine reclassifies a task from its observed behaviour. Runs in
+/// SysTick context: keep it short and non-blocking.
+pub fn set_reclassify_hook(hook: fn(usize, Strategy)) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_reclassify_hook(Some(hook));
+    });
+}
+
+/// Install a hook called with the outgoing task, the incoming task and the
+/// current tick at every context switch, e.g. to stream a switch trace
+/// over ITM or semihosting.
+///
+/// The hook runs inside PendSV, after the scheduling decision and before
+/// the new task's registers are restored, so its execution time adds
+/// directly to every switch latency: keep it to a few stores. Without a
+/// hook the cost is one indirect call to the empty `no_switch_hook`.
+pub fn set_switch_hook(hook: fn(usize, usize, u64)) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_switch_hook(hook);
+    });
+}
+
+/// Replace the definition of each task's fair CPU share used by the payoff
+/// function (default: `game::equal_fair_share`). The policy runs in
+/// SysTick context at every evaluation: keep it short.
+pub fn set_fair_share_policy(policy: FairSharePolicy) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_fair_share_policy(policy);
+    });
+}
+
+/// Replace the weights of the payoff components, e.g. to let deadline
+/// misses dominate on a hard real-time system. Applies from the next
+/// evaluation window.
+pub fn set_payoff_config(config: PayoffConfig) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_payoff_config(config);
+    });
+}
+
+/// Install a hook called with the task id whenever a task exhausts its
+/// time slice without yielding. Runs in SysTick context: keep it short
+/// and non-blocking.
+pub fn set_quantum_hook(hook: fn(usize)) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_quantum_hook(Some(hook));
+    });
+}
+
+/// Install a hook the idle task calls on every iteration, before it
+/// waits for an interrupt, e.g. to enter a deeper sleep mode or feed a
+/// watchdog. It runs in thread mode as the idle task: it must never block
+/// or call a kernel function that could.
+pub fn set_idle_hook(hook: fn()) {
+    sync::critical_section(|_cs| unsafe {
+        (*SCHEDULER_PTR).set_idle_hook(Some(hook));
+    });
+}
+
+/// Id of the idle task `init()` creates, e.g. to read CPU headroom with
+/// `cpu_utilization()`.
+pub fn idle_task_id() -> Option<usize> {
+    sync::critical_section(|_cs| unsafe { (*SCHEDULER_PTR).idle_task() })
+}
+
+/// Install a compare timer that enforces hard deadlines (see
+/// `TaskConfig::hard_deadline`) at their exact instant. The timer is kept
+/// armed for the earliest outstanding hard deadline and re-armed after
+/// every tick; its interrupt handler must call
+/// [`deadline_timer_interrupt`].
+pub fn set_deadline_timer(timer: &'static mut dyn CompareTimer) {
+    sync::critical_section(|_cs| unsafe {
+        *core::ptr::addr_of_mut!(DEADLINE_TIMER) = Some(timer);
+        DEADLINE_ARMED_AT = None;
+        rearm_deadline_timer();
+    });
+}
+
+/// Arm the deadline timer for the earliest outstanding hard deadline, or
+/// disarm it if there is none. The hardware is only reprogrammed when the
+/// target changes.
+///
+/// # Safety
+/// Must be called from the tick or compare interrupt, or inside a
+/// critical section, after `init()`.
+pub(crate) unsafe fn rearm_deadline_timer() {
+    if let Some(timer) = (*core::ptr::addr_of_mut!(DEADLINE_TIMER)).as_mut() {
+        let next = (*SCHEDULER_PTR).earliest_hard_deadline().map(|(_, at)| at);
+        if next == DEADLINE_ARMED_AT {
+            return;
+        }
+        match next {
+            Some(at) => timer.arm(at),
+            None => timer.disarm(),
+        }
+        DEADLINE_ARMED_AT = next;
+    }
+}
+
+/// Deadline compare interrupt entry point. Records the miss of every hard
+/// deadline due at the armed instant, pends a context switch so the
+/// scheduler reacts immediately, and re-arms for the next hard deadline.
+///
+/// # Safety
+/// Must be called from the compare timer's interrupt handler, after
+/// `init()`.
+pub unsafe fn deadline_timer_interrupt() {
+    let scheduler = &mut *SCHEDULER_PTR;
+    if let Some(at) = DEADLINE_ARMED_AT {
+        DEADLINE_ARMED_AT = None;
+        scheduler.enforce_hard_deadlines(at);
+    }
+    rearm_deadline_timer();
+
+    if scheduler.needs_reschedule {
+        Port::trigger_context_switch();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // No test calls `init()`, so the global scheduler is never set up here
+    #[test]
+    fn test_fallible_apis_report_not_initialized() {
+        assert_eq!(set_priority(1, 0), Err(KernelError::NotInitialized));
+        assert_eq!(cancel_delay(1), Err(KernelError::NotInitialized));
+        assert_eq!(restore_snapshot(&[]), Err(KernelError::NotInitialized));
+        assert_eq!(notify_from_isr(1), Err(KernelError::NotInitialized));
+        assert_eq!(set_event_bits(&EventGroup::new(), 1), Err(KernelError::NotInitialized));
+        assert_eq!(get_strategy(1), Err(KernelError::NotInitialized));
+    }
 }
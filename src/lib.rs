@@ -1,7 +1,8 @@
 //! # EqOS — Equilibrium Operating System
 //!
 //! A game-theory-based Real-Time Operating System (RTOS) scheduler for
-//! ARM Cortex-M4 microcontrollers.
+//! ARM Cortex-M4 microcontrollers, with a Cortex-M0(+) port behind the
+//! `cortex-m0` feature.
 //!
 //! ## Overview
 //!
@@ -31,7 +32,7 @@
 //! │              Task Model (task.rs)                       │
 //! │    TCB · Strategy · PayoffMetrics · TaskState           │
 //! ├────────────────────────────────────────────────────────┤
-//! │            Arch Port (arch/cortex_m4.rs)                │
+//! │     Arch Port (arch/cortex_m4.rs, arch/cortex_m0.rs)    │
 //! │    PendSV · SysTick · Context Switch · Stack Init      │
 //! ├────────────────────────────────────────────────────────┤
 //! │         ARM Cortex-M4 Hardware (Thumb-2)                │
@@ -81,9 +82,28 @@
 #![no_std]
 
 pub mod config;
+pub mod error;
 pub mod task;
 pub mod game;
+pub mod analysis;
 pub mod scheduler;
+pub mod policy;
+pub mod invariant;
+pub mod snapshot;
 pub mod arch;
 pub mod kernel;
 pub mod sync;
+pub mod syscall;
+pub mod timer;
+
+#[cfg(feature = "stall-watchdog")]
+pub mod watchdog;
+
+#[cfg(feature = "bench")]
+pub mod bench;
+
+#[cfg(any(test, feature = "sim"))]
+pub mod sim;
+
+#[cfg(feature = "stack-pool")]
+pub mod stack_pool;
@@ -32,11 +32,14 @@
 #![no_std]
 #![no_main]
 
+use core::time::Duration;
+
 use cortex_m_rt::entry;
 use panic_halt as _;
 
 use eqos::kernel;
-use eqos::task::{TaskConfig, Strategy};
+use eqos::config::TICK_HZ;
+use eqos::task::{duration_to_ticks, PeriodicRelease};
 
 // ---------------------------------------------------------------------------
 // Task entry points
@@ -73,8 +76,8 @@ extern "C" fn cpu_bound_task() -> ! {
 /// **Periodic Deadline Task** (Priority 3)
 ///
 /// Simulates a periodic real-time task with a 100ms deadline.
-/// It does a small amount of work and then yields, completing
-/// well within its deadline.
+/// It does a small amount of work and then sleeps until its next
+/// release, completing well within its deadline.
 ///
 /// **Game effect**: This task accumulates:
 /// - Deadline compliance bonuses (+100 per met deadline)
@@ -85,6 +88,9 @@ extern "C" fn cpu_bound_task() -> ! {
 /// Its effective priority grows over time, ensuring reliable
 /// deadline compliance even under contention.
 extern "C" fn periodic_deadline_task() -> ! {
+    // Releases fall on exact multiples of the period, however long the
+    // work takes
+    let mut release = PeriodicRelease::new(kernel::tick_count(), ms(100));
     loop {
         // Simulate periodic work (e.g., sensor sampling)
         // Do ~5ms of computation within a 100ms period
@@ -93,10 +99,10 @@ extern "C" fn periodic_deadline_task() -> ! {
             work = work.wrapping_add(1);
         }
 
-        // Yield early — cooperative behavior
+        // Sleep until the next release — cooperative behavior
         // This signals to the game engine that the task is
         // cooperating by not consuming its full time slice.
-        kernel::yield_task();
+        kernel::delay_until_next(&mut release);
     }
 }
 
@@ -149,6 +155,11 @@ extern "C" fn sporadic_high_prio_task() -> ! {
     }
 }
 
+/// Milliseconds to scheduler ticks at `TICK_HZ`, rounded up.
+fn ms(millis: u64) -> u32 {
+    duration_to_ticks(Duration::from_millis(millis), TICK_HZ)
+}
+
 // ---------------------------------------------------------------------------
 // Main entry point
 // ---------------------------------------------------------------------------
@@ -164,58 +175,31 @@ fn main() -> ! {
     kernel::init();
 
     // --- Create tasks ---
-
-    // Task 0: CPU-bound selfish task
-    kernel::create_task(
-        cpu_bound_task,
-        TaskConfig {
-            priority: 2,
-            deadline_ticks: 0,          // No deadline (best-effort)
-            wcet_ticks: 15,             // 15-tick WCET
-            affinity_mask: 0x01,        // Core 0
-            time_slice: 10,             // Standard slice
-        },
-        Strategy::Selfish,
-    ).expect("Failed to create cpu_bound_task");
-
-    // Task 1: Periodic deadline task (100ms period at 1kHz = 100 ticks)
-    kernel::create_task(
-        periodic_deadline_task,
-        TaskConfig {
+    //
+    // Task 0: CPU-bound selfish task — no deadline (best-effort), 15-tick WCET
+    // Task 1: Periodic deadline task — 100ms period, converted at TICK_HZ
+    // Task 2: Cooperative yielding task — no deadline or WCET constraint
+    // Task 3: Sporadic high-priority task — 50ms response deadline, shorter
+    //         slice for responsiveness
+    eqos::define_tasks! {
+        cpu_bound_task: Selfish { name: "cpu_bound", priority: 2, wcet_ticks: 15, time_slice: 10 },
+        periodic_deadline_task: Cooperative {
+            name: "periodic",
             priority: 3,
-            deadline_ticks: 100,        // 100ms deadline
-            wcet_ticks: 5,              // 5ms WCET
-            affinity_mask: 0x01,
-            time_slice: 10,
-        },
-        Strategy::Cooperative,
-    ).expect("Failed to create periodic_deadline_task");
-
-    // Task 2: Cooperative yielding task
-    kernel::create_task(
-        cooperative_yielding_task,
-        TaskConfig {
-            priority: 1,
-            deadline_ticks: 0,          // No deadline
-            wcet_ticks: 0,              // No WCET constraint
-            affinity_mask: 0x01,
-            time_slice: 10,
+            deadline_ticks: ms(100),
+            wcet_ticks: ms(5),
+            time_slice: ms(10),
         },
-        Strategy::Cooperative,
-    ).expect("Failed to create cooperative_yielding_task");
-
-    // Task 3: Sporadic high-priority task
-    kernel::create_task(
-        sporadic_high_prio_task,
-        TaskConfig {
+        cooperative_yielding_task: Cooperative { name: "yielding", priority: 1, time_slice: 10 },
+        sporadic_high_prio_task: Cooperative {
+            name: "sporadic",
             priority: 5,
-            deadline_ticks: 50,         // 50ms response deadline
-            wcet_ticks: 3,              // 3ms WCET
-            affinity_mask: 0x01,
-            time_slice: 5,              // Shorter slice for responsiveness
+            deadline_ticks: ms(50),
+            wcet_ticks: ms(3),
+            time_slice: ms(5),
         },
-        Strategy::Cooperative,
-    ).expect("Failed to create sporadic_high_prio_task");
+    }
+    .expect("Failed to create tasks");
 
     // Start the scheduler — does not return
     kernel::start(cp)
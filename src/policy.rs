@@ -0,0 +1,304 @@
+//! # Scheduling Policies
+//!
+//! Task-selection rules `schedule()` can run, so the game-theoretic
+//! selection can be compared against classic real-time policies on the
+//! same task set.
+//!
+//! Each policy is a zero-sized [`SchedulingPolicy`] implementor; the
+//! scheduler holds the active one as the concrete [`SchedPolicy`] enum, so
+//! selection needs no heap and no trait objects and stays callable from
+//! the PendSV context-switch path.
+//!
+//! Every policy considers only runnable tasks that can run on core 0,
+//! never picks the idle task while another is runnable, and resolves ties
+//! with the scheduler's `TieBreak`. The same ordering decides whether a
+//! task that becomes ready preempts the running one.
+
+use core::cmp::Reverse;
+
+use crate::scheduler::{SchedPolicy, Scheduler};
+
+/// A rule for choosing the next task to run.
+pub trait SchedulingPolicy {
+    /// Pick the next task from `sched`'s task table.
+    ///
+    /// # Returns
+    /// The selected task id; the idle task (or 0 without one) if no other
+    /// task is runnable.
+    fn select_next(&self, sched: &Scheduler) -> usize;
+
+    /// Whether task `challenger`, newly ready, should preempt the running
+    /// task `current`.
+    fn outranks(&self, sched: &Scheduler, challenger: usize, current: usize) -> bool;
+}
+
+/// Highest effective priority (base plus payoff adjustment) plus
+/// starvation boost. Runnable anchor tasks are always preferred over
+/// non-anchors, so no starvation boost or payoff penalty can displace
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameTheoryPolicy;
+
+/// Highest base priority (plus any donated priority), ignoring payoff and
+/// starvation boosts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrictPriorityPolicy;
+
+/// Rate-monotonic: the shortest period wins. The deadline doubles as the
+/// period, so tasks without one run only when no periodic task is ready,
+/// in base-priority order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateMonotonicPolicy;
+
+/// Earliest deadline first: the fewest ticks left until the current
+/// job's deadline wins, and a job past its deadline beats any other.
+/// Payoff is ignored; equal deadlines fall to base priority. Tasks
+/// without a deadline run only when no task with one is ready, in
+/// base-priority order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EarliestDeadlineFirstPolicy;
+
+impl SchedulingPolicy for GameTheoryPolicy {
+    fn select_next(&self, sched: &Scheduler) -> usize {
+        select_max(sched, |id| {
+            let tcb = &sched.tasks[id];
+            (tcb.config.anchor, tcb.effective_priority() + sched.starvation_boost(id))
+        })
+    }
+
+    fn outranks(&self, sched: &Scheduler, challenger: usize, current: usize) -> bool {
+        sched.tasks[challenger].effective_priority() > sched.tasks[current].effective_priority()
+    }
+}
+
+impl SchedulingPolicy for StrictPriorityPolicy {
+    fn select_next(&self, sched: &Scheduler) -> usize {
+        select_max(sched, |id| sched.tasks[id].boosted_priority())
+    }
+
+    fn outranks(&self, sched: &Scheduler, challenger: usize, current: usize) -> bool {
+        GameTheoryPolicy.outranks(sched, challenger, current)
+    }
+}
+
+impl RateMonotonicPolicy {
+    fn rank(sched: &Scheduler, id: usize) -> (bool, Reverse<u32>, i32) {
+        let tcb = &sched.tasks[id];
+        match tcb.release_window() {
+            0 => (false, Reverse(0), tcb.boosted_priority()),
+            period => (true, Reverse(period), 0),
+        }
+    }
+}
+
+impl SchedulingPolicy for RateMonotonicPolicy {
+    fn select_next(&self, sched: &Scheduler) -> usize {
+        select_max(sched, |id| Self::rank(sched, id))
+    }
+
+    fn outranks(&self, sched: &Scheduler, challenger: usize, current: usize) -> bool {
+        Self::rank(sched, challenger) > Self::rank(sched, current)
+    }
+}
+
+impl EarliestDeadlineFirstPolicy {
+    fn rank(sched: &Scheduler, id: usize) -> (bool, Reverse<i64>, i32) {
+        let tcb = &sched.tasks[id];
+        match tcb.ticks_to_deadline(sched.tick_count) {
+            None => (false, Reverse(0), tcb.boosted_priority()),
+            Some(left) => (true, Reverse(left), tcb.config.priority as i32),
+        }
+    }
+}
+
+impl SchedulingPolicy for EarliestDeadlineFirstPolicy {
+    fn select_next(&self, sched: &Scheduler) -> usize {
+        select_max(sched, |id| Self::rank(sched, id))
+    }
+
+    fn outranks(&self, sched: &Scheduler, challenger: usize, current: usize) -> bool {
+        Self::rank(sched, challenger) > Self::rank(sched, current)
+    }
+}
+
+impl SchedulingPolicy for SchedPolicy {
+    fn select_next(&self, sched: &Scheduler) -> usize {
+        match self {
+            SchedPolicy::GameTheoretic => GameTheoryPolicy.select_next(sched),
+            SchedPolicy::StrictPriority => StrictPriorityPolicy.select_next(sched),
+            SchedPolicy::RateMonotonic => RateMonotonicPolicy.select_next(sched),
+            SchedPolicy::EarliestDeadlineFirst => EarliestDeadlineFirstPolicy.select_next(sched),
+        }
+    }
+
+    fn outranks(&self, sched: &Scheduler, challenger: usize, current: usize) -> bool {
+        match self {
+            SchedPolicy::GameTheoretic => GameTheoryPolicy.outranks(sched, challenger, current),
+            SchedPolicy::StrictPriority => StrictPriorityPolicy.outranks(sched, challenger, current),
+            SchedPolicy::RateMonotonic => RateMonotonicPolicy.outranks(sched, challenger, current),
+            SchedPolicy::EarliestDeadlineFirst => {
+                EarliestDeadlineFirstPolicy.outranks(sched, challenger, current)
+            }
+        }
+    }
+}
+
+/// The eligible task with the greatest `rank`, ties resolved by the
+/// scheduler's `TieBreak`.
+fn select_max<K: Ord>(sched: &Scheduler, rank: impl Fn(usize) -> K) -> usize {
+    // Fallback: the idle task, unless another core is on it already
+    let mut best_task = match sched.idle_task() {
+        Some(idle) if !sched.is_running_elsewhere(idle) => idle,
+        Some(_) => sched.current(),
+        None => 0,
+    };
+    let mut best_rank: Option<K> = None;
+
+    for i in 0..sched.task_count {
+        let tcb = &sched.tasks[i];
+        // Ready only, so never a task running on another core
+        if !tcb.is_runnable() || tcb.idle || !tcb.can_run_on_core(sched.current_core as u32) {
+            continue;
+        }
+        let r = rank(i);
+        let better = match &best_rank {
+            None => true,
+            Some(best) => r > *best || (r == *best && sched.wins_tie(i, best_task)),
+        };
+        if better {
+            best_rank = Some(r);
+            best_task = i;
+        }
+    }
+
+    best_task
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Strategy, TaskConfig, TaskEntry, TaskState};
+    #[cfg(feature = "stack-pool")]
+    use crate::config::{MAX_TASKS, STACK_SIZE};
+    #[cfg(feature = "stack-pool")]
+    use crate::stack_pool::tests::leaked_region;
+
+    extern "C" fn spin() -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    const TEST_ENTRY: TaskEntry = TaskEntry::new(spin);
+
+    /// Three ready tasks that each policy orders differently:
+    /// - `long`: highest base priority, 100-tick period with 90 ticks left
+    /// - `short`: 20-tick period, 15 ticks already elapsed (5 left)
+    /// - `medium`: 50-tick period, 10 ticks left, strong payoff
+    fn scenario() -> (Scheduler, usize, usize, usize) {
+        let mut sched = Scheduler::new();
+        #[cfg(feature = "stack-pool")]
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        let config = |priority, deadline_ticks| TaskConfig { priority, deadline_ticks, ..TaskConfig::DEFAULT };
+        let long = sched.create_task(TEST_ENTRY, config(6, 100), Strategy::Cooperative).unwrap();
+        let short = sched.create_task(TEST_ENTRY, config(2, 20), Strategy::Cooperative).unwrap();
+        let medium = sched.create_task(TEST_ENTRY, config(3, 50), Strategy::Cooperative).unwrap();
+        // Deadlines 90, 5 and 10 ticks out
+        sched.tick_count = 100;
+        sched.tasks[long].deadline_tick = 190;
+        sched.tasks[short].deadline_tick = 105;
+        sched.tasks[medium].deadline_tick = 110;
+        sched.tasks[medium].payoff.payoff = 500;
+        (sched, long, short, medium)
+    }
+
+    #[test]
+    fn test_policies_pick_expected_task() {
+        let (mut sched, long, short, medium) = scenario();
+        assert_eq!(StrictPriorityPolicy.select_next(&sched), long);
+        assert_eq!(RateMonotonicPolicy.select_next(&sched), short);
+        // Payoff lifts `medium` to 3 + 5 = 8, above `long`
+        assert_eq!(GameTheoryPolicy.select_next(&sched), medium);
+
+        // `short` has 5 ticks left against `medium`'s 10, but not 15
+        assert_eq!(EarliestDeadlineFirstPolicy.select_next(&sched), short);
+        sched.tasks[short].deadline_tick = 115;
+        assert_eq!(EarliestDeadlineFirstPolicy.select_next(&sched), medium);
+
+        // The enum dispatches to the matching policy
+        assert_eq!(SchedPolicy::RateMonotonic.select_next(&sched), short);
+        assert_eq!(SchedPolicy::EarliestDeadlineFirst.select_next(&sched), medium);
+    }
+
+    #[test]
+    fn test_best_effort_tasks_rank_last_under_deadline_policies() {
+        let mut sched = Scheduler::new();
+        #[cfg(feature = "stack-pool")]
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        let config = |priority, deadline_ticks| TaskConfig { priority, deadline_ticks, ..TaskConfig::DEFAULT };
+        let periodic = sched.create_task(TEST_ENTRY, config(1, 1000), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, config(5, 0), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, config(9, 0), Strategy::Cooperative).unwrap();
+
+        assert_eq!(RateMonotonicPolicy.select_next(&sched), periodic);
+        assert_eq!(EarliestDeadlineFirstPolicy.select_next(&sched), periodic);
+
+        // Without a periodic task ready, base priority decides
+        sched.tasks[periodic].state = TaskState::Blocked;
+        assert_eq!(RateMonotonicPolicy.select_next(&sched), high);
+        assert_eq!(EarliestDeadlineFirstPolicy.select_next(&sched), high);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_edf_preempts_for_tighter_deadline() {
+        let mut sched = Scheduler::new();
+        #[cfg(feature = "stack-pool")]
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        sched.set_sched_policy(SchedPolicy::EarliestDeadlineFirst);
+        let config = |priority, deadline_ticks| TaskConfig { priority, deadline_ticks, ..TaskConfig::DEFAULT };
+        let long = sched.create_task(TEST_ENTRY, config(9, 200), Strategy::Cooperative).unwrap();
+        let tight = sched.create_task(TEST_ENTRY, config(1, 10), Strategy::Cooperative).unwrap();
+
+        // The tighter deadline runs first despite its low priority, then
+        // sleeps and hands the CPU to `long`
+        assert_eq!(sched.start_first(), tight);
+        sched.delay_current(3);
+        assert_eq!(sched.schedule(), long);
+
+        // Waking up, `tight` preempts `long`
+        for _ in 0..3 {
+            sched.tick();
+        }
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.schedule(), tight);
+
+        // Once its job is done, the next deadline is a window further out
+        sched.job_complete();
+        assert_eq!(sched.tasks[tight].ticks_to_deadline(sched.tick_count), Some(17));
+    }
+
+    #[test]
+    fn test_edf_ranks_late_jobs_first_and_ties_by_priority() {
+        let mut sched = Scheduler::new();
+        #[cfg(feature = "stack-pool")]
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        let config = |priority, deadline_ticks| TaskConfig { priority, deadline_ticks, ..TaskConfig::DEFAULT };
+        let late = sched.create_task(TEST_ENTRY, config(1, 100), Strategy::Cooperative).unwrap();
+        let low = sched.create_task(TEST_ENTRY, config(2, 10), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, config(5, 10), Strategy::Cooperative).unwrap();
+        sched.tasks[high].payoff.payoff = -500;
+
+        // Equal deadlines fall to base priority; payoff plays no part
+        assert_eq!(EarliestDeadlineFirstPolicy.select_next(&sched), high);
+
+        sched.tasks[late].job_late = true;
+        assert_eq!(sched.tasks[late].ticks_to_deadline(sched.tick_count), Some(i64::MIN));
+        assert_eq!(EarliestDeadlineFirstPolicy.select_next(&sched), late);
+        assert!(EarliestDeadlineFirstPolicy.outranks(&sched, late, low));
+    }
+}
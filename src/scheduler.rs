@@ -18,13 +18,278 @@
 //!
 //! ## Starvation Prevention
 //!
-//! Any task that receives zero CPU for `STARVATION_THRESHOLD` ticks gets a
+//! Any task that receives zero CPU for the starvation threshold gets a
 //! temporary priority boost, ensuring eventual execution regardless of
-//! game-theory dynamics.
+//! game-theory dynamics. The threshold scales with the number of active
+//! tasks (`STARVATION_THRESHOLD` at `STARVATION_BASE_TASKS`), bounded by
+//! `STARVATION_THRESHOLD_MIN`/`STARVATION_THRESHOLD_MAX`. A task may set
+//! its own threshold in `TaskConfig::starvation_threshold` instead.
+//!
+//! ## Cooperative-only Builds
+//!
+//! With the `cooperative-only` feature, the preemption in step 2 is
+//! compiled out and a Running task is never preempted: switches happen
+//! only when it yields or blocks. Time slices still run out, pacing the
+//! WCET overrun checks. SysTick still drives delays, metrics, and game evaluation,
+//! which can also be run on demand with `evaluate_now()`.
+
+use crate::config::{
+    MAX_TASKS, EVAL_FREQUENCY, STARVATION_THRESHOLD, STARVATION_BASE_TASKS,
+    STARVATION_THRESHOLD_MIN, STARVATION_THRESHOLD_MAX, TICK_HZ, PRIORITY_LEVELS,
+    EQUILIBRIUM_DEBOUNCE, DEFECTION_RATIO_THRESHOLD, DEFECTION_RECOVERY_WINDOWS,
+    STACK_CANARY, MAX_BUNDLES, OVERLOAD_PERIODS, COOPERATION_TREND_LEN,
+    STARVATION_BOOST_MAX, MAX_TIMERS, MAX_CORES, PAYOFF_DECAY_SHIFT, QUANTUM_SCALE_MIN,
+    QUANTUM_SCALE_MAX, FAIRNESS_DECAY_SHIFT,
+};
+#[cfg(feature = "stack-pool")]
+use crate::config::STACK_SIZE;
+use crate::error::KernelError;
+use crate::timer::TimerControlBlock;
+use crate::task::{
+    TaskControlBlock, TaskState, TaskConfig, Strategy, BlockReason, WakeReason, StackCheck, StackFault,
+    Donation, TaskEntry,
+};
+use crate::game::{self, FairSharePolicy, PayoffConfig, SystemMetrics};
+use crate::analysis;
+use crate::policy::SchedulingPolicy;
+use crate::snapshot::TaskImage;
+use crate::arch::{Arch, Port};
+#[cfg(feature = "stack-pool")]
+use crate::stack_pool::StackPool;
+
+// ---------------------------------------------------------------------------
+// Scheduling policy
+// ---------------------------------------------------------------------------
+
+/// Task-selection policy used by `schedule()`. Each variant dispatches to
+/// the matching `policy::SchedulingPolicy` implementor.
+///
+/// The game engine keeps running under every policy (payoffs, strategies,
+/// metrics); the policy only decides which runnable task goes next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedPolicy {
+    /// Effective priority = base priority + payoff adjustment, plus
+    /// starvation boost. The default.
+    GameTheoretic,
+    /// Base priority only; payoff and starvation boosts are ignored.
+    StrictPriority,
+    /// Rate-monotonic: shortest period first.
+    RateMonotonic,
+    /// Earliest deadline first: fewest ticks to the job's deadline first,
+    /// late jobs before all others; payoff is ignored.
+    EarliestDeadlineFirst,
+}
+
+/// How the schedule-time starvation boost grows once a task has gone
+/// `starvation_threshold` ticks without running. Either way it is capped
+/// at `starvation_boost_max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoostCurve {
+    /// +2 for each full threshold elapsed. The default.
+    Stepped,
+    /// +2 per threshold, pro rata for every tick past the first threshold.
+    Linear,
+}
+
+/// Secondary ordering used by `schedule()` when runnable tasks tie on
+/// priority under the active `SchedPolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Lowest task id wins.
+    LowestId,
+    /// The first tied task after the current one, in cyclic id order.
+    RoundRobin,
+    /// Highest cooperation score wins, rewarding cooperative behaviour.
+    HighestCooperation,
+    /// Fewest ticks left until the deadline wins; tasks without a deadline
+    /// lose to any task with one.
+    EarliestDeadline,
+    /// The task least recently selected by `schedule()` wins, so tied
+    /// tasks take turns well before starvation boosting would kick in.
+    /// The default.
+    LeastRecentlyRun,
+}
+
+/// How a job that exceeds its `wcet_ticks` is penalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrunPolicy {
+    /// Every time-slice expiry past the WCET records a full overrun. The
+    /// default.
+    Strict,
+    /// For tasks with a deadline, the overrun is held until the deadline
+    /// outcome is known: a job that still meets its deadline records only
+    /// a penalty-free soft overrun, and a job that misses records one full
+    /// overrun. Tasks without a deadline are treated as under `Strict`.
+    SoftIfMet,
+}
+
+/// What the scheduler does once a periodic task is overloaded: it has
+/// missed `OVERLOAD_PERIODS` deadlines in a row with its job still
+/// unfinished, i.e. its execution time has reached its period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegradePolicy {
+    /// Keep recording misses. The default.
+    None,
+    /// Run the task at a reduced rate: each release spans one more period
+    /// (`release_stride`), so the job gets that much longer per deadline.
+    /// The configured period is unchanged.
+    SkipReleases,
+    /// Widen the task's deadline/period by half (at least one tick) in its
+    /// configuration.
+    WidenPeriod,
+    /// Leave the task's timing alone and call the overload hook.
+    Alarm,
+}
+
+/// A group of tasks that must all finish their per-period work before one
+/// shared deadline, modelling an end-to-end deadline across a task chain.
+///
+/// The bundle's period runs on wall-clock ticks. Each member reports its
+/// work with `job_complete()`; at the deadline the bundle is met only if
+/// every member did. On a miss the penalty is attributed to the members
+/// that had not finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineBundle {
+    /// Whether this slot is allocated.
+    pub active: bool,
+    /// Shared deadline (and period) in ticks.
+    pub deadline_ticks: u32,
+    /// Ticks elapsed in the current period.
+    pub elapsed: u32,
+    /// Bitmask of member task ids.
+    pub members: u32,
+    /// Bitmask of members that completed this period.
+    pub done: u32,
+    /// Periods in which every member finished in time.
+    pub met: u32,
+    /// Periods in which at least one member was late.
+    pub missed: u32,
+}
+
+impl DeadlineBundle {
+    pub(crate) const EMPTY: Self = Self {
+        active: false,
+        deadline_ticks: 0,
+        elapsed: 0,
+        members: 0,
+        done: 0,
+        met: 0,
+        missed: 0,
+    };
+}
+
+/// Why the scheduler last switched to a different task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchCause {
+    /// The running task used up its time slice.
+    SliceExpiry,
+    /// The running task yielded voluntarily.
+    Yield,
+    /// The running task blocked (delay, deferral, or predecessor wait) or
+    /// was deleted.
+    Block,
+    /// Another task became more eligible: a wakeup, priority or policy
+    /// change, priority donation, or task restart.
+    Preempt,
+    /// The game engine boosted a starving task.
+    Starvation,
+    /// A task missed its deadline.
+    Deadline,
+}
+
+/// A task's scheduling priority split into its sources, as reported by
+/// `priority_breakdown()`. The components sum to `effective`, the value
+/// game-theoretic selection ranks the task by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityBreakdown {
+    /// Static base priority from the task's configuration.
+    pub base: i32,
+    /// Priority donated by another task with `donate_priority()`.
+    pub inheritance_boost: i32,
+    /// Payoff-driven adjustment actually applied, i.e. after the floor at
+    /// zero (or at the base, for anchor tasks).
+    pub payoff_adjustment: i32,
+    /// Schedule-time boost for a starving task.
+    pub starvation_boost: i32,
+    /// Total priority used by game-theoretic selection.
+    pub effective: i32,
+}
+
+/// One task's row in a [`SystemStats`] snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskStats {
+    pub id: usize,
+    pub name: &'static str,
+    pub strategy: Strategy,
+    /// Priority after the payoff adjustment (see `effective_priority()`).
+    pub effective_priority: i32,
+    /// Payoff as of the last evaluation (fixed-point ×100).
+    pub payoff: i32,
+    /// CPU ticks used in the current fairness epoch.
+    pub cpu_ticks_used: u32,
+}
+
+/// Copy of one task's state, as filled in by `collect_task_info()`, for
+/// monitors that process it outside the critical section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskInfo {
+    pub id: usize,
+    pub name: &'static str,
+    pub state: TaskState,
+    /// Why the task is blocked, if it is.
+    pub block_reason: Option<BlockReason>,
+    pub strategy: Strategy,
+    /// Configured base priority.
+    pub priority: u8,
+    /// Priority after the payoff adjustment (see `effective_priority()`).
+    pub effective_priority: i32,
+    /// Payoff as of the last evaluation (fixed-point ×100).
+    pub payoff: i32,
+    /// Cooperation score (fixed-point ×100, 0–500).
+    pub cooperation_score: i32,
+    /// Ticks spent Running.
+    pub total_ticks: u32,
+    pub deadlines_met: u32,
+    pub deadlines_missed: u32,
+}
+
+impl TaskInfo {
+    /// Placeholder for sizing buffers passed to `collect_task_info()`.
+    pub const EMPTY: Self = Self {
+        id: 0,
+        name: "",
+        state: TaskState::Suspended,
+        block_reason: None,
+        strategy: Strategy::Cooperative,
+        priority: 0,
+        effective_priority: 0,
+        payoff: 0,
+        cooperation_score: 0,
+        total_ticks: 0,
+        deadlines_met: 0,
+        deadlines_missed: 0,
+    };
+}
 
-use crate::config::{MAX_TASKS, EVAL_FREQUENCY, STARVATION_THRESHOLD};
-use crate::task::{TaskControlBlock, TaskState, TaskConfig, Strategy};
-use crate::game::{self, SystemMetrics};
+/// The whole system at one instant, as returned by `stats()`, for
+/// debugging game convergence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemStats {
+    pub tick_count: u64,
+    /// Tasks taking part in the game.
+    pub active_tasks: u32,
+    /// Percentage of tasks cooperating at the last evaluation.
+    pub global_cooperation_ratio: u32,
+    pub context_switches: u64,
+    /// Debounced equilibrium status.
+    pub in_equilibrium: bool,
+    /// Result of the most recent equilibrium check.
+    pub last_equilibrium: bool,
+    /// Evaluations since the game engine last switched a task's strategy.
+    pub evaluations_since_change: u32,
+    /// Rows indexed by task id; `None` for free slots.
+    pub tasks: [Option<TaskStats>; MAX_TASKS],
+}
 
 // ---------------------------------------------------------------------------
 // Scheduler struct
@@ -36,16 +301,23 @@ use crate::game::{self, SystemMetrics};
 /// ## Design Notes
 ///
 /// - All tasks are stored inline in a fixed-size array (no heap)
-/// - `current_task` tracks the index of the currently running task
-/// - An idle task (index 0) is always present as a fallback
+/// - `current_task` tracks the task running on each core, and
+///   `current_core` which of them the scheduler is acting for
+/// - An idle task, created by `kernel::init()`, is the fallback when
+///   nothing else is runnable
 pub struct Scheduler {
-    /// Fixed-size array of TCBs. Index 0 is reserved for the idle task.
+    /// Fixed-size array of TCBs.
     pub tasks: [TaskControlBlock; MAX_TASKS],
 
-    /// Index of the currently running task.
-    pub current_task: usize,
+    /// Index of the task running on each core.
+    pub current_task: [usize; MAX_CORES],
 
-    /// Number of allocated tasks (including idle task).
+    /// Core the scheduler is acting for: the one whose exception (tick,
+    /// PendSV, SVC) is being handled. "The current task" means
+    /// `current_task[current_core]`. Always 0 when `MAX_CORES == 1`.
+    pub current_core: usize,
+
+    /// Number of allocated tasks (including the idle task).
     pub task_count: usize,
 
     /// Aggregate system metrics for the game engine.
@@ -54,324 +326,5761 @@ pub struct Scheduler {
     /// Monotonic tick counter.
     pub tick_count: u64,
 
+    /// Ticks during which no task was Running (the CPU was idle).
+    pub idle_ticks: u64,
+
+    /// Context switches performed by PendSV.
+    pub context_switches: u64,
+
+    /// While set, `tick()` is ignored so the whole system stays frozen;
+    /// `single_tick()` still advances it one tick at a time.
+    pub paused: bool,
+
+    /// Cycle-counter reading at the start of the current run segment, or
+    /// `None` before the first `account_cycles()`.
+    pub segment_start: Option<u32>,
+
+    /// Task that owns the current run segment. `None` while idle.
+    pub segment_owner: Option<usize>,
+
     /// Flag set by `tick()` when a reschedule is needed.
     pub needs_reschedule: bool,
+
+    /// Nesting depth of `preempt_disable()`. While non-zero the running
+    /// task keeps the CPU and reschedule requests stay pending.
+    pub preempt_lock_count: u32,
+
+    /// A yield made while preemption was locked, performed at the final
+    /// `preempt_enable()`.
+    pub yield_pending: bool,
+
+    /// Task the next `schedule()` runs regardless of priority, set by
+    /// `yield_to()` and cleared by that `schedule()`.
+    pub forced_next: Option<usize>,
+
+    /// Active task-selection policy.
+    pub policy: SchedPolicy,
+
+    /// Ordering among tasks that tie on priority.
+    pub tie_break: TieBreak,
+
+    /// Penalty applied to jobs that exceed their WCET.
+    pub overrun_policy: OverrunPolicy,
+
+    /// Action taken when a periodic task is overloaded.
+    pub degrade_policy: DegradePolicy,
+
+    /// Debounced equilibrium status. Only changes after
+    /// `EQUILIBRIUM_DEBOUNCE` consecutive evaluations disagree with it.
+    pub in_equilibrium: bool,
+
+    /// Consecutive evaluations whose raw equilibrium check disagreed with
+    /// `in_equilibrium`.
+    pub equilibrium_streak: u32,
+
+    /// Result of the most recent (raw, undebounced) equilibrium check.
+    pub last_equilibrium: bool,
+
+    /// Evaluations since the game engine last switched any task's
+    /// strategy, by hysteresis, reclassification or the defection
+    /// circuit-breaker.
+    pub evaluations_since_change: u32,
+
+    /// Hook invoked with the task id whenever a task's time slice expires
+    /// (it consumed its full quantum without yielding).
+    pub quantum_hook: Option<fn(usize)>,
+
+    /// Hook invoked by the idle task on every iteration, before it waits
+    /// for the next interrupt.
+    pub idle_hook: Option<fn()>,
+
+    /// Called by `start_first()` when no task was created. Defaults to
+    /// `halt_no_tasks`.
+    pub no_tasks_hook: fn() -> !,
+
+    /// Called with the task id and fault when a context switch finds a
+    /// corrupted stack. Defaults to `halt_stack_fault`.
+    pub stack_overflow_hook: fn(usize, StackFault) -> !,
+
+    /// Hook invoked with the task id when an overloaded task trips the
+    /// `Alarm` degradation policy.
+    pub overload_hook: Option<fn(usize)>,
+
+    /// Hook invoked with the task id and response time (ticks) when a
+    /// handled event exceeded the task's `response_threshold`.
+    pub response_alarm_hook: Option<fn(usize, u32)>,
+
+    /// Called with the outgoing task, the incoming task and the tick at
+    /// every context switch, from PendSV. Defaults to `no_switch_hook`.
+    pub switch_hook: fn(usize, usize, u64),
+
+    /// Hook invoked with the task id and its new strategy when the game
+    /// engine reclassifies a task from its observed behaviour.
+    pub reclassify_hook: Option<fn(usize, Strategy)>,
+
+    /// Defines each task's fair CPU allotment for the payoff's fairness
+    /// term. Defaults to an equal split among active tasks.
+    pub fair_share_policy: FairSharePolicy,
+
+    /// Weights of the payoff components used by the game engine.
+    pub payoff_config: PayoffConfig,
+
+    /// Stack-pointer validation level applied on every context switch.
+    pub stack_check: StackCheck,
+
+    /// Whether the mutual-defection circuit-breaker is enabled.
+    pub defection_recovery: bool,
+
+    /// Consecutive evaluation windows spent at or below
+    /// `DEFECTION_RATIO_THRESHOLD` cooperation.
+    pub defection_windows: u32,
+
+    /// Effective starvation threshold in ticks, rescaled to the active task
+    /// count at each game evaluation.
+    pub starvation_threshold: u32,
+
+    /// Growth of the schedule-time starvation boost.
+    pub boost_curve: BoostCurve,
+
+    /// Ceiling on the schedule-time starvation boost, in priority levels.
+    pub starvation_boost_max: u32,
+
+    /// Bounds, in percent of the configured slice, on the time slice a
+    /// task's payoff earns (see `game::quantum_scale()`).
+    pub quantum_scale_min: u32,
+    pub quantum_scale_max: u32,
+
+    /// Runtime tick rate in Hz. Used to convert millisecond-based task
+    /// configuration into ticks. Starts at `TICK_HZ`.
+    pub tick_hz: u32,
+
+    /// Region task stacks are carved from by `create_task()`.
+    #[cfg(feature = "stack-pool")]
+    pub stack_pool: StackPool,
+
+    /// Deadline bundles, allocated by `create_bundle()`.
+    pub bundles: [DeadlineBundle; MAX_BUNDLES],
+
+    /// Software timers, allocated by `create_timer()`.
+    pub timers: [TimerControlBlock; MAX_TIMERS],
+
+    /// Remaining ticks on which payoffs and the equilibrium status are
+    /// refreshed every tick, set by `request_dense_evaluation()`. `0` means
+    /// the normal interval.
+    pub dense_eval_ticks: u32,
+
+    /// Game evaluations run so far, dense refreshes included.
+    pub evaluations: u64,
+
+    /// Tick at which the current fairness epoch began. Fair shares are
+    /// computed over the ticks since then.
+    pub epoch_start_tick: u64,
+
+    /// Tick at which the current utilization window began (the last game
+    /// evaluation).
+    pub window_start_tick: u64,
+
+    /// Tick from which `uptime_ms()` counts at the current `tick_hz`.
+    /// Moved to the present by each tick rate change.
+    pub uptime_base_tick: u64,
+
+    /// Milliseconds of uptime accrued before `uptime_base_tick`.
+    pub uptime_base_ms: u64,
+
+    /// Ring buffer of `global_cooperation_ratio` at each evaluation.
+    pub cooperation_trend: [u8; COOPERATION_TREND_LEN],
+
+    /// Slot the next ratio is written to.
+    pub cooperation_trend_head: usize,
+
+    /// Ratios recorded, up to `COOPERATION_TREND_LEN`.
+    pub cooperation_trend_len: usize,
+
+    /// Cause of the most recent reschedule request, consumed by the next
+    /// `schedule()`.
+    pub pending_switch_cause: Option<SwitchCause>,
+
+    /// Cause of the most recent context switch to a different task. `None`
+    /// until the first tagged switch.
+    pub last_switch_cause: Option<SwitchCause>,
 }
 
 impl Scheduler {
-    /// Create a new scheduler with an idle task at index 0.
+    /// Create a new scheduler with no tasks.
     pub const fn new() -> Self {
         Self {
             tasks: [TaskControlBlock::EMPTY; MAX_TASKS],
-            current_task: 0,
+            current_task: [0; MAX_CORES],
+            current_core: 0,
             task_count: 0,
             metrics: SystemMetrics::new(),
             tick_count: 0,
+            idle_ticks: 0,
+            context_switches: 0,
+            paused: false,
+            segment_start: None,
+            segment_owner: None,
             needs_reschedule: false,
+            preempt_lock_count: 0,
+            yield_pending: false,
+            forced_next: None,
+            policy: SchedPolicy::GameTheoretic,
+            tie_break: TieBreak::LeastRecentlyRun,
+            overrun_policy: OverrunPolicy::Strict,
+            degrade_policy: DegradePolicy::None,
+            in_equilibrium: false,
+            equilibrium_streak: 0,
+            last_equilibrium: false,
+            evaluations_since_change: 0,
+            quantum_hook: None,
+            idle_hook: None,
+            overload_hook: None,
+            no_tasks_hook: halt_no_tasks,
+            stack_overflow_hook: halt_stack_fault,
+            fair_share_policy: game::equal_fair_share,
+            payoff_config: PayoffConfig::DEFAULT,
+            response_alarm_hook: None,
+            reclassify_hook: None,
+            switch_hook: no_switch_hook,
+            stack_check: StackCheck::None,
+            defection_recovery: true,
+            defection_windows: 0,
+            starvation_threshold: STARVATION_THRESHOLD,
+            boost_curve: BoostCurve::Stepped,
+            starvation_boost_max: STARVATION_BOOST_MAX,
+            quantum_scale_min: QUANTUM_SCALE_MIN,
+            quantum_scale_max: QUANTUM_SCALE_MAX,
+            tick_hz: TICK_HZ,
+            #[cfg(feature = "stack-pool")]
+            stack_pool: StackPool::EMPTY,
+            bundles: [DeadlineBundle::EMPTY; MAX_BUNDLES],
+            timers: [TimerControlBlock::EMPTY; MAX_TIMERS],
+            dense_eval_ticks: 0,
+            evaluations: 0,
+            epoch_start_tick: 0,
+            window_start_tick: 0,
+            uptime_base_tick: 0,
+            uptime_base_ms: 0,
+            cooperation_trend: [0; COOPERATION_TREND_LEN],
+            cooperation_trend_head: 0,
+            cooperation_trend_len: 0,
+            pending_switch_cause: None,
+            last_switch_cause: None,
         }
     }
 
     /// Register a new task with the scheduler.
     ///
+    /// The task takes the lowest free slot: one left by `delete_task()`
+    /// if any, otherwise the next unused one.
+    ///
     /// # Returns
     /// - `Ok(task_id)` — the index of the newly created task
-    /// - `Err(())` — if the task array is full
+    /// - `Err(KernelError::TooManyTasks)` — if the task array is full
+    /// - `Err(KernelError::InvalidPriority)` — if `config.priority` is not
+    ///   below `PRIORITY_LEVELS`
+    /// - `Err(KernelError::InvalidConfig)` — if `config` fails
+    ///   `TaskConfig::validate()`, or the slot's stack cannot hold the
+    ///   initial frame
+    /// - `Err(KernelError::StackPoolExhausted)` — with `stack-pool`, if the
+    ///   pool cannot fit another `STACK_SIZE` stack
     pub fn create_task(
         &mut self,
-        entry: extern "C" fn() -> !,
+        entry: TaskEntry,
         config: TaskConfig,
         strategy: Strategy,
-    ) -> Result<usize, ()> {
-        if self.task_count >= MAX_TASKS {
-            return Err(());
+    ) -> Result<usize, KernelError> {
+        let id = (0..self.task_count)
+            .find(|&i| !self.tasks[i].active)
+            .unwrap_or(self.task_count);
+        if id >= MAX_TASKS {
+            return Err(KernelError::TooManyTasks);
         }
+        config.validate(self.tick_hz)?;
 
-        let id = self.task_count;
+        // A recycled slot keeps the stack it was carved before
+        #[cfg(feature = "stack-pool")]
+        if self.tasks[id].stack.0.is_null() {
+            self.tasks[id].stack =
+                self.stack_pool.carve(STACK_SIZE).ok_or(KernelError::StackPoolExhausted)?;
+        }
+        if self.tasks[id].stack_size() < MIN_STACK_BYTES {
+            return Err(KernelError::InvalidConfig);
+        }
         self.tasks[id].init(id, config, strategy);
+        self.tasks[id].ticks_remaining = config.effective_time_slice(self.tick_hz);
+        self.tasks[id].deadline_tick = self.tick_count + config.deadline_ticks as u64;
+        self.tasks[id].response_threshold = 0;
+        self.tasks[id].idle = false;
+        self.tasks[id].strategy_locked = false;
+        self.tasks[id].entry = Some(entry);
 
         // Initialize the stack frame for context switching
         init_task_stack(&mut self.tasks[id], entry);
 
-        self.task_count += 1;
+        if id == self.task_count {
+            self.task_count += 1;
+        }
         Ok(id)
     }
 
-    /// Called from the SysTick handler every tick.
+    /// Register a batch of tasks all-or-nothing.
     ///
-    /// Updates execution statistics, decrements time slices, and triggers
-    /// periodic game evaluation. Sets `needs_reschedule` if a context
-    /// switch should occur.
-    pub fn tick(&mut self) {
-        self.tick_count += 1;
-
-        // --- Update current task metrics ---
-        let current = self.current_task;
-        if current < self.task_count && self.tasks[current].active {
-            self.tasks[current].payoff.cpu_ticks_used += 1;
-            self.tasks[current].total_ticks += 1;
-            self.tasks[current].period_ticks += 1;
+    /// Tasks are created in order and their ids returned in the same order.
+    /// If any creation fails (e.g., the table fills partway through), every
+    /// task created by this call is discarded and the error returned.
+    pub fn create_tasks<const N: usize>(
+        &mut self,
+        specs: &[(TaskEntry, TaskConfig, Strategy); N],
+    ) -> Result<[usize; N], KernelError> {
+        let start_count = self.task_count;
+        #[cfg(feature = "stack-pool")]
+        let pool_mark = self.stack_pool.mark();
+        let mut ids = [0usize; N];
 
-            // Decrement time slice
-            if self.tasks[current].ticks_remaining > 0 {
-                self.tasks[current].ticks_remaining -= 1;
+        for (slot, &(entry, config, strategy)) in specs.iter().enumerate() {
+            match self.create_task(entry, config, strategy) {
+                Ok(id) => ids[slot] = id,
+                Err(e) => {
+                    // Roll back everything this batch created: recycled
+                    // slots are deleted again, fresh ones return to their
+                    // initial state and give their stacks back to the pool
+                    for &id in &ids[..slot] {
+                        self.tasks[id].active = false;
+                        self.tasks[id].entry = None;
+                        if id < start_count {
+                            self.tasks[id].state = TaskState::Terminated;
+                        } else {
+                            self.tasks[id].state = TaskState::Suspended;
+                            #[cfg(feature = "stack-pool")]
+                            {
+                                self.tasks[id].stack = (core::ptr::null_mut(), 0);
+                            }
+                        }
+                    }
+                    self.task_count = start_count;
+                    #[cfg(feature = "stack-pool")]
+                    self.stack_pool.rewind(pool_mark);
+                    return Err(e);
+                }
             }
+        }
 
-            // Time slice expired → yield to scheduler
-            if self.tasks[current].ticks_remaining == 0 {
-                self.tasks[current].state = TaskState::Ready;
-                self.tasks[current].ticks_remaining =
-                    self.tasks[current].config.effective_time_slice();
-
-                // Check for WCET overrun
-                if self.tasks[current].config.wcet_ticks > 0
-                    && self.tasks[current].period_ticks > self.tasks[current].config.wcet_ticks
-                {
-                    self.tasks[current].record_overrun();
-                }
+        Ok(ids)
+    }
 
-                self.needs_reschedule = true;
-            }
+    /// Register the idle task, run by `schedule()` only when no other task
+    /// is runnable. Its entry must never block; it runs at priority 0 and
+    /// takes no part in the game or CPU accounting.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidState)` — an idle task already exists
+    /// - otherwise as `create_task()`
+    pub fn create_idle_task(&mut self, entry: TaskEntry) -> Result<usize, KernelError> {
+        if self.idle_task().is_some() {
+            return Err(KernelError::InvalidState);
         }
+        let config = TaskConfig { priority: 0, name: "idle", ..TaskConfig::DEFAULT };
+        let id = self.create_task(entry, config, Strategy::Cooperative)?;
+        self.tasks[id].idle = true;
+        Ok(id)
+    }
 
-        // --- Update starvation counters for non-running tasks ---
-        for i in 0..self.task_count {
-            if i != current && self.tasks[i].active && self.tasks[i].state == TaskState::Ready {
-                self.tasks[i].payoff.ticks_since_last_run += 1;
-            }
+    /// Id of the idle task, if one was created.
+    pub fn idle_task(&self) -> Option<usize> {
+        self.tasks[..self.task_count].iter().position(|t| t.active && t.idle)
+    }
+
+    /// Delete a task and free its slot for reuse by a later
+    /// `create_task()`.
+    ///
+    /// The task becomes Terminated and inactive. Donations it made are
+    /// withdrawn, tasks that depend on it as a predecessor are released
+    /// from the constraint, and it leaves its bundle. Deleting the current
+    /// task requests a switch away from it; the caller must never resume
+    /// the deleted context.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidTaskId)` — `id` is not an allocated task
+    /// - `Err(KernelError::InvalidState)` — `id` is the idle task
+    pub fn delete_task(&mut self, id: usize) -> Result<(), KernelError> {
+        if id >= self.task_count || !self.tasks[id].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        if self.tasks[id].idle {
+            return Err(KernelError::InvalidState);
         }
+        let tcb = &mut self.tasks[id];
+        tcb.state = TaskState::Terminated;
+        tcb.active = false;
+        tcb.entry = None;
+        tcb.block_reason = None;
+        tcb.wake_tick = None;
+        tcb.donation = None;
+        tcb.event_wait = None;
 
-        // --- Deadline checking for periodic tasks ---
         for i in 0..self.task_count {
             if !self.tasks[i].active {
                 continue;
             }
-            let deadline = self.tasks[i].config.deadline_ticks;
-            if deadline > 0 && self.tasks[i].period_ticks >= deadline {
-                if self.tasks[i].state == TaskState::Ready
-                    || self.tasks[i].state == TaskState::Running
-                {
-                    // Task was still running/ready at deadline → missed
-                    self.tasks[i].record_deadline_missed();
+            if self.tasks[i].donation.is_some_and(|d| d.donor == id) {
+                self.tasks[i].donation = None;
+            }
+            if self.tasks[i].predecessor == Some(id) {
+                self.tasks[i].predecessor = None;
+                if self.tasks[i].block_reason == Some(BlockReason::Predecessor) {
+                    self.unblock(i);
                 }
-                // Reset period counter
-                self.tasks[i].period_ticks = 0;
             }
         }
+        for bundle in self.bundles.iter_mut() {
+            bundle.members &= !(1 << id);
+            bundle.done &= !(1 << id);
+        }
 
-        // --- Periodic game evaluation ---
-        if self.tick_count % EVAL_FREQUENCY as u64 == 0 {
-            self.evaluate_game();
+        if id == self.current() {
+            self.request_switch(SwitchCause::Block);
         }
+        Ok(())
     }
 
-    /// Run the game-theory evaluation engine.
+    /// Replace a task's entry function.
     ///
-    /// Recomputes payoff for each task, checks equilibrium, and
-    /// updates strategies if the system is not in a stable state.
-    fn evaluate_game(&mut self) {
-        // Update system metrics
-        self.update_system_metrics();
+    /// Only takes effect when the task is next restarted via
+    /// `restart_task()`; a task that is already executing keeps running
+    /// its current code until then.
+    pub fn set_entry(&mut self, id: usize, entry: TaskEntry) -> Result<(), KernelError> {
+        if id >= self.task_count || !self.tasks[id].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        self.tasks[id].entry = Some(entry);
+        Ok(())
+    }
 
-        // Recompute payoff for each active task
-        for i in 0..self.task_count {
-            if self.tasks[i].active {
-                let payoff = game::compute_payoff(&self.tasks[i], &self.metrics);
-                self.tasks[i].payoff.payoff = payoff;
+    /// Change one task's base priority. See `set_priorities()`.
+    pub fn set_priority(&mut self, id: usize, priority: u8) -> Result<(), KernelError> {
+        self.set_priorities(&[(id, priority)])
+    }
+
+    /// Change the base priority of several tasks as one update.
+    ///
+    /// Every entry is validated before any is applied, so an invalid id or
+    /// priority leaves all tasks untouched. On success a single reschedule
+    /// is requested, regardless of how many tasks changed.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidTaskId)` — an id is not an allocated task
+    /// - `Err(KernelError::InvalidPriority)` — a priority is not below
+    ///   `PRIORITY_LEVELS`
+    pub fn set_priorities(&mut self, changes: &[(usize, u8)]) -> Result<(), KernelError> {
+        for &(id, priority) in changes {
+            if id >= self.task_count || !self.tasks[id].active {
+                return Err(KernelError::InvalidTaskId);
+            }
+            if priority as usize >= PRIORITY_LEVELS {
+                return Err(KernelError::InvalidPriority);
             }
         }
 
-        // Check equilibrium and update strategies if needed
-        if !game::is_in_equilibrium(&self.tasks, self.task_count, &self.metrics) {
-            game::update_strategies(&mut self.tasks, self.task_count, &self.metrics);
+        for &(id, priority) in changes {
+            self.tasks[id].config.priority = priority;
         }
-
-        // Starvation prevention: boost starving tasks
-        for i in 0..self.task_count {
-            if self.tasks[i].active
-                && self.tasks[i].payoff.ticks_since_last_run >= STARVATION_THRESHOLD
-            {
-                // Temporary payoff boost to ensure execution
-                self.tasks[i].payoff.payoff += 500;
-                self.needs_reschedule = true;
-            }
+        if !changes.is_empty() {
+            self.request_preemption(SwitchCause::Preempt);
         }
+        Ok(())
     }
 
-    /// Update aggregate system metrics for the game engine.
-    fn update_system_metrics(&mut self) {
-        self.metrics.total_ticks = self.tick_count;
+    /// Change a task's timing at runtime, e.g. for a rate-adaptive loop.
+    ///
+    /// As with `TaskConfigBuilder`, the deadline doubles as the period:
+    /// `deadline_ticks` is used if non-zero, otherwise `period_ticks`. The
+    /// task's period restarts from now with no job outstanding, so the
+    /// change never records a miss for the interrupted period, and a task
+    /// held by deadline enforcement is released at once.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidTaskId)` — `id` is not an allocated task
+    /// - `Err(KernelError::InvalidConfig)` — `period_ticks` and
+    ///   `deadline_ticks` are both zero, or both non-zero and different, or
+    ///   the new timing fails `TaskConfig::validate()`; the task is left
+    ///   unchanged
+    /// - `Err(KernelError::Unschedulable)` — the new timing would push total
+    ///   periodic utilization above 100%; the task is left unchanged
+    pub fn set_timing(
+        &mut self,
+        id: usize,
+        period_ticks: u32,
+        deadline_ticks: u32,
+        wcet_ticks: u32,
+    ) -> Result<(), KernelError> {
+        let old = self.task(id).ok_or(KernelError::InvalidTaskId)?.config;
+        let window = match (period_ticks, deadline_ticks) {
+            (0, 0) => return Err(KernelError::InvalidConfig),
+            (period, 0) => period,
+            (0, deadline) => deadline,
+            (period, deadline) if period == deadline => deadline,
+            _ => return Err(KernelError::InvalidConfig),
+        };
+        let config = TaskConfig { deadline_ticks: window, wcet_ticks, ..old };
+        config.validate(self.tick_hz)?;
 
-        let mut active = 0u32;
-        let mut cooperative = 0u32;
+        self.tasks[id].config = config;
+        if analysis::total_utilization(&self.tasks, self.task_count) > 100 {
+            self.tasks[id].config = old;
+            return Err(KernelError::Unschedulable);
+        }
 
-        for i in 0..self.task_count {
-            if self.tasks[i].active {
-                active += 1;
-                if self.tasks[i].strategy == Strategy::Cooperative {
-                    cooperative += 1;
-                }
-            }
+        if self.tasks[id].block_reason == Some(BlockReason::DeadlineOverrun) {
+            self.wake_task(id, WakeReason::Cancelled);
         }
+        let tcb = &mut self.tasks[id];
+        tcb.period_ticks = 0;
+        tcb.deadline_tick = self.tick_count + tcb.config.deadline_ticks as u64;
+        tcb.job_done = false;
+        tcb.job_late = false;
+        tcb.deadline_enforced = false;
+        tcb.job_overran = false;
+        tcb.overload_streak = 0;
+        tcb.release_stride = 1;
+        Ok(())
+    }
 
-        self.metrics.active_tasks = active;
-        self.metrics.global_cooperation_ratio = if active > 0 {
-            cooperative * 100 / active
-        } else {
-            100
+    /// Restart a task from its (possibly updated) entry function.
+    ///
+    /// Resets the task's payoff metrics and execution counters, rebuilds its
+    /// initial stack frame and makes it Ready. The task keeps its
+    /// configuration and strategy.
+    ///
+    /// The currently running task cannot restart itself: its stack is live,
+    /// and the next context switch would overwrite the fresh frame. Such a
+    /// request returns `KernelError::InvalidState`.
+    pub fn restart_task(&mut self, id: usize) -> Result<(), KernelError> {
+        if id >= self.task_count || !self.tasks[id].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        if id == self.current() && self.tasks[id].state == TaskState::Running {
+            return Err(KernelError::InvalidState);
+        }
+        let entry = match self.tasks[id].entry {
+            Some(entry) => entry,
+            None => return Err(KernelError::InvalidState),
         };
 
-        // Overload: more ready tasks than cores can serve
-        self.metrics.overload = active > crate::config::MAX_CORES as u32;
+        let config = self.tasks[id].config;
+        let strategy = self.tasks[id].strategy;
+        self.tasks[id].init(id, config, strategy);
+        self.tasks[id].ticks_remaining = config.effective_time_slice(self.tick_hz);
+        self.tasks[id].deadline_tick = self.tick_count + config.deadline_ticks as u64;
+        init_task_stack(&mut self.tasks[id], entry);
+
+        self.request_preemption(SwitchCause::Preempt);
+        Ok(())
     }
 
-    /// Select the next task to run.
+    /// Called from the SysTick handler every tick.
     ///
-    /// Picks the highest effective-priority runnable task that can run on core 0.
-    /// Effective priority = base priority + payoff-adjusted weight.
+    /// Updates execution statistics, decrements time slices, and triggers
+    /// periodic game evaluation. Sets `needs_reschedule` if a context
+    /// switch should occur. Does nothing while paused.
+    ///
+    /// With several cores, one core drives the tick: its current task is
+    /// charged the tick and consumes its slice, while tasks on the other
+    /// cores run until they yield or block.
+    pub fn tick(&mut self) {
+        if self.paused {
+            return;
+        }
+        self.advance_tick();
+    }
+
+    /// Change the tick rate to `hz`, translating every pending tick count
+    /// so it keeps its wall-clock duration: wake times, task deadlines,
+    /// WCETs, starvation thresholds and execution budgets, the progress
+    /// through the current period and budget period, remaining time
+    /// slices, donation expiries, bundle deadlines and software timers.
+    /// Durations round up and a non-zero one never becomes 0.
     ///
-    /// If no task is runnable, returns 0 (the idle task).
+    /// Accumulated statistics (CPU ticks, idle ticks, `tick_count`) and
+    /// the tick-based tuning constants are left as they are.
     ///
     /// # Returns
-    /// Index of the next task to run.
-    pub fn schedule(&mut self) -> usize {
-        let mut best_task: usize = 0; // fallback to idle/first task
-        let mut best_priority: i32 = i32::MIN;
-
-        for i in 0..self.task_count {
-            if !self.tasks[i].is_runnable() {
-                continue;
-            }
-
-            // Must be able to run on core 0 (single-core)
-            if !self.tasks[i].can_run_on_core(0) {
-                continue;
-            }
-
-            let eff_prio = self.tasks[i].effective_priority();
-
-            // Starvation boost: add extra priority weight for starving tasks
-            let starvation_boost = if self.tasks[i].payoff.ticks_since_last_run >= STARVATION_THRESHOLD {
-                (self.tasks[i].payoff.ticks_since_last_run / STARVATION_THRESHOLD) as i32 * 2
-            } else {
-                0
-            };
-
-            let total_prio = eff_prio + starvation_boost;
+    /// `Err(KernelError::InvalidConfig)` if `hz` is 0.
+    pub fn set_tick_hz(&mut self, hz: u32) -> Result<(), KernelError> {
+        if hz == 0 {
+            return Err(KernelError::InvalidConfig);
+        }
+        let from = self.tick_hz;
+        if hz == from {
+            return Ok(());
+        }
+        let now = self.tick_count;
+        let span = |ticks: u32| rescale_ticks(ticks as u64, from, hz) as u32;
+        let at = |tick: u64| now + rescale_ticks(tick.saturating_sub(now), from, hz);
 
-            if total_prio > best_priority {
-                best_priority = total_prio;
-                best_task = i;
+        for tcb in self.tasks[..self.task_count].iter_mut().filter(|t| t.active) {
+            tcb.wake_tick = tcb.wake_tick.map(at);
+            tcb.config.deadline_ticks = span(tcb.config.deadline_ticks);
+            tcb.config.wcet_ticks = span(tcb.config.wcet_ticks);
+            tcb.config.starvation_threshold = span(tcb.config.starvation_threshold);
+            tcb.config.budget_ticks = span(tcb.config.budget_ticks);
+            tcb.config.budget_period = span(tcb.config.budget_period);
+            tcb.budget_elapsed = (tcb.budget_elapsed as u64 * hz as u64 / from as u64) as u32;
+            tcb.period_ticks = (tcb.period_ticks as u64 * hz as u64 / from as u64) as u32;
+            tcb.deadline_tick = at(tcb.deadline_tick);
+            tcb.ticks_remaining = span(tcb.ticks_remaining);
+            if let Some(donation) = tcb.donation.as_mut() {
+                donation.expires_tick = donation.expires_tick.map(at);
             }
         }
-
-        // Mark previous task as Ready (if it was Running)
-        let prev = self.current_task;
-        if prev < self.task_count && self.tasks[prev].state == TaskState::Running {
-            self.tasks[prev].state = TaskState::Ready;
+        for bundle in self.bundles.iter_mut().filter(|b| b.active) {
+            bundle.deadline_ticks = span(bundle.deadline_ticks);
+            bundle.elapsed = (bundle.elapsed as u64 * hz as u64 / from as u64) as u32;
         }
-
-        // Mark new task as Running
-        if best_task < self.task_count {
-            self.tasks[best_task].state = TaskState::Running;
-            self.tasks[best_task].payoff.ticks_since_last_run = 0;
+        for timer in self.timers.iter_mut().filter(|t| t.active) {
+            timer.period = span(timer.period);
+            timer.remaining = span(timer.remaining);
         }
+        self.uptime_base_ms = self.uptime_ms();
+        self.uptime_base_tick = now;
+        self.tick_hz = hz;
+        Ok(())
+    }
 
-        self.current_task = best_task;
-        self.needs_reschedule = false;
+    /// Milliseconds since start, counting each tick at the rate it was
+    /// taken at, so tick rate changes do not distort it. Rounds down.
+    pub fn uptime_ms(&self) -> u64 {
+        let ticks = self.tick_count.saturating_sub(self.uptime_base_tick);
+        self.uptime_base_ms + ticks * 1000 / self.tick_hz as u64
+    }
 
-        best_task
+    /// Freeze the scheduler: further ticks are dropped, so no counter or
+    /// timeout advances until `resume()`.
+    pub fn pause(&mut self) {
+        self.paused = true;
     }
 
-    /// Record a voluntary yield from the current task.
+    /// Let ticks advance the scheduler again after `pause()`.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Keep the running task on the CPU without masking interrupts: ticks,
+    /// wakeups and timers still run, but `schedule()` defers any switch
+    /// until the matching `preempt_enable()`. Nests.
     ///
-    /// Called from `kernel::yield_task()`. Marks the current task as Ready,
-    /// records the yield in payoff metrics, and triggers rescheduling.
-    pub fn yield_current(&mut self) {
-        let current = self.current_task;
-        if current < self.task_count && self.tasks[current].active {
-            self.tasks[current].state = TaskState::Ready;
-            self.tasks[current].record_yield();
-            self.tasks[current].ticks_remaining =
-                self.tasks[current].config.effective_time_slice();
-            self.needs_reschedule = true;
-        }
+    /// A task that blocks or is deleted while holding the lock still
+    /// switches away; the lock only guards against preemption.
+    pub fn preempt_disable(&mut self) {
+        self.preempt_lock_count += 1;
     }
 
-    /// Get a reference to the current task's TCB.
-    pub fn current_tcb(&self) -> &TaskControlBlock {
-        &self.tasks[self.current_task]
+    /// Undo one `preempt_disable()`. Unbalanced calls are ignored.
+    ///
+    /// # Returns
+    /// `true` if this released the last level and a reschedule was
+    /// deferred meanwhile (including a yield), so the caller should
+    /// trigger it now.
+    pub fn preempt_enable(&mut self) -> bool {
+        if self.preempt_lock_count == 0 {
+            return false;
+        }
+        self.preempt_lock_count -= 1;
+        if self.preempt_lock_count > 0 {
+            return false;
+        }
+        if self.yield_pending {
+            self.yield_pending = false;
+            self.yield_current();
+        }
+        self.needs_reschedule
     }
 
-    /// Get a mutable reference to the current task's TCB.
-    pub fn current_tcb_mut(&mut self) -> &mut TaskControlBlock {
-        &mut self.tasks[self.current_task]
+    /// Whether `preempt_disable()` is in effect.
+    pub fn preemption_locked(&self) -> bool {
+        self.preempt_lock_count > 0
     }
-}
 
-// ---------------------------------------------------------------------------
-// Stack initialization helper
-// ---------------------------------------------------------------------------
+    /// Advance exactly one tick while paused, for single-stepping from a
+    /// debugger. Behaves like a normal `tick()` when not paused.
+    pub fn single_tick(&mut self) {
+        self.advance_tick();
+    }
 
-/// Initialize a task's stack frame for first-time context switch.
-///
-/// The Cortex-M4 hardware automatically pushes an exception frame on
-/// interrupt entry. We pre-populate this frame on the task's stack so
-/// that the first PendSV "return" starts executing the task function.
-///
-/// ## Stack Layout (top = high address, growing down)
-///
-/// ```text
-/// [Hardware stacked frame]   <- initial PSP points here
-///   xPSR  (Thumb bit set)
-///   PC    (task entry point)
-///   LR    (task_exit)
-///   R12   (0)
-///   R3    (0)
-///   R2    (0)
-///   R1    (0)
-///   R0    (0)
-/// [Software saved context]
-///   R11   (0)
-///   R10   (0)
-///   R9    (0)
-///   R8    (0)
-///   R7    (0)
-///   R6    (0)
-///   R5    (0)
-///   R4    (0)              <- stack_pointer after init
-/// ```
-fn init_task_stack(tcb: &mut TaskControlBlock, entry: extern "C" fn() -> !) {
-    let stack_top = tcb.stack.as_ptr() as usize + STACK_SIZE;
-    // Align to 8 bytes (AAPCS requirement)
-    let aligned_top = stack_top & !0x07;
+    /// One tick of scheduler bookkeeping, regardless of `paused`.
+    fn advance_tick(&mut self) {
+        self.tick_count += 1;
+        self.metrics.recent_ticks += 1;
 
-    // We need space for 16 registers (8 HW + 8 SW)
-    let frame_ptr = (aligned_top - 16 * 4) as *mut u32;
+        // --- Update current task metrics ---
+        // A blocked fallback task is parked, not running, and the idle task
+        // is not a player: charge the tick to idle rather than to the task.
+        let current = self.current();
+        let running = self.current_is_running();
+        if !running {
+            self.idle_ticks += 1;
+            if let Some(idle) = self.idle_task() {
+                self.tasks[idle].total_ticks += 1;
+                self.tasks[idle].window_ticks_used += 1;
+            }
+        }
+        if running && self.tasks[current].active {
+            self.tasks[current].payoff.cpu_ticks_used += 1;
+            self.tasks[current].payoff.ticks_since_last_yield += 1;
+            self.tasks[current].payoff.run_streak += 1;
+            if self.tasks[current].payoff.run_streak > self.tasks[current].payoff.max_run_streak {
+                self.tasks[current].payoff.max_run_streak = self.tasks[current].payoff.run_streak;
+            }
+            self.tasks[current].total_ticks += 1;
+            self.tasks[current].window_ticks_used += 1;
+            self.tasks[current].recent_ticks_used += 1;
+            self.tasks[current].period_ticks += 1;
 
-    unsafe {
-        // Software-saved registers (R4–R11) — bottom of frame
-        for i in 0..8 {
-            *frame_ptr.add(i) = 0; // R4, R5, R6, R7, R8, R9, R10, R11
+            self.consume_time_slice(current);
+            self.charge_budget(current);
         }
 
-        // Hardware-stacked frame (R0–R3, R12, LR, PC, xPSR)
-        *frame_ptr.add(8) = 0;  // R0
-        *frame_ptr.add(9) = 0;  // R1
-        *frame_ptr.add(10) = 0; // R2
-        *frame_ptr.add(11) = 0; // R3
-        *frame_ptr.add(12) = 0; // R12
-        *frame_ptr.add(13) = task_exit as u32; // LR — return address if task returns
-        *frame_ptr.add(14) = entry as u32;     // PC — task entry point
-        *frame_ptr.add(15) = 0x0100_0000;      // xPSR — Thumb bit set
+        // --- Replenish execution budgets ---
+        self.replenish_budgets();
+
+        // --- Wake tasks whose timed wait has expired ---
+        for i in 0..self.task_count {
+            if let Some(wake) = self.tasks[i].wake_tick {
+                if self.tasks[i].state == TaskState::Blocked && wake <= self.tick_count {
+                    if !matches!(
+                        self.tasks[i].block_reason,
+                        Some(BlockReason::Delay) | Some(BlockReason::DeadlineOverrun)
+                    ) {
+                        self.tasks[i].payoff.timeouts += 1;
+                    }
+                    self.wake_task(i, WakeReason::Timeout);
+                }
+            }
+        }
+
+        // --- Lapse expired priority donations ---
+        for i in 0..self.task_count {
+            if let Some(donation) = self.tasks[i].donation {
+                if donation.expires_tick.is_some_and(|t| t <= self.tick_count) {
+                    self.tasks[i].donation = None;
+                    self.request_preemption(SwitchCause::Preempt);
+                }
+            }
+        }
+
+        // --- Update starvation counters for non-running tasks ---
+        for i in 0..self.task_count {
+            if i != current && self.tasks[i].is_player() && self.tasks[i].state == TaskState::Ready {
+                self.tasks[i].payoff.ticks_since_last_run += 1;
+            }
+        }
+
+        // --- Deadline checking for periodic tasks ---
+        // Every period runs on the release clock, whether or not the task
+        // got the CPU during it
+        for i in 0..self.task_count {
+            if !self.tasks[i].active {
+                continue;
+            }
+            let deadline = self.tasks[i].deadline_tick;
+            if self.tasks[i].release_window() > 0 && self.tick_count >= deadline {
+                if self.tasks[i].job_done || self.tasks[i].deadline_enforced {
+                    // Outcome already recorded by job_complete() or by the
+                    // deadline compare interrupt
+                } else if self.tasks[i].state == TaskState::Ready
+                    || self.tasks[i].state == TaskState::Running
+                {
+                    // Task was still running/ready at deadline → missed
+                    self.record_missed_job(i);
+                    if self.tasks[i].config.enforce_deadline {
+                        self.hold_until_next_period(i, deadline);
+                        continue;
+                    }
+                }
+                self.begin_period(i, deadline);
+            }
+        }
+
+        // --- Shared deadlines of task bundles ---
+        for b in 0..MAX_BUNDLES {
+            self.check_bundle_deadline(b);
+        }
+
+        // --- Software timers (callbacks run in tick context) ---
+        for t in 0..MAX_TIMERS {
+            if let Some(callback) = self.timers[t].tick() {
+                callback();
+            }
+        }
+
+        // --- Periodic game evaluation (payoffs every tick while dense) ---
+        if self.tick_count.is_multiple_of(EVAL_FREQUENCY as u64) {
+            self.evaluate_game();
+        } else if self.dense_eval_ticks > 0 {
+            self.evaluate_dense();
+        }
+        self.dense_eval_ticks = self.dense_eval_ticks.saturating_sub(1);
+
+        #[cfg(feature = "debug-assertions")]
+        crate::invariant::enforce(self);
+    }
+
+    /// Charge one tick against the current task's time slice, preempting
+    /// it when the slice runs out. Under `cooperative-only` the slice still
+    /// paces WCET overrun checks, but the task keeps running.
+    fn consume_time_slice(&mut self, current: usize) {
+        // Decrement time slice
+        if self.tasks[current].ticks_remaining > 0 {
+            self.tasks[current].ticks_remaining -= 1;
+        }
+
+        // Time slice expired → yield to scheduler
+        if self.tasks[current].ticks_remaining == 0 {
+            #[cfg(not(feature = "cooperative-only"))]
+            {
+                self.tasks[current].state = TaskState::Ready;
+            }
+            self.tasks[current].ticks_remaining = self.quantum(current);
+
+            if let Some(hook) = self.quantum_hook {
+                hook(current);
+            }
+
+            // Check for WCET overrun
+            if self.tasks[current].config.wcet_ticks > 0
+                && self.tasks[current].period_ticks > self.tasks[current].config.wcet_ticks
+            {
+                if self.overrun_policy == OverrunPolicy::SoftIfMet
+                    && self.tasks[current].config.deadline_ticks > 0
+                {
+                    // Penalized once the deadline outcome is known
+                    self.tasks[current].job_overran = true;
+                } else {
+                    self.tasks[current].record_overrun();
+                }
+            }
+
+            #[cfg(not(feature = "cooperative-only"))]
+            self.request_switch(SwitchCause::SliceExpiry);
+        }
+    }
+
+    /// Charge one tick to the current task's execution budget, blocking it
+    /// once the budget is used up.
+    fn charge_budget(&mut self, current: usize) {
+        let tcb = &mut self.tasks[current];
+        if tcb.config.budget_ticks == 0 {
+            return;
+        }
+        tcb.budget_used += 1;
+        // The tick may already have ended its time slice (Ready)
+        if tcb.budget_used >= tcb.config.budget_ticks
+            && matches!(tcb.state, TaskState::Running | TaskState::Ready)
+        {
+            tcb.state = TaskState::Blocked;
+            tcb.block_reason = Some(BlockReason::BudgetExhausted);
+            tcb.payoff.run_streak = 0;
+            self.request_switch(SwitchCause::Block);
+        }
+    }
+
+    /// Advance every budgeted task's budget period, refilling the budget
+    /// and releasing a task held for exhausting it at each period start.
+    fn replenish_budgets(&mut self) {
+        for i in 0..self.task_count {
+            let tcb = &mut self.tasks[i];
+            if !tcb.active || tcb.config.budget_ticks == 0 {
+                continue;
+            }
+            tcb.budget_elapsed += 1;
+            if tcb.budget_elapsed < tcb.config.budget_period {
+                continue;
+            }
+            tcb.budget_elapsed = 0;
+            tcb.budget_used = 0;
+            if tcb.state == TaskState::Blocked && tcb.block_reason == Some(BlockReason::BudgetExhausted) {
+                self.wake_task(i, WakeReason::Timeout);
+            }
+        }
+    }
+
+    /// Start task `i`'s next period, released at absolute tick `release`.
+    fn begin_period(&mut self, i: usize, release: u64) {
+        self.tasks[i].deadline_tick = release + self.tasks[i].release_window() as u64;
+        self.tasks[i].period_ticks = 0;
+        self.tasks[i].job_done = false;
+        self.tasks[i].deadline_enforced = false;
+        self.tasks[i].job_overran = false;
+
+        // New period: wait for the predecessor again
+        self.rearm_predecessor(i);
+    }
+
+    /// Deadline enforcement: block task `i`, which just missed its
+    /// `deadline`, through the following period, and release its next job
+    /// at the period boundary one release window later.
+    fn hold_until_next_period(&mut self, i: usize, deadline: u64) {
+        let release = deadline + self.tasks[i].release_window() as u64;
+        self.begin_period(i, release);
+        let tcb = &mut self.tasks[i];
+        tcb.state = TaskState::Blocked;
+        tcb.block_reason = Some(BlockReason::DeadlineOverrun);
+        tcb.wake_tick = Some(release);
+        tcb.payoff.run_streak = 0;
+        if i == self.current() {
+            self.request_switch(SwitchCause::Deadline);
+        }
+    }
+
+    /// Record a missed deadline for task `i`'s outstanding job and force a
+    /// reschedule.
+    fn record_missed_job(&mut self, i: usize) {
+        self.tasks[i].record_deadline_missed();
+        self.tasks[i].job_late = true;
+        if self.tasks[i].job_overran {
+            self.tasks[i].record_overrun();
+        }
+        // Unfinished at its deadline
+        self.note_overloaded_period(i);
+        self.request_preemption(SwitchCause::Deadline);
+    }
+
+    /// Advance a bundle's period and, at its deadline, credit every member
+    /// if all completed or penalize the ones that did not.
+    fn check_bundle_deadline(&mut self, b: usize) {
+        let bundle = &mut self.bundles[b];
+        if !bundle.active || bundle.members == 0 {
+            return;
+        }
+        bundle.elapsed += 1;
+        if bundle.elapsed < bundle.deadline_ticks {
+            return;
+        }
+
+        let (members, done) = (bundle.members, bundle.done);
+        if done & members == members {
+            bundle.met += 1;
+        } else {
+            bundle.missed += 1;
+        }
+        bundle.elapsed = 0;
+        bundle.done = 0;
+
+        for id in 0..self.task_count {
+            if members & (1 << id) == 0 || !self.tasks[id].active {
+                continue;
+            }
+            if done & members == members {
+                self.tasks[id].record_deadline_met();
+            } else if done & (1 << id) == 0 {
+                self.tasks[id].record_deadline_missed();
+            }
+        }
+    }
+
+    /// Request a reschedule, tagging it with the cause recorded if it leads
+    /// to a context switch. The most recent request wins.
+    fn request_switch(&mut self, cause: SwitchCause) {
+        self.needs_reschedule = true;
+        self.pending_switch_cause = Some(cause);
+    }
+
+    /// Ask for the current task to be switched out in favour of another.
+    ///
+    /// Under `cooperative-only` a task that is still Running is never
+    /// preempted; the request only goes through when the CPU is idle.
+    fn request_preemption(&mut self, cause: SwitchCause) {
+        #[cfg(feature = "cooperative-only")]
+        if self.current_is_running() {
+            return;
+        }
+        self.request_switch(cause);
+    }
+
+    /// Close the current run segment at cycle-counter reading `now` and open
+    /// a new one for the task now current.
+    ///
+    /// The elapsed cycles are charged to the segment's owner and to
+    /// `metrics.total_cycles` (and the recent counts); idle segments count
+    /// only towards the totals.
+    /// The counter may wrap between readings, so segments must be shorter
+    /// than one full counter period. Called after every `schedule()` by the
+    /// port when the `cycle-accounting` feature is enabled.
+    pub fn account_cycles(&mut self, now: u32) {
+        if let Some(start) = self.segment_start {
+            let elapsed = now.wrapping_sub(start) as u64;
+            self.metrics.total_cycles += elapsed;
+            self.metrics.recent_cycles += elapsed;
+            if let Some(owner) = self.segment_owner {
+                self.tasks[owner].payoff.cpu_cycles_used += elapsed;
+                self.tasks[owner].recent_cycles_used += elapsed;
+            }
+        }
+        self.segment_start = Some(now);
+        self.segment_owner = if self.current_is_running() {
+            Some(self.current())
+        } else {
+            None
+        };
+    }
+
+    /// Whether the current task is actually Running (not an idle fallback
+    /// or the idle task).
+    fn current_is_running(&self) -> bool {
+        let current = self.current();
+        current < self.task_count
+            && self.tasks[current].state == TaskState::Running
+            && !self.tasks[current].idle
+    }
+
+    /// Run the game evaluation immediately rather than waiting for the next
+    /// `EVAL_FREQUENCY` boundary. Useful under `cooperative-only`, where
+    /// the application decides when strategies are revisited.
+    pub fn evaluate_now(&mut self) {
+        self.evaluate_game();
+    }
+
+    /// Recompute payoffs and check equilibrium on every tick for the next
+    /// `windows` evaluation windows (`windows * EVAL_FREQUENCY` ticks),
+    /// then fall back to the normal interval. Use after a transient such as
+    /// a mode change or a burst of deadline misses, when the system is far
+    /// from equilibrium.
+    ///
+    /// Everything that keeps history — decay, the window reset, the
+    /// starvation boost, strategy hysteresis and reclassification — still
+    /// runs only on `EVAL_FREQUENCY` boundaries, so a dense burst does not
+    /// age it faster. A request never shortens a dense period already in
+    /// progress.
+    pub fn request_dense_evaluation(&mut self, windows: u32) {
+        let ticks = windows.saturating_mul(EVAL_FREQUENCY);
+        self.dense_eval_ticks = self.dense_eval_ticks.max(ticks);
+    }
+
+    /// Run the game-theory evaluation engine.
+    ///
+    /// Recomputes payoff for each task, checks equilibrium, and
+    /// updates strategies if the system is not in a stable state.
+    fn evaluate_game(&mut self) {
+        self.evaluations += 1;
+
+        // Update system metrics
+        self.update_system_metrics();
+        self.record_cooperation_ratio();
+        self.starvation_threshold = scaled_starvation_threshold(self.metrics.active_tasks);
+
+        self.recompute_payoffs();
+        for tcb in self.tasks[..self.task_count].iter_mut().filter(|t| t.is_player()) {
+            // New streak window: keep only a streak still in progress
+            tcb.payoff.max_run_streak = tcb.payoff.run_streak;
+        }
+
+        let mut before = [Strategy::Cooperative; MAX_TASKS];
+        for (strategy, tcb) in before.iter_mut().zip(&self.tasks[..self.task_count]) {
+            *strategy = tcb.strategy;
+        }
+
+        // Declared strategies that the window's behaviour contradicts
+        let reclassified = game::reclassify_strategies(
+            &mut self.tasks,
+            self.task_count,
+            &self.metrics,
+            self.fair_share_policy,
+            self.tick_count - self.window_start_tick,
+        );
+        if let Some(hook) = self.reclassify_hook {
+            for id in (0..self.task_count).filter(|id| reclassified & (1 << id) != 0) {
+                hook(id, self.tasks[id].strategy);
+            }
+        }
+
+        // Check equilibrium and update strategies if needed
+        let equilibrium = self.check_equilibrium();
+        if !equilibrium {
+            game::update_strategies(&mut self.tasks, self.task_count, &self.metrics);
+        }
+        game::play_tit_for_tat(&mut self.tasks, self.task_count, &self.metrics);
+
+        self.defection_circuit_breaker();
+
+        let changed = self.tasks[..self.task_count]
+            .iter()
+            .zip(&before)
+            .any(|(tcb, &strategy)| tcb.strategy != strategy);
+        if changed {
+            self.evaluations_since_change = 0;
+        } else {
+            self.evaluations_since_change = self.evaluations_since_change.saturating_add(1);
+        }
+
+        // Starvation prevention: boost starving tasks
+        for i in 0..self.task_count {
+            if self.tasks[i].is_player()
+                && self.tasks[i].payoff.ticks_since_last_run >= self.task_starvation_threshold(i)
+            {
+                // Temporary payoff boost to ensure execution
+                self.tasks[i].payoff.payoff += 500;
+                self.request_preemption(SwitchCause::Starvation);
+            }
+        }
+
+        // New utilization window; older behaviour weighs less from now on
+        self.window_start_tick = self.tick_count;
+        self.metrics.recent_ticks -= self.metrics.recent_ticks >> FAIRNESS_DECAY_SHIFT;
+        self.metrics.recent_cycles -= self.metrics.recent_cycles >> FAIRNESS_DECAY_SHIFT;
+        for tcb in self.tasks[..self.task_count].iter_mut() {
+            tcb.window_ticks_used = 0;
+            tcb.window_yields = 0;
+            tcb.recent_ticks_used -= tcb.recent_ticks_used >> FAIRNESS_DECAY_SHIFT;
+            tcb.recent_cycles_used -= tcb.recent_cycles_used >> FAIRNESS_DECAY_SHIFT;
+            tcb.payoff.decay_recent(PAYOFF_DECAY_SHIFT);
+        }
+    }
+
+    /// The part of the evaluation a dense tick between window boundaries
+    /// runs: fresh payoffs and equilibrium status, leaving strategies and
+    /// the window's history alone.
+    fn evaluate_dense(&mut self) {
+        self.evaluations += 1;
+        self.update_system_metrics();
+        self.recompute_payoffs();
+        self.check_equilibrium();
+    }
+
+    /// Recompute the payoff of each active task from the current metrics.
+    fn recompute_payoffs(&mut self) {
+        for i in 0..self.task_count {
+            if self.tasks[i].is_player() {
+                let payoff = game::compute_payoff_with(
+                    &self.tasks[i],
+                    &self.metrics,
+                    &self.payoff_config,
+                    self.fair_share_policy,
+                );
+                self.tasks[i].payoff.payoff = payoff;
+            }
+        }
+    }
+
+    /// Check for equilibrium and fold the result into the debounced status.
+    ///
+    /// # Returns
+    /// The raw, undebounced result.
+    fn check_equilibrium(&mut self) -> bool {
+        let equilibrium =
+            game::is_in_equilibrium(&self.tasks, self.task_count, &self.metrics, &self.payoff_config);
+        self.debounce_equilibrium(equilibrium);
+        self.last_equilibrium = equilibrium;
+        equilibrium
+    }
+
+    /// Break out of sustained mutual defection.
+    ///
+    /// When the cooperation ratio stays at or below
+    /// `DEFECTION_RATIO_THRESHOLD` for `DEFECTION_RECOVERY_WINDOWS`
+    /// evaluations, the lowest-payoff selfish task is switched back to
+    /// Cooperative. Disabled by `set_defection_recovery(false)`.
+    fn defection_circuit_breaker(&mut self) {
+        if !self.defection_recovery || self.metrics.active_tasks == 0 {
+            self.defection_windows = 0;
+            return;
+        }
+        if self.metrics.global_cooperation_ratio > DEFECTION_RATIO_THRESHOLD {
+            self.defection_windows = 0;
+            return;
+        }
+
+        self.defection_windows += 1;
+        if self.defection_windows >= DEFECTION_RECOVERY_WINDOWS {
+            game::nudge_to_cooperation(&mut self.tasks, self.task_count);
+            self.defection_windows = 0;
+        }
+    }
+
+    /// Enable or disable the mutual-defection circuit-breaker.
+    pub fn set_defection_recovery(&mut self, enabled: bool) {
+        self.defection_recovery = enabled;
+        self.defection_windows = 0;
+    }
+
+    /// Fold one raw equilibrium check into the debounced status.
+    fn debounce_equilibrium(&mut self, raw: bool) {
+        if raw == self.in_equilibrium {
+            self.equilibrium_streak = 0;
+            return;
+        }
+        self.equilibrium_streak += 1;
+        if self.equilibrium_streak >= EQUILIBRIUM_DEBOUNCE {
+            self.in_equilibrium = raw;
+            self.equilibrium_streak = 0;
+        }
+    }
+
+    /// Update aggregate system metrics for the game engine.
+    fn update_system_metrics(&mut self) {
+        self.metrics.total_ticks = self.tick_count - self.epoch_start_tick;
+
+        let mut active = 0u32;
+        let mut cooperative = 0u32;
+
+        for i in 0..self.task_count {
+            if self.tasks[i].is_player() {
+                active += 1;
+                if self.tasks[i].is_cooperating() {
+                    cooperative += 1;
+                }
+            }
+        }
+
+        self.metrics.active_tasks = active;
+        self.metrics.global_cooperation_ratio = (cooperative * 100).checked_div(active).unwrap_or(100);
+
+        // Overload: more ready tasks than cores can serve
+        self.metrics.overload = active > crate::config::MAX_CORES as u32;
+    }
+
+    /// Start a new fairness epoch, e.g. at an operational phase boundary,
+    /// so CPU use in the previous phase no longer biases fair shares.
+    ///
+    /// Every task's CPU accounting and the system-wide fair-share baseline
+    /// restart from zero; with `reset_payoff`, payoff trends and
+    /// cooperation scores also return to neutral. Strategies are kept.
+    pub fn begin_fairness_epoch(&mut self, reset_payoff: bool) {
+        self.epoch_start_tick = self.tick_count;
+        self.metrics.total_ticks = 0;
+        self.metrics.total_cycles = 0;
+        self.metrics.recent_ticks = 0;
+        self.metrics.recent_cycles = 0;
+        for tcb in self.tasks[..self.task_count].iter_mut() {
+            tcb.payoff.reset_cpu_accounting();
+            tcb.recent_ticks_used = 0;
+            tcb.recent_cycles_used = 0;
+            if reset_payoff {
+                tcb.payoff.reset_payoff_trend();
+            }
+        }
+    }
+
+    /// Append the current `global_cooperation_ratio` to the trend, dropping
+    /// the oldest entry once full.
+    fn record_cooperation_ratio(&mut self) {
+        self.cooperation_trend[self.cooperation_trend_head] = self.metrics.global_cooperation_ratio as u8;
+        self.cooperation_trend_head = (self.cooperation_trend_head + 1) % COOPERATION_TREND_LEN;
+        if self.cooperation_trend_len < COOPERATION_TREND_LEN {
+            self.cooperation_trend_len += 1;
+        }
+    }
+
+    /// Copy the recorded cooperation ratios (percent, one per evaluation
+    /// window) into `out`, oldest first. If `out` is shorter than the
+    /// history, it receives the most recent entries.
+    ///
+    /// # Returns
+    /// Number of entries written.
+    pub fn cooperation_trend(&self, out: &mut [u8]) -> usize {
+        let count = self.cooperation_trend_len.min(out.len());
+        let first = self.cooperation_trend_head + COOPERATION_TREND_LEN - count;
+        for (i, slot) in out[..count].iter_mut().enumerate() {
+            *slot = self.cooperation_trend[(first + i) % COOPERATION_TREND_LEN];
+        }
+        count
+    }
+
+    /// Discard the recorded cooperation trend.
+    pub fn reset_cooperation_trend(&mut self) {
+        self.cooperation_trend_head = 0;
+        self.cooperation_trend_len = 0;
+    }
+
+    /// Select the task the scheduler launches first.
+    ///
+    /// With no task created at all (the idle task aside) there is nothing
+    /// to launch, so the no-tasks hook runs instead of returning. Tasks
+    /// that exist but are all blocked are a normal start: the CPU idles
+    /// until one wakes.
+    pub fn start_first(&mut self) -> usize {
+        if !self.tasks[..self.task_count].iter().any(|t| t.is_player()) {
+            (self.no_tasks_hook)();
+        }
+        self.schedule()
+    }
+
+    /// Select the next task to run.
+    ///
+    /// Delegates the choice to the active `SchedPolicy`, then updates task
+    /// states. Under the default `GameTheoretic` policy this picks the
+    /// highest effective-priority runnable task that can run on
+    /// `current_core`; see [`Self::schedule_for_core`].
+    ///
+    /// If no task is runnable, returns the idle task (or 0 without one).
+    ///
+    /// # Returns
+    /// Index of the next task to run.
+    pub fn schedule(&mut self) -> usize {
+        // Preemption locked: a current task that can still run keeps the
+        // CPU (even past its slice) and the request stays pending
+        let current = self.current();
+        if self.preemption_locked()
+            && current < self.task_count
+            && self.tasks[current].active
+            && matches!(self.tasks[current].state, TaskState::Running | TaskState::Ready)
+        {
+            self.tasks[current].state = TaskState::Running;
+            return current;
+        }
+
+        // A directed yield overrides the policy for this one switch
+        let best_task = match self.forced_next.take() {
+            Some(id) if self.can_force(id) => id,
+            _ => self.policy.select_next(self),
+        };
+
+        // Mark previous task as Ready (if it was Running)
+        let prev = self.current();
+        if prev < self.task_count && prev != best_task {
+            self.tasks[prev].payoff.run_streak = 0;
+        }
+        // A still-running task displaced by a preemption request was
+        // pushed off by whichever task takes its place
+        let preempted = prev < self.task_count
+            && prev != best_task
+            && !self.tasks[prev].idle
+            && self.tasks[prev].state == TaskState::Running
+            && matches!(
+                self.pending_switch_cause,
+                Some(SwitchCause::Preempt) | Some(SwitchCause::Starvation)
+            );
+        if prev != best_task && self.pending_switch_cause.is_some() {
+            self.last_switch_cause = self.pending_switch_cause;
+        }
+        self.pending_switch_cause = None;
+        if prev < self.task_count && self.tasks[prev].state == TaskState::Running {
+            self.tasks[prev].state = TaskState::Ready;
+        }
+
+        // Mark new task as Running (never resurrect a blocked fallback)
+        if best_task < self.task_count && self.tasks[best_task].is_runnable() {
+            self.tasks[best_task].state = TaskState::Running;
+            self.tasks[best_task].payoff.ticks_since_last_run = 0;
+            self.tasks[best_task].last_scheduled_at = Some(self.tick_count);
+            if preempted {
+                self.tasks[best_task].payoff.preemptions_caused += 1;
+            }
+        }
+
+        self.current_task[self.current_core] = best_task;
+        self.needs_reschedule = false;
+
+        #[cfg(feature = "debug-assertions")]
+        crate::invariant::enforce(self);
+
+        best_task
+    }
+
+    /// Select the next task for core `core_id`, making it the core the
+    /// scheduler acts for.
+    ///
+    /// Only tasks whose affinity mask includes `core_id` are eligible, and
+    /// a task running on another core is never picked: it is Running, not
+    /// Ready. When the idle task is already in use on another core, a core
+    /// with nothing eligible stays parked on its previous task.
+    ///
+    /// Every core shares this scheduler, so on a multi-core part the port
+    /// must serialize calls across cores (e.g. with a hardware spinlock);
+    /// masking interrupts only excludes the local core.
+    ///
+    /// # Panics
+    /// If `core_id >= MAX_CORES`.
+    pub fn schedule_for_core(&mut self, core_id: usize) -> usize {
+        self.enter_core(core_id);
+        self.schedule()
+    }
+
+    /// Pick the next task for core `core_id` at a context switch, as
+    /// `schedule_for_core()`, counting the switch and reporting it to the
+    /// switch hook when the task changes. Called by the port from PendSV.
+    pub fn context_switch(&mut self, core_id: usize) -> usize {
+        let prev = self.current_task[core_id];
+        let next = self.schedule_for_core(core_id);
+        if next != prev {
+            self.context_switches += 1;
+            (self.switch_hook)(prev, next, self.tick_count);
+        }
+        next
+    }
+
+    /// Act for core `core_id` from now on: "the current task" becomes the
+    /// task running there. Called by the port on each kernel entry.
+    ///
+    /// # Panics
+    /// If `core_id >= MAX_CORES`.
+    pub fn enter_core(&mut self, core_id: usize) {
+        assert!(core_id < MAX_CORES, "core id out of range");
+        self.current_core = core_id;
+    }
+
+    /// Whether task `id` is running on a core other than `current_core`.
+    pub fn is_running_elsewhere(&self, id: usize) -> bool {
+        self.tasks[id].state == TaskState::Running && self.current() != id
+    }
+
+    /// Starvation threshold in ticks for task `id`: its own
+    /// `TaskConfig::starvation_threshold`, or the global
+    /// `starvation_threshold` when that is 0.
+    pub fn task_starvation_threshold(&self, id: usize) -> u32 {
+        match self.tasks[id].config.starvation_threshold {
+            0 => self.starvation_threshold,
+            own => own,
+        }
+    }
+
+    /// Extra priority weight `schedule()` gives task `id` for starving:
+    /// zero below its starvation threshold, then growing along
+    /// `boost_curve` up to `starvation_boost_max`.
+    pub fn starvation_boost(&self, id: usize) -> i32 {
+        let starved = self.tasks[id].payoff.ticks_since_last_run as u64;
+        let threshold = self.task_starvation_threshold(id) as u64;
+        if starved < threshold {
+            return 0;
+        }
+        let boost = match self.boost_curve {
+            BoostCurve::Stepped => starved / threshold * 2,
+            BoostCurve::Linear => starved * 2 / threshold,
+        };
+        boost.min(self.starvation_boost_max as u64) as i32
+    }
+
+    /// Whether `candidate` should replace `incumbent` when both tie on
+    /// priority. Candidates are visited in id order, so `incumbent` always
+    /// has the lower id.
+    pub(crate) fn wins_tie(&self, candidate: usize, incumbent: usize) -> bool {
+        let c = &self.tasks[candidate];
+        let i = &self.tasks[incumbent];
+        match self.tie_break {
+            TieBreak::LowestId => false,
+            TieBreak::RoundRobin => {
+                let n = self.task_count;
+                let after_current = |id: usize| (id + n - self.current() - 1) % n;
+                after_current(candidate) < after_current(incumbent)
+            }
+            TieBreak::HighestCooperation => {
+                c.payoff.cooperation_score > i.payoff.cooperation_score
+            }
+            TieBreak::EarliestDeadline => {
+                let slack = |t: &TaskControlBlock| match t.release_window() {
+                    0 => u64::MAX,
+                    _ => t.deadline_tick.saturating_sub(self.tick_count),
+                };
+                slack(c) < slack(i)
+            }
+            // Never run (`None`) orders before any tick
+            TieBreak::LeastRecentlyRun => c.last_scheduled_at < i.last_scheduled_at,
+        }
+    }
+
+    /// Cause of the most recent context switch, or `None` before any.
+    pub fn last_switch_cause(&self) -> Option<SwitchCause> {
+        self.last_switch_cause
+    }
+
+    /// Get the tie-break ordering.
+    pub fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    /// Time slice task `id` gets when its slice is refilled: the
+    /// configured slice scaled by `game::quantum_scale()` of its current
+    /// payoff, at least one tick. As in `effective_priority()`, negative
+    /// payoff never shortens an anchor's slice.
+    fn quantum(&self, id: usize) -> u32 {
+        let tcb = &self.tasks[id];
+        let payoff = if tcb.config.anchor { tcb.payoff.payoff.max(0) } else { tcb.payoff.payoff };
+        let percent = game::quantum_scale(payoff, self.quantum_scale_min, self.quantum_scale_max);
+        let ticks = tcb.config.effective_time_slice(self.tick_hz) as u64 * percent as u64 / 100;
+        ticks.clamp(1, u32::MAX as u64) as u32
+    }
+
+    /// Bound the payoff-scaled time slice to `min..=max` percent of each
+    /// task's configured slice; `set_quantum_scale(100, 100)` gives every
+    /// task exactly its configured slice. Applies from each task's next
+    /// slice refill.
+    ///
+    /// # Errors
+    /// `InvalidConfig` if `min` is 0 or above `max`.
+    pub fn set_quantum_scale(&mut self, min: u32, max: u32) -> Result<(), KernelError> {
+        if min == 0 || min > max {
+            return Err(KernelError::InvalidConfig);
+        }
+        self.quantum_scale_min = min;
+        self.quantum_scale_max = max;
+        Ok(())
+    }
+
+    /// Configure the schedule-time starvation boost: its growth curve and
+    /// its ceiling in priority levels (`0` disables the boost). Takes
+    /// effect at the next `schedule()`.
+    pub fn set_starvation_boost(&mut self, curve: BoostCurve, max: u32) {
+        self.boost_curve = curve;
+        self.starvation_boost_max = max;
+    }
+
+    /// Choose how tasks that tie on priority are ordered. Takes effect at
+    /// the next `schedule()`.
+    pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+    }
+
+    /// Get the WCET overrun policy.
+    pub fn overrun_policy(&self) -> OverrunPolicy {
+        self.overrun_policy
+    }
+
+    /// Choose how WCET overruns are penalized. Applies to overruns detected
+    /// from now on.
+    pub fn set_overrun_policy(&mut self, policy: OverrunPolicy) {
+        self.overrun_policy = policy;
+    }
+
+    /// Get the overload degradation policy.
+    pub fn degrade_policy(&self) -> DegradePolicy {
+        self.degrade_policy
+    }
+
+    /// Choose how overloaded periodic tasks are degraded. Timing already
+    /// degraded under a previous policy is kept.
+    pub fn set_degrade_policy(&mut self, policy: DegradePolicy) {
+        self.degrade_policy = policy;
+    }
+
+    /// Count a period that ended with task `id`'s job unfinished, and
+    /// apply the degradation policy every `OVERLOAD_PERIODS` in a row.
+    fn note_overloaded_period(&mut self, id: usize) {
+        let tcb = &mut self.tasks[id];
+        tcb.overload_streak += 1;
+        if tcb.overload_streak < OVERLOAD_PERIODS {
+            return;
+        }
+        tcb.overload_streak = 0;
+
+        match self.degrade_policy {
+            DegradePolicy::None => {}
+            DegradePolicy::SkipReleases => {
+                tcb.release_stride = tcb.release_stride.saturating_add(1);
+            }
+            DegradePolicy::WidenPeriod => {
+                let deadline = tcb.config.deadline_ticks;
+                tcb.config.deadline_ticks = deadline.saturating_add((deadline / 2).max(1));
+            }
+            DegradePolicy::Alarm => {
+                if let Some(hook) = self.overload_hook {
+                    hook(id);
+                }
+            }
+        }
+    }
+
+    /// Get the active scheduling policy.
+    pub fn sched_policy(&self) -> SchedPolicy {
+        self.policy
+    }
+
+    /// Switch the scheduling policy at runtime.
+    ///
+    /// Takes effect at the next `schedule()`, which is requested here.
+    /// Policies select purely from per-task state, so the game engine's
+    /// payoff metrics carry over to the new policy.
+    pub fn set_sched_policy(&mut self, policy: SchedPolicy) {
+        self.policy = policy;
+        self.request_switch(SwitchCause::Preempt);
+    }
+
+    /// Yield the current task in favour of task `id`, which the next
+    /// `schedule()` runs regardless of priority or payoff if it is then
+    /// Ready and allowed on this core. Otherwise normal selection applies.
+    /// Either way the caller is charged a voluntary yield, as with
+    /// `yield_current()`, and only that one switch is affected.
+    ///
+    /// # Returns
+    /// `Err(KernelError::InvalidTaskId)` if `id` is not an allocated task;
+    /// the caller does not yield then.
+    pub fn yield_to(&mut self, id: usize) -> Result<(), KernelError> {
+        if id >= self.task_count || !self.tasks[id].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        self.forced_next = Some(id);
+        self.yield_current();
+        Ok(())
+    }
+
+    /// Whether a directed yield may hand the CPU to task `id`.
+    fn can_force(&self, id: usize) -> bool {
+        id < self.task_count
+            && self.tasks[id].is_runnable()
+            && self.tasks[id].can_run_on_core(self.current_core as u32)
+    }
+
+    /// Record a voluntary yield from the current task.
+    ///
+    /// Called from `kernel::yield_task()`. Marks the current task as Ready,
+    /// records the yield in payoff metrics, and triggers rescheduling.
+    ///
+    /// For a task with a deadline, yielding inside its release window
+    /// completes the period's job as `job_complete()` would: the deadline
+    /// counts as met rather than missed when it passes with the task Ready.
+    pub fn yield_current(&mut self) {
+        if self.preemption_locked() {
+            // No-op until the final `preempt_enable()`
+            self.yield_pending = true;
+            self.needs_reschedule = true;
+            return;
+        }
+        let current = self.current();
+        if current < self.task_count && self.tasks[current].active {
+            self.complete_job(current);
+            self.tasks[current].state = TaskState::Ready;
+            self.tasks[current].record_yield();
+            self.tasks[current].ticks_remaining = self.quantum(current);
+            self.request_switch(SwitchCause::Yield);
+
+            if self.tasks[current].config.deadline_ticks == 0 {
+                self.rearm_predecessor(current);
+            }
+        }
+    }
+
+    /// Block the current task for `ticks` ticks.
+    ///
+    /// Called from `kernel::delay_ticks()`. The task becomes Ready again
+    /// when `tick()` reaches its wake tick, or earlier via `cancel_delay()`.
+    /// A delay of 0 is a plain voluntary yield.
+    pub fn delay_current(&mut self, ticks: u32) {
+        if ticks == 0 {
+            self.yield_current();
+            return;
+        }
+        let current = self.current();
+        if current < self.task_count && self.tasks[current].active {
+            self.tasks[current].state = TaskState::Blocked;
+            self.tasks[current].block_reason = Some(BlockReason::Delay);
+            self.tasks[current].wake_tick = Some(self.tick_count + ticks as u64);
+            self.tasks[current].record_voluntary_block();
+            self.request_switch(SwitchCause::Block);
+        }
+    }
+
+    /// Block the current task until absolute tick `next`.
+    ///
+    /// Unlike `delay_current()` the wake time does not depend on when the
+    /// call is made, so a periodic loop does not drift. A `next` that is
+    /// not in the future does not block. Like a yield, sleeping until the
+    /// next release completes the current job of a task with a deadline,
+    /// and its next job is released at `next`: a loop on absolute release
+    /// instants sets the phase of the task's periods.
+    ///
+    /// # Returns
+    /// `true` if the task was blocked.
+    pub fn delay_until(&mut self, next: u64) -> bool {
+        if next <= self.tick_count {
+            return false;
+        }
+        let current = self.current();
+        self.complete_job(current);
+        if current < self.task_count && self.tasks[current].job_done {
+            self.tasks[current].deadline_tick = next;
+        }
+        self.delay_current((next - self.tick_count).min(u32::MAX as u64) as u32);
+        true
+    }
+
+    /// Wake a task from a pending timed wait before it expires.
+    ///
+    /// The task becomes Ready with `WakeReason::Cancelled`. A reschedule is
+    /// requested if it outranks the current task.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidTaskId)` — `id` is not an allocated task
+    /// - `Err(KernelError::InvalidState)` — the task is not in a timed wait,
+    ///   or is held by deadline enforcement
+    pub fn cancel_delay(&mut self, id: usize) -> Result<(), KernelError> {
+        if id >= self.task_count || !self.tasks[id].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        if self.tasks[id].state != TaskState::Blocked
+            || self.tasks[id].wake_tick.is_none()
+            || self.tasks[id].block_reason == Some(BlockReason::DeadlineOverrun)
+        {
+            return Err(KernelError::InvalidState);
+        }
+
+        self.wake_task(id, WakeReason::Cancelled);
+        Ok(())
+    }
+
+    /// Move a blocked task back to Ready, recording why it woke.
+    /// Requests a reschedule if it outranks the current task.
+    fn wake_task(&mut self, id: usize, reason: WakeReason) {
+        self.tasks[id].wake_tick = None;
+        self.tasks[id].wake_reason = reason;
+        self.unblock(id);
+    }
+
+    /// Make task `id` Ready from an ISR and have it run as soon as the ISR
+    /// returns, if it is then the highest-priority runnable task.
+    ///
+    /// A blocked task is woken whatever it was waiting for, with
+    /// `WakeReason::Interrupt`; a task that is already Ready only gets the
+    /// reschedule. Does no more than a state change and a reschedule
+    /// request, so it is safe in ISR context.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidTaskId)` — `id` is not an allocated task
+    /// - `Err(KernelError::InvalidState)` — the task is Suspended or
+    ///   Terminated
+    pub fn wake_and_switch(&mut self, id: usize) -> Result<(), KernelError> {
+        if id >= self.task_count || !self.tasks[id].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        match self.tasks[id].state {
+            TaskState::Blocked => self.wake_task(id, WakeReason::Interrupt),
+            TaskState::Ready => self.preempt_if_outranks(id),
+            TaskState::Running => {}
+            TaskState::Suspended | TaskState::Terminated => return Err(KernelError::InvalidState),
+        }
+        Ok(())
+    }
+
+    /// Move a blocked task back to Ready. Requests a reschedule if it
+    /// outranks the current task.
+    fn unblock(&mut self, id: usize) {
+        self.tasks[id].state = TaskState::Ready;
+        self.tasks[id].block_reason = None;
+        self.preempt_if_outranks(id);
+    }
+
+    /// Request a reschedule if Ready task `id` outranks the current task
+    /// under the active policy, or nothing is running.
+    fn preempt_if_outranks(&mut self, id: usize) {
+        let current = self.current();
+        if !self.current_is_running() || self.policy.outranks(self, id, current) {
+            self.request_preemption(SwitchCause::Preempt);
+        }
+    }
+
+    /// Report that the current periodic task has finished this period's job.
+    ///
+    /// Records the deadline as met and folds the completion offset into
+    /// the task's jitter range: the ticks since its release less the
+    /// deadline (zero or negative) for an on-time job. A job that already missed its deadline is
+    /// counted as late by the ticks elapsed since that deadline, and takes
+    /// the place of the period it ran into, which then ends without another
+    /// miss. Has no effect for tasks without a deadline, or once this
+    /// period's job is already complete.
+    pub fn job_complete(&mut self) {
+        let current = self.current();
+        if current >= self.task_count || !self.tasks[current].active {
+            return;
+        }
+        for bundle in self.bundles.iter_mut() {
+            if bundle.active && bundle.members & (1 << current) != 0 {
+                bundle.done |= 1 << current;
+            }
+        }
+        self.complete_job(current);
+    }
+
+    /// Record the outcome of task `id`'s job for this period, once: met
+    /// if its deadline has not passed, otherwise the lateness for jitter.
+    fn complete_job(&mut self, id: usize) {
+        if id >= self.task_count || !self.tasks[id].active {
+            return;
+        }
+        let tcb = &mut self.tasks[id];
+        let deadline = tcb.release_window();
+        if deadline == 0 || tcb.job_done {
+            return;
+        }
+        // Finishing the job hands the CPU back as much as a yield does
+        tcb.payoff.ticks_since_last_yield = 0;
+
+        let offset = (self.tick_count as i64 - tcb.deadline_tick as i64) as i32;
+        if tcb.job_late {
+            // The late job ran into this period and is its job: measured
+            // from the period start, and no second miss at its end
+            tcb.record_completion_offset(offset + deadline as i32);
+            tcb.job_late = false;
+            tcb.job_done = true;
+        } else {
+            tcb.record_completion_offset(offset);
+            tcb.record_deadline_met();
+            tcb.job_done = true;
+            tcb.overload_streak = 0;
+            if tcb.job_overran {
+                tcb.record_soft_overrun();
+                tcb.job_overran = false;
+            }
+        }
+    }
+
+    /// Reinstate a task's saved state and restart it from its entry
+    /// function with a fresh stack. Used by `snapshot::restore()`.
+    pub(crate) fn restore_task(&mut self, id: usize, image: &TaskImage) {
+        let tcb = &mut self.tasks[id];
+        tcb.state = image.state;
+        tcb.strategy = image.strategy;
+        tcb.tft_cooperating = image.tft_cooperating;
+        tcb.strategy_locked = image.strategy_locked;
+        // Names are static strings, not snapshot data: keep the slot's own
+        tcb.config = TaskConfig { name: tcb.config.name, ..image.config };
+        tcb.payoff = image.payoff;
+        tcb.ticks_remaining = image.ticks_remaining;
+        tcb.total_ticks = image.total_ticks;
+        tcb.window_ticks_used = image.window_ticks_used;
+        tcb.window_yields = image.window_yields;
+        tcb.recent_ticks_used = image.recent_ticks_used;
+        tcb.recent_cycles_used = image.recent_cycles_used;
+        tcb.budget_used = image.budget_used;
+        tcb.budget_elapsed = image.budget_elapsed;
+        tcb.period_ticks = image.period_ticks;
+        tcb.deadline_tick = image.deadline_tick;
+        tcb.block_reason = image.block_reason;
+        tcb.event_wait = None;
+        tcb.wake_tick = image.wake_tick;
+        tcb.wake_reason = image.wake_reason;
+        tcb.predecessor = image.predecessor;
+        tcb.predecessor_signalled = image.predecessor_signalled;
+        tcb.job_done = image.job_done;
+        tcb.job_late = image.job_late;
+        tcb.deadline_enforced = image.deadline_enforced;
+        tcb.job_overran = image.job_overran;
+        tcb.overload_streak = image.overload_streak;
+        tcb.release_stride = image.release_stride;
+        tcb.completion_jitter = image.completion_jitter;
+        tcb.donation = image.donation;
+        tcb.deferrals_pending = image.deferrals_pending;
+        tcb.event_arrival = image.event_arrival;
+        tcb.last_response = image.last_response;
+        tcb.max_response = image.max_response;
+        tcb.response_threshold = image.response_threshold;
+        tcb.last_scheduled_at = image.last_scheduled_at;
+        tcb.notification = image.notification;
+        tcb.notify_pending = image.notify_pending;
+
+        if let Some(entry) = tcb.entry {
+            init_task_stack(tcb, entry);
+        }
+    }
+
+    /// Earliest and latest job completion relative to the deadline, or
+    /// `None` for an invalid id or before any job completed.
+    pub fn deadline_jitter(&self, id: usize) -> Option<(i32, i32)> {
+        self.task(id).and_then(|t| t.completion_jitter)
+    }
+
+    /// Absolute tick of a task's upcoming deadline: the release of its
+    /// current period plus its relative deadline. It only moves at the
+    /// next release, however long the task waits for the CPU. This is the
+    /// key the `EarliestDeadline` tie-break orders by.
+    ///
+    /// `None` for an invalid id or a task without a deadline.
+    pub fn next_deadline(&self, id: usize) -> Option<u64> {
+        let tcb = self.task(id)?;
+        match tcb.release_window() {
+            0 => None,
+            _ => Some(tcb.deadline_tick),
+        }
+    }
+
+    /// The earliest upcoming hard deadline and the task it belongs to: the
+    /// value a deadline compare timer should be armed for. Ties go to the
+    /// lowest task id.
+    ///
+    /// Only active `hard_deadline` tasks whose current job is still
+    /// outstanding count; `None` when there is no such task.
+    pub fn earliest_hard_deadline(&self) -> Option<(usize, u64)> {
+        let mut earliest: Option<(usize, u64)> = None;
+        for i in 0..self.task_count {
+            let tcb = &self.tasks[i];
+            if !tcb.active || !tcb.config.hard_deadline || tcb.job_done || tcb.deadline_enforced {
+                continue;
+            }
+            if let Some(at) = self.next_deadline(i) {
+                if earliest.is_none_or(|(_, best)| at < best) {
+                    earliest = Some((i, at));
+                }
+            }
+        }
+        earliest
+    }
+
+    /// Record the miss of every outstanding hard deadline at or before
+    /// absolute tick `at`. Called from the deadline compare interrupt, which
+    /// fires at the deadline instant, possibly just before the tick that
+    /// would otherwise notice it.
+    ///
+    /// As at the tick, only jobs still Ready or Running miss; the tick
+    /// closing the period then leaves the outcome alone.
+    ///
+    /// # Returns
+    /// The number of misses recorded.
+    pub fn enforce_hard_deadlines(&mut self, at: u64) -> u32 {
+        let mut missed = 0;
+        for i in 0..self.task_count {
+            let tcb = &self.tasks[i];
+            if !tcb.active || !tcb.config.hard_deadline || tcb.job_done || tcb.deadline_enforced {
+                continue;
+            }
+            let deadline = match self.next_deadline(i) {
+                Some(deadline) if deadline <= at => deadline,
+                _ => continue,
+            };
+            self.tasks[i].deadline_enforced = true;
+            if self.tasks[i].state == TaskState::Ready || self.tasks[i].state == TaskState::Running {
+                self.record_missed_job(i);
+                if self.tasks[i].config.enforce_deadline {
+                    self.hold_until_next_period(i, deadline);
+                }
+                missed += 1;
+            }
+        }
+        missed
+    }
+
+    /// Start a new jitter measurement window for a task.
+    pub fn reset_deadline_jitter(&mut self, id: usize) -> Result<(), KernelError> {
+        let tcb = self.task_mut(id).ok_or(KernelError::InvalidTaskId)?;
+        tcb.completion_jitter = None;
+        Ok(())
+    }
+
+    /// Hand deferred interrupt work to bottom-half task `id`. Safe to call
+    /// from an ISR.
+    ///
+    /// If the task is waiting in `wait_for_deferral()` it becomes Ready at
+    /// once, preempting the current task if it has higher priority.
+    /// Otherwise the deferral is counted and consumed by its next wait.
+    pub fn defer_to(&mut self, id: usize) -> Result<(), KernelError> {
+        if id >= self.task_count || !self.tasks[id].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        if self.tasks[id].state == TaskState::Blocked
+            && self.tasks[id].block_reason == Some(BlockReason::Deferral)
+        {
+            self.wake_task(id, WakeReason::Signalled);
+        } else {
+            self.tasks[id].deferrals_pending = self.tasks[id].deferrals_pending.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    /// Wait for deferred work as the current task.
+    ///
+    /// With `timeout` set, the wait ends after that many ticks if no
+    /// deferral arrives: the task wakes with `WakeReason::Timeout` and its
+    /// timeout count is incremented. A delivered deferral wakes it with
+    /// `WakeReason::Signalled`.
+    ///
+    /// # Returns
+    /// `true` if a pending deferral was consumed and the task may continue
+    /// immediately; `false` if it was blocked until the next `defer_to()`,
+    /// which then delivers the work directly.
+    pub fn wait_for_deferral(&mut self, timeout: Option<u32>) -> bool {
+        let current = self.current();
+        if current >= self.task_count || !self.tasks[current].active {
+            return true;
+        }
+        if self.tasks[current].deferrals_pending > 0 {
+            self.tasks[current].deferrals_pending -= 1;
+            return true;
+        }
+        self.tasks[current].state = TaskState::Blocked;
+        self.tasks[current].block_reason = Some(BlockReason::Deferral);
+        self.tasks[current].wake_tick = timeout.map(|t| self.tick_count + t as u64);
+        self.tasks[current].record_voluntary_block();
+        self.request_switch(SwitchCause::Block);
+        false
+    }
+
+    /// Send a notification to task `id`. Safe to call from an ISR.
+    ///
+    /// Notifications count up until the task takes them, so none is lost
+    /// if several arrive first. A task blocked in `wait_notification()` is
+    /// woken with `WakeReason::Signalled`, preempting the current task if
+    /// it has higher priority.
+    pub fn notify(&mut self, id: usize) -> Result<(), KernelError> {
+        if id >= self.task_count || !self.tasks[id].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        let tcb = &mut self.tasks[id];
+        tcb.notification = tcb.notification.saturating_add(1);
+        tcb.notify_pending = true;
+        if tcb.state == TaskState::Blocked && tcb.block_reason == Some(BlockReason::Notification) {
+            self.wake_task(id, WakeReason::Signalled);
+        }
+        Ok(())
+    }
+
+    /// Take the current task's notifications, or block it until one
+    /// arrives.
+    ///
+    /// With `timeout` set, the wait ends after that many ticks if no
+    /// notification arrives: the task wakes with `WakeReason::Timeout` and
+    /// its timeout count is incremented.
+    ///
+    /// # Returns
+    /// The number of notifications received since the last take, clearing
+    /// it; or `None` if the task was blocked, in which case it should call
+    /// again once woken unless it timed out.
+    pub fn wait_notification(&mut self, timeout: Option<u32>) -> Option<u32> {
+        let current = self.current();
+        if current >= self.task_count || !self.tasks[current].active {
+            return Some(0);
+        }
+        let tcb = &mut self.tasks[current];
+        if tcb.notify_pending {
+            tcb.notify_pending = false;
+            return Some(core::mem::take(&mut tcb.notification));
+        }
+        tcb.state = TaskState::Blocked;
+        tcb.block_reason = Some(BlockReason::Notification);
+        tcb.wake_tick = timeout.map(|t| self.tick_count + t as u64);
+        tcb.record_voluntary_block();
+        self.request_switch(SwitchCause::Block);
+        None
+    }
+
+    /// Block the current task on the event group at address `group` (see
+    /// `sync::EventGroup`), until absolute tick `until` at the latest.
+    ///
+    /// # Returns
+    /// The blocked task's id, or `None` if no task is running.
+    pub(crate) fn block_on_event_group(&mut self, group: usize, until: Option<u64>) -> Option<usize> {
+        let current = self.current();
+        if current >= self.task_count || !self.tasks[current].active {
+            return None;
+        }
+        let tcb = &mut self.tasks[current];
+        tcb.state = TaskState::Blocked;
+        tcb.block_reason = Some(BlockReason::EventGroup(group));
+        tcb.event_wait = Some(group);
+        tcb.wake_tick = until;
+        tcb.record_voluntary_block();
+        self.request_switch(SwitchCause::Block);
+        Some(current)
+    }
+
+    /// Whether task `id` is blocked on the event group at address `group`.
+    pub(crate) fn waits_on_event_group(&self, id: usize, group: usize) -> bool {
+        self.task(id).is_some_and(|t| {
+            t.state == TaskState::Blocked && t.block_reason == Some(BlockReason::EventGroup(group))
+        })
+    }
+
+    /// Wake task `id` from a wait on the event group at address `group`
+    /// with `WakeReason::Signalled`, preempting the current task if it has
+    /// higher priority.
+    pub(crate) fn wake_event_waiter(&mut self, id: usize, group: usize) {
+        if self.waits_on_event_group(id, group) {
+            self.wake_task(id, WakeReason::Signalled);
+        }
+    }
+
+    /// Take the address of the event group task `id` blocked in and has
+    /// not yet collected the outcome of, if any.
+    pub(crate) fn take_event_wait(&mut self, id: usize) -> Option<usize> {
+        self.tasks.get_mut(id).and_then(|t| t.event_wait.take())
+    }
+
+    /// Donate up to `amount` priority levels from the current task to task
+    /// `to`, e.g. a consumer boosting the producer it waits on.
+    ///
+    /// The donation is capped at the donor's own base priority and raises
+    /// the recipient's priority under every policy. It lapses after
+    /// `duration` ticks (`0` = until revoked) or when the donor calls
+    /// `revoke_donation()`. A task holds at most one donation; a new one
+    /// from the same donor replaces it.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidTaskId)` — `to` is not an allocated task
+    /// - `Err(KernelError::InvalidState)` — `to` is the current task, or
+    ///   already holds a donation from another task
+    pub fn donate_priority(&mut self, to: usize, amount: u8, duration: u32) -> Result<(), KernelError> {
+        if to >= self.task_count || !self.tasks[to].active {
+            return Err(KernelError::InvalidTaskId);
+        }
+        let donor = self.current();
+        if donor >= self.task_count || donor == to {
+            return Err(KernelError::InvalidState);
+        }
+        if let Some(existing) = self.tasks[to].donation {
+            if existing.donor != donor {
+                return Err(KernelError::InvalidState);
+            }
+        }
+
+        self.tasks[to].donation = Some(Donation {
+            donor,
+            amount: amount.min(self.tasks[donor].config.priority),
+            expires_tick: match duration {
+                0 => None,
+                d => Some(self.tick_count + d as u64),
+            },
+        });
+        self.request_preemption(SwitchCause::Preempt);
+        Ok(())
+    }
+
+    /// Withdraw every donation made by the current task.
+    ///
+    /// # Returns
+    /// `Err(KernelError::InvalidState)` if the current task has no
+    /// outstanding donation.
+    pub fn revoke_donation(&mut self) -> Result<(), KernelError> {
+        let donor = self.current();
+        let mut revoked = false;
+        for i in 0..self.task_count {
+            if self.tasks[i].donation.is_some_and(|d| d.donor == donor) {
+                self.tasks[i].donation = None;
+                revoked = true;
+            }
+        }
+        if !revoked {
+            return Err(KernelError::InvalidState);
+        }
+        self.request_preemption(SwitchCause::Preempt);
+        Ok(())
+    }
+
+    /// Make `id` depend on `predecessor`: each period, `id` stays Blocked
+    /// until the predecessor calls `signal_done()`. Pass `None` to remove
+    /// the constraint.
+    pub fn set_predecessor(&mut self, id: usize, predecessor: Option<usize>) -> Result<(), KernelError> {
+        if self.task(id).is_none() {
+            return Err(KernelError::InvalidTaskId);
+        }
+        if let Some(pred) = predecessor {
+            if pred == id || self.task(pred).is_none() {
+                return Err(KernelError::InvalidTaskId);
+            }
+        }
+
+        self.tasks[id].predecessor = predecessor;
+        if predecessor.is_some() {
+            self.rearm_predecessor(id);
+        } else if self.tasks[id].block_reason == Some(BlockReason::Predecessor) {
+            self.unblock(id);
+        }
+        Ok(())
+    }
+
+    /// Allocate a deadline bundle whose members must all complete within
+    /// `deadline_ticks` of each period start.
+    ///
+    /// # Returns
+    /// - `Ok(bundle_id)` — the new bundle, initially empty
+    /// - `Err(KernelError::InvalidState)` — `deadline_ticks` is 0
+    /// - `Err(KernelError::TooManyBundles)` — every slot is in use
+    pub fn create_bundle(&mut self, deadline_ticks: u32) -> Result<usize, KernelError> {
+        if deadline_ticks == 0 {
+            return Err(KernelError::InvalidState);
+        }
+        let b = self
+            .bundles
+            .iter()
+            .position(|bundle| !bundle.active)
+            .ok_or(KernelError::TooManyBundles)?;
+        self.bundles[b] = DeadlineBundle {
+            active: true,
+            deadline_ticks,
+            ..DeadlineBundle::EMPTY
+        };
+        Ok(b)
+    }
+
+    /// Allocate a software timer that calls `callback` every `period`
+    /// ticks (`repeating`) or once, `period` ticks after it is started.
+    /// The timer is created stopped.
+    ///
+    /// # Returns
+    /// - `Ok(timer_id)` — the new timer
+    /// - `Err(KernelError::InvalidState)` — `period` is 0
+    /// - `Err(KernelError::TooManyTimers)` — every slot is in use
+    pub fn create_timer(&mut self, period: u32, repeating: bool, callback: fn()) -> Result<usize, KernelError> {
+        if period == 0 {
+            return Err(KernelError::InvalidState);
+        }
+        let t = self
+            .timers
+            .iter()
+            .position(|timer| !timer.active)
+            .ok_or(KernelError::TooManyTimers)?;
+        self.timers[t] = TimerControlBlock {
+            active: true,
+            period,
+            repeating,
+            callback: Some(callback),
+            ..TimerControlBlock::EMPTY
+        };
+        Ok(t)
+    }
+
+    /// Start a timer, or restart a running one, counting a full period
+    /// from the current tick.
+    pub fn start_timer(&mut self, id: usize) -> Result<(), KernelError> {
+        self.timer_mut(id)?.start();
+        Ok(())
+    }
+
+    /// Stop a timer without firing it. It keeps its slot and can be
+    /// started again.
+    pub fn stop_timer(&mut self, id: usize) -> Result<(), KernelError> {
+        self.timer_mut(id)?.running = false;
+        Ok(())
+    }
+
+    fn timer_mut(&mut self, id: usize) -> Result<&mut TimerControlBlock, KernelError> {
+        match self.timers.get_mut(id) {
+            Some(timer) if timer.active => Ok(timer),
+            _ => Err(KernelError::InvalidTimerId),
+        }
+    }
+
+    /// Move task `id` into `bundle`, or out of any bundle with `None`.
+    /// A task belongs to at most one bundle.
+    pub fn set_bundle(&mut self, id: usize, bundle: Option<usize>) -> Result<(), KernelError> {
+        if self.task(id).is_none() {
+            return Err(KernelError::InvalidTaskId);
+        }
+        if let Some(b) = bundle {
+            if b >= MAX_BUNDLES || !self.bundles[b].active {
+                return Err(KernelError::InvalidState);
+            }
+        }
+
+        for slot in self.bundles.iter_mut() {
+            slot.members &= !(1 << id);
+            slot.done &= !(1 << id);
+        }
+        if let Some(b) = bundle {
+            self.bundles[b].members |= 1 << id;
+        }
+        Ok(())
+    }
+
+    /// The bundle task `id` belongs to, if any.
+    pub fn bundle_of(&self, id: usize) -> Option<usize> {
+        self.bundles
+            .iter()
+            .position(|bundle| bundle.active && bundle.members & (1 << id) != 0)
+    }
+
+    /// Signal that the current task has produced its output for this
+    /// period, releasing every task that depends on it.
+    pub fn signal_done(&mut self) {
+        let current = self.current();
+        for i in 0..self.task_count {
+            if !self.tasks[i].active || self.tasks[i].predecessor != Some(current) {
+                continue;
+            }
+            self.tasks[i].predecessor_signalled = true;
+            if self.tasks[i].block_reason == Some(BlockReason::Predecessor) {
+                self.unblock(i);
+            }
+        }
+    }
+
+    /// Start a new dependency period for `id`: clear the predecessor's
+    /// signal and block the task until it arrives again.
+    ///
+    /// Periodic tasks re-arm at each period boundary; best-effort tasks
+    /// (no deadline) re-arm on every voluntary yield, so each signal
+    /// releases one activation.
+    fn rearm_predecessor(&mut self, id: usize) {
+        if self.tasks[id].predecessor.is_none() {
+            return;
+        }
+        self.tasks[id].predecessor_signalled = false;
+        let state = self.tasks[id].state;
+        if state == TaskState::Ready || state == TaskState::Running {
+            self.tasks[id].state = TaskState::Blocked;
+            self.tasks[id].block_reason = Some(BlockReason::Predecessor);
+            if id == self.current() {
+                self.request_switch(SwitchCause::Block);
+            }
+        }
+    }
+
+    /// Collect the ids of tasks currently Blocked for `reason`.
+    ///
+    /// Fills `out` with up to `out.len()` task ids, in id order, and
+    /// returns the number written.
+    pub fn waiters_on(&self, reason: BlockReason, out: &mut [usize]) -> usize {
+        let mut count = 0;
+        for i in 0..self.task_count {
+            if count >= out.len() {
+                break;
+            }
+            if self.tasks[i].active
+                && self.tasks[i].state == TaskState::Blocked
+                && self.tasks[i].block_reason == Some(reason)
+            {
+                out[count] = i;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Collect the ids of tasks currently flagged as non-yielding.
+    ///
+    /// Fills `out` with up to `out.len()` task ids and returns the number
+    /// written.
+    pub fn non_yielding_tasks(&self, out: &mut [usize]) -> usize {
+        let mut count = 0;
+        for i in 0..self.task_count {
+            if count >= out.len() {
+                break;
+            }
+            if self.tasks[i].is_non_yielding() {
+                out[count] = i;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Install (or clear) the quantum-exhausted hook.
+    ///
+    /// The hook runs from `tick()` in SysTick context, so it must be short
+    /// and must not block.
+    pub fn set_quantum_hook(&mut self, hook: Option<fn(usize)>) {
+        self.quantum_hook = hook;
+    }
+
+    /// Install (or clear) the hook the idle task calls on every iteration.
+    pub fn set_idle_hook(&mut self, hook: Option<fn()>) {
+        self.idle_hook = hook;
+    }
+
+    /// Replace the fair-share policy used by the payoff computation. It
+    /// runs from `evaluate_game()` in SysTick context.
+    pub fn set_fair_share_policy(&mut self, policy: FairSharePolicy) {
+        self.fair_share_policy = policy;
+    }
+
+    /// Replace the payoff weights. Takes effect at the next game
+    /// evaluation, for both scoring and equilibrium detection.
+    pub fn set_payoff_config(&mut self, config: PayoffConfig) {
+        self.payoff_config = config;
+    }
+
+    /// Replace the action taken when the scheduler is started with no
+    /// tasks.
+    pub fn set_no_tasks_hook(&mut self, hook: fn() -> !) {
+        self.no_tasks_hook = hook;
+    }
+
+    /// Replace the action taken when a context switch detects a corrupted
+    /// task stack.
+    pub fn set_stack_overflow_hook(&mut self, hook: fn(usize, StackFault) -> !) {
+        self.stack_overflow_hook = hook;
+    }
+
+    /// Install (or clear) the overload hook used by `DegradePolicy::Alarm`.
+    /// It runs from `tick()` in SysTick context.
+    pub fn set_overload_hook(&mut self, hook: Option<fn(usize)>) {
+        self.overload_hook = hook;
+    }
+
+    /// Install (or clear) the response-time alarm hook. It runs from
+    /// `event_handled()` in the handling task's context.
+    pub fn set_response_alarm_hook(&mut self, hook: Option<fn(usize, u32)>) {
+        self.response_alarm_hook = hook;
+    }
+
+    /// Install the context-switch trace hook; `no_switch_hook` removes it.
+    /// It runs from PendSV on every switch, see `kernel::set_switch_hook()`.
+    pub fn set_switch_hook(&mut self, hook: fn(usize, usize, u64)) {
+        self.switch_hook = hook;
+    }
+
+    /// Install (or clear) the strategy reclassification hook. It runs from
+    /// the game evaluation in `tick()`, in SysTick context.
+    pub fn set_reclassify_hook(&mut self, hook: Option<fn(usize, Strategy)>) {
+        self.reclassify_hook = hook;
+    }
+
+    /// Set the response time in ticks above which task `id` raises the
+    /// response alarm. `0` disables the alarm.
+    pub fn set_response_threshold(&mut self, id: usize, ticks: u32) -> Result<(), KernelError> {
+        let tcb = self.task_mut(id).ok_or(KernelError::InvalidTaskId)?;
+        tcb.response_threshold = ticks;
+        Ok(())
+    }
+
+    /// Record that an event for task `id` has arrived. Safe to call from
+    /// an ISR. If an earlier event is still unhandled its arrival time is
+    /// kept, so the measured response covers the oldest pending event.
+    pub fn event_arrived(&mut self, id: usize) -> Result<(), KernelError> {
+        let now = self.tick_count;
+        let tcb = self.task_mut(id).ok_or(KernelError::InvalidTaskId)?;
+        if tcb.event_arrival.is_none() {
+            tcb.event_arrival = Some(now);
+        }
+        Ok(())
+    }
+
+    /// Record that the current task finished handling its pending event.
+    ///
+    /// Updates the last and maximum response times and fires the response
+    /// alarm hook if the response exceeded the task's threshold.
+    ///
+    /// # Returns
+    /// The response time in ticks, or `None` if no event was pending.
+    pub fn event_handled(&mut self) -> Option<u32> {
+        let current = self.current();
+        let now = self.tick_count;
+        let tcb = self.task_mut(current)?;
+        let arrival = tcb.event_arrival.take()?;
+
+        let response = (now - arrival).min(u32::MAX as u64) as u32;
+        tcb.last_response = Some(response);
+        tcb.max_response = tcb.max_response.max(response);
+        let threshold = tcb.response_threshold;
+
+        if threshold > 0 && response > threshold {
+            if let Some(hook) = self.response_alarm_hook {
+                hook(current, response);
+            }
+        }
+        Some(response)
+    }
+
+    /// Last and maximum event response times of task `id`, in ticks, or
+    /// `None` for an invalid id or before any event was handled.
+    pub fn response_times(&self, id: usize) -> Option<(u32, u32)> {
+        let tcb = self.task(id)?;
+        tcb.last_response.map(|last| (last, tcb.max_response))
+    }
+
+    /// Provide the region task stacks are carved from. Must be called
+    /// before the first `create_task()`; replaces any previous pool.
+    #[cfg(feature = "stack-pool")]
+    pub fn set_stack_pool(&mut self, mem: &'static mut [u8]) {
+        self.stack_pool = StackPool::new(mem);
+    }
+
+    /// Set the stack-pointer validation level used on context switches.
+    pub fn set_stack_check(&mut self, level: StackCheck) {
+        self.stack_check = level;
+    }
+
+    /// Validate a task's saved stack pointer at the configured level.
+    /// Called from the context-switch path for both outgoing and incoming
+    /// tasks.
+    pub fn validate_stack(&self, id: usize, sp: *const u32) -> Result<(), StackFault> {
+        self.tasks[id].check_stack(sp, self.stack_check)
+    }
+
+    /// `validate_stack()`, diverging into the stack overflow hook on a
+    /// fault: resuming a task on a clobbered context is never safe.
+    pub fn enforce_stack(&self, id: usize, sp: *const u32) {
+        if let Err(fault) = self.validate_stack(id, sp) {
+            (self.stack_overflow_hook)(id, fault);
+        }
+    }
+
+    /// Overall CPU load in percent: the share of all ticks so far during
+    /// which some task was Running. Returns 0 before the first tick.
+    pub fn load_percent(&self) -> u32 {
+        if self.tick_count == 0 {
+            return 0;
+        }
+        let busy = self.tick_count - self.idle_ticks;
+        (busy * 100 / self.tick_count) as u32
+    }
+
+    /// Share of all ticks so far that task `id` spent Running, in
+    /// per-mille (0–1000). For the idle task this is the share no task was
+    /// running, i.e. the CPU headroom. `None` for an invalid id.
+    pub fn cpu_utilization(&self, id: usize) -> Option<u32> {
+        self.task(id).map(|t| per_mille(t.total_ticks as u64, self.tick_count))
+    }
+
+    /// Like `cpu_utilization()`, but over the ticks since the last game
+    /// evaluation only, to show recent rather than lifetime usage.
+    pub fn window_cpu_utilization(&self, id: usize) -> Option<u32> {
+        let window = self.tick_count - self.window_start_tick;
+        self.task(id).map(|t| per_mille(t.window_ticks_used as u64, window))
+    }
+
+    /// Ticks left in the current task's time slice.
+    pub fn remaining_slice(&self) -> u32 {
+        let current = self.current();
+        if current < self.task_count {
+            self.tasks[current].ticks_remaining
+        } else {
+            0
+        }
+    }
+
+    /// Number of event waits by a task that timed out, or `None` for an
+    /// invalid id.
+    pub fn timeout_count(&self, id: usize) -> Option<u32> {
+        self.task(id).map(|t| t.payoff.timeouts)
+    }
+
+    /// A task's priority split into base, donation, payoff adjustment and
+    /// starvation boost, or `None` for an invalid id.
+    pub fn priority_breakdown(&self, id: usize) -> Option<PriorityBreakdown> {
+        let tcb = self.task(id)?;
+        let base = tcb.config.priority as i32;
+        let boosted = tcb.boosted_priority();
+        let adjusted = tcb.effective_priority();
+        let starvation_boost = self.starvation_boost(id);
+        Some(PriorityBreakdown {
+            base,
+            inheritance_boost: boosted - base,
+            payoff_adjustment: adjusted - boosted,
+            starvation_boost,
+            effective: adjusted + starvation_boost,
+        })
+    }
+
+    /// Gather the system-wide counters and every task's strategy, priority,
+    /// payoff and CPU use. Read-only.
+    pub fn stats(&self) -> SystemStats {
+        let mut tasks = [None; MAX_TASKS];
+        for (id, row) in tasks[..self.task_count].iter_mut().enumerate() {
+            *row = self.task(id).map(|tcb| TaskStats {
+                id,
+                name: tcb.config.name,
+                strategy: tcb.strategy,
+                effective_priority: tcb.effective_priority(),
+                payoff: tcb.payoff.payoff,
+                cpu_ticks_used: tcb.payoff.cpu_ticks_used,
+            });
+        }
+        SystemStats {
+            tick_count: self.tick_count,
+            active_tasks: self.metrics.active_tasks,
+            global_cooperation_ratio: self.metrics.global_cooperation_ratio,
+            context_switches: self.context_switches,
+            in_equilibrium: self.in_equilibrium,
+            last_equilibrium: self.last_equilibrium,
+            evaluations_since_change: self.evaluations_since_change,
+            tasks,
+        }
+    }
+
+    /// Call `f` with each allocated task's TCB, in id order. Free slots are
+    /// skipped.
+    pub fn for_each_task(&self, mut f: impl FnMut(&TaskControlBlock)) {
+        for tcb in (0..self.task_count).filter_map(|id| self.task(id)) {
+            f(tcb);
+        }
+    }
+
+    /// Copy each allocated task's [`TaskInfo`] into `out`, in id order,
+    /// stopping when `out` is full.
+    ///
+    /// # Returns
+    /// The number of entries written.
+    pub fn collect_task_info(&self, out: &mut [TaskInfo]) -> usize {
+        let mut written = 0;
+        let tasks = (0..self.task_count).filter_map(|id| self.task(id));
+        for (info, tcb) in out.iter_mut().zip(tasks) {
+            *info = TaskInfo {
+                id: tcb.id,
+                name: tcb.config.name,
+                state: tcb.state,
+                block_reason: tcb.block_reason,
+                strategy: tcb.strategy,
+                priority: tcb.config.priority,
+                effective_priority: tcb.effective_priority(),
+                payoff: tcb.payoff.payoff,
+                cooperation_score: tcb.payoff.cooperation_score,
+                total_ticks: tcb.total_ticks,
+                deadlines_met: tcb.payoff.deadlines_met,
+                deadlines_missed: tcb.payoff.deadlines_missed,
+            };
+            written += 1;
+        }
+        written
+    }
+
+    /// Number of times the game engine reclassified a task's strategy from
+    /// its behaviour, or `None` for an invalid id.
+    pub fn reclassifications(&self, id: usize) -> Option<u32> {
+        self.task(id).map(|t| t.payoff.reclassifications)
+    }
+
+    /// Number of times a task displaced a running task by becoming runnable
+    /// or being boosted, or `None` for an invalid id.
+    pub fn preemptions_caused(&self, id: usize) -> Option<u32> {
+        self.task(id).map(|t| t.payoff.preemptions_caused)
+    }
+
+    /// Number of consecutive declining evaluation windows for a task — how
+    /// close it is to a hysteresis-driven strategy switch.
+    pub fn decline_streak(&self, id: usize) -> Option<u32> {
+        self.task(id).map(|t| t.payoff.decline_streak)
+    }
+
+    /// Zero a task's decline streak, giving the strategy-switch hysteresis
+    /// a clean slate.
+    pub fn reset_decline_streak(&mut self, id: usize) -> Result<(), KernelError> {
+        let task = self.task_mut(id).ok_or(KernelError::InvalidTaskId)?;
+        task.payoff.decline_streak = 0;
+        Ok(())
+    }
+
+    /// Pin a task's strategy: the decline-streak hysteresis and the
+    /// defection circuit-breaker leave it alone until `unlock_strategy()`.
+    /// Pinning `TitForTat` still lets the task mirror the system each window.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidTaskId)` — `id` is not an allocated task
+    pub fn set_strategy(&mut self, id: usize, strategy: Strategy) -> Result<(), KernelError> {
+        let task = self.task_mut(id).ok_or(KernelError::InvalidTaskId)?;
+        task.strategy = strategy;
+        task.strategy_locked = true;
+        task.payoff.decline_streak = 0;
+        Ok(())
+    }
+
+    /// Return a pinned task's strategy to game control.
+    pub fn unlock_strategy(&mut self, id: usize) -> Result<(), KernelError> {
+        let task = self.task_mut(id).ok_or(KernelError::InvalidTaskId)?;
+        task.strategy_locked = false;
+        Ok(())
+    }
+
+    /// A task's current strategy.
+    pub fn strategy(&self, id: usize) -> Result<Strategy, KernelError> {
+        self.task(id).map(|t| t.strategy).ok_or(KernelError::InvalidTaskId)
+    }
+
+    /// A task's debug name (empty if none was configured), or `None` for
+    /// an invalid id.
+    pub fn task_name(&self, id: usize) -> Option<&'static str> {
+        self.task(id).map(|t| t.config.name)
+    }
+
+    /// Id of the lowest-numbered task named `name`. Meant for tests and
+    /// debugging; an empty name matches nothing.
+    pub fn find_by_name(&self, name: &str) -> Option<usize> {
+        if name.is_empty() {
+            return None;
+        }
+        (0..self.task_count).find(|&id| self.task_name(id) == Some(name))
+    }
+
+    /// Get a reference to an allocated task's TCB, or `None` if `id` is
+    /// out of range or a free slot.
+    pub fn task(&self, id: usize) -> Option<&TaskControlBlock> {
+        if id < self.task_count && self.tasks[id].active {
+            Some(&self.tasks[id])
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to an allocated task's TCB.
+    pub fn task_mut(&mut self, id: usize) -> Option<&mut TaskControlBlock> {
+        if id < self.task_count && self.tasks[id].active {
+            Some(&mut self.tasks[id])
+        } else {
+            None
+        }
+    }
+
+    /// Index of the task running on `current_core`.
+    #[inline]
+    pub fn current(&self) -> usize {
+        self.current_task[self.current_core]
+    }
+
+    /// Get a reference to the current task's TCB.
+    pub fn current_tcb(&self) -> &TaskControlBlock {
+        &self.tasks[self.current()]
+    }
+
+    /// Get a mutable reference to the current task's TCB.
+    pub fn current_tcb_mut(&mut self) -> &mut TaskControlBlock {
+        &mut self.tasks[self.current()]
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scale the starvation threshold with system load.
+///
+/// With more active tasks each one legitimately waits longer between
+/// runs, so the threshold grows proportionally from `STARVATION_THRESHOLD`
+/// at `STARVATION_BASE_TASKS` tasks, clamped to
+/// `STARVATION_THRESHOLD_MIN..=STARVATION_THRESHOLD_MAX`.
+pub fn scaled_starvation_threshold(active_tasks: u32) -> u32 {
+    let scaled = STARVATION_THRESHOLD * active_tasks / STARVATION_BASE_TASKS;
+    scaled.clamp(STARVATION_THRESHOLD_MIN, STARVATION_THRESHOLD_MAX)
+}
+
+/// Convert a duration of `ticks` at `from` Hz to ticks at `to` Hz,
+/// rounding up so a non-zero duration stays non-zero.
+fn rescale_ticks(ticks: u64, from: u32, to: u32) -> u64 {
+    (ticks * to as u64).div_ceil(from as u64)
+}
+
+/// `part` of `whole` in per-mille; 0 when `whole` is 0.
+fn per_mille(part: u64, whole: u64) -> u32 {
+    if whole == 0 {
+        return 0;
+    }
+    (part * 1000 / whole) as u32
+}
+
+// ---------------------------------------------------------------------------
+// Stack initialization helper
+// ---------------------------------------------------------------------------
+
+/// Smallest stack `init_task_stack()` can build a first frame in: the
+/// canary, up to 7 bytes lost to alignment, and the initial context.
+const MIN_STACK_BYTES: usize = 4 + 7 + Port::INITIAL_FRAME_WORDS * 4;
+
+/// Initialize a task's stack frame for first-time context switch.
+///
+/// The port builds its initial context (see `Arch::init_task_stack`)
+/// below the 8-byte aligned stack top (AAPCS requirement), so that the
+/// first switch starts executing the task function and a task that
+/// returns lands in `task_exit`.
+///
+/// The lowest word of the stack is set to `STACK_CANARY` for overflow
+/// detection (`StackCheck::CanaryCheck`).
+fn init_task_stack(tcb: &mut TaskControlBlock, entry: TaskEntry) {
+    let stack_top = tcb.stack_base() as usize + tcb.stack_size();
+    // Align to 8 bytes (AAPCS requirement)
+    let aligned_top = (stack_top & !0x07) as *mut u32;
+
+    unsafe {
+        // Canary at the stack base
+        core::ptr::write_unaligned(tcb.stack_base_mut() as *mut u32, STACK_CANARY);
+        let exit = TaskEntry::new(task_exit);
+        tcb.stack_pointer = Port::init_task_stack(aligned_top, entry.address(), exit.address());
+    }
+}
+
+/// Default stack overflow hook: halt with the task and fault. There is no
+/// safe way to continue.
+fn halt_stack_fault(task: usize, fault: StackFault) -> ! {
+    panic!("stack fault in task {}: {:?}", task, fault);
+}
+
+/// Default context-switch hook: does nothing.
+pub fn no_switch_hook(_from: usize, _to: usize, _at_tick: u64) {}
+
+/// Default no-tasks hook: stop at a breakpoint so an attached debugger
+/// shows the misconfiguration, then sleep forever.
+fn halt_no_tasks() -> ! {
+    cortex_m::asm::bkpt();
+    loop {
+        cortex_m::asm::wfi();
+    }
+}
+
+/// Fallback for tasks that return (they shouldn't — entry is `fn() -> !`).
+/// Loops forever to prevent undefined behavior.
+extern "C" fn task_exit() -> ! {
+    loop {
+        cortex_m::asm::wfi();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        NON_YIELD_THRESHOLD, DEFAULT_TIME_SLICE, STRATEGY_HYSTERESIS, MAX_TIME_SLICE, MAX_CORES, QUANTUM_PAYOFF_STEP,
+        STACK_SIZE,
+    };
+    use crate::task::PeriodicRelease;
+    use crate::arch::cortex_m4::SW_FRAME_WORDS;
+    #[cfg(feature = "fpu")]
+    use crate::arch::cortex_m4::EXC_RETURN_THREAD_PSP;
+    #[cfg(feature = "stack-pool")]
+    use crate::stack_pool::tests::leaked_region;
+
+    extern "C" fn spin() -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    const TEST_ENTRY: TaskEntry = TaskEntry::new(spin);
+
+    fn make_config(priority: u8) -> TaskConfig {
+        TaskConfig {
+            priority,
+            ..TaskConfig::DEFAULT
+        }
+    }
+
+    #[cfg(not(feature = "stack-pool"))]
+    fn new_scheduler() -> Scheduler {
+        Scheduler::new()
+    }
+
+    /// A scheduler given a full table's worth of stacks, as an application
+    /// would with `set_stack_pool()`.
+    #[cfg(feature = "stack-pool")]
+    fn new_scheduler() -> Scheduler {
+        let mut sched = Scheduler::new();
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        sched
+    }
+
+    /// Advance the scheduler one tick, rescheduling if requested.
+    fn step(sched: &mut Scheduler) {
+        sched.tick();
+        if sched.needs_reschedule {
+            sched.schedule();
+        }
+    }
+
+    fn panic_no_tasks() -> ! {
+        panic!("started with no tasks");
+    }
+
+    #[test]
+    #[should_panic(expected = "started with no tasks")]
+    fn test_start_without_tasks_runs_hook() {
+        let mut sched = new_scheduler();
+        sched.set_no_tasks_hook(panic_no_tasks);
+        sched.start_first();
+    }
+
+    #[test]
+    fn test_start_with_only_blocked_tasks_idles() {
+        let mut sched = new_scheduler();
+        sched.set_no_tasks_hook(panic_no_tasks);
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.tasks[id].state = TaskState::Blocked;
+        sched.tasks[id].block_reason = Some(BlockReason::Delay);
+        sched.tasks[id].wake_tick = Some(5);
+
+        sched.start_first();
+        assert!(!sched.current_is_running());
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.idle_ticks, 5);
+        assert_eq!(sched.tasks[id].state, TaskState::Running);
+    }
+
+    #[test]
+    fn test_idle_task_runs_only_when_nothing_else_is_runnable() {
+        let mut sched = new_scheduler();
+        let idle = sched.create_idle_task(TEST_ENTRY).unwrap();
+        assert_eq!(sched.create_idle_task(TEST_ENTRY), Err(KernelError::InvalidState));
+        let id = sched.create_task(TEST_ENTRY, make_config(0), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.start_first(), id);
+
+        sched.delay_current(5);
+        assert_eq!(sched.schedule(), idle);
+        assert_eq!(sched.tasks[idle].state, TaskState::Running);
+        for _ in 0..4 {
+            step(&mut sched);
+        }
+        // Time on the idle task is idle time, never the idle task's CPU use
+        assert_eq!(sched.current(), idle);
+        assert_eq!(sched.idle_ticks, 4);
+        assert_eq!(sched.tasks[idle].payoff.cpu_ticks_used, 0);
+
+        // The wakeup displaces the idle task
+        step(&mut sched);
+        assert_eq!(sched.current(), id);
+        assert_eq!(sched.tasks[id].payoff.preemptions_caused, 0);
+        assert_eq!(sched.delete_task(idle), Err(KernelError::InvalidState));
+    }
+
+    #[test]
+    fn test_idle_task_is_not_a_player() {
+        let mut sched = new_scheduler();
+        sched.set_no_tasks_hook(panic_no_tasks);
+        let idle = sched.create_idle_task(TEST_ENTRY).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Selfish).unwrap();
+        sched.start_first();
+
+        sched.evaluate_now();
+        assert_eq!(sched.metrics.active_tasks, 2);
+        assert_eq!(sched.metrics.global_cooperation_ratio, 50);
+        assert_eq!(sched.tasks[idle].payoff.payoff, 0);
+        assert_eq!(sched.tasks[idle].strategy, Strategy::Cooperative);
+    }
+
+    #[test]
+    fn test_priority_levels_validated() {
+        let mut sched = new_scheduler();
+        assert_eq!(sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative), Ok(0));
+
+        let top = (PRIORITY_LEVELS - 1) as u8;
+        assert_eq!(sched.create_task(TEST_ENTRY, make_config(top), Strategy::Cooperative), Ok(1));
+
+        let over = PRIORITY_LEVELS as u8;
+        assert_eq!(
+            sched.create_task(TEST_ENTRY, make_config(over), Strategy::Cooperative),
+            Err(KernelError::InvalidPriority)
+        );
+        assert_eq!(sched.task_count, 2);
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        let mut sched = new_scheduler();
+        let invalid = [
+            TaskConfig { time_slice: MAX_TIME_SLICE + 1, ..make_config(3) },
+            TaskConfig { time_slice: 0, time_slice_ms: 2 * MAX_TIME_SLICE, ..make_config(3) },
+            TaskConfig { deadline_ticks: 10, wcet_ticks: 11, ..make_config(3) },
+            TaskConfig { affinity_mask: 0, ..make_config(3) },
+            TaskConfig { affinity_mask: 1 << MAX_CORES, ..make_config(3) },
+        ];
+        for config in invalid {
+            assert_eq!(
+                sched.create_task(TEST_ENTRY, config, Strategy::Cooperative),
+                Err(KernelError::InvalidConfig),
+                "{:?}", config
+            );
+        }
+        assert_eq!(sched.task_count, 0);
+
+        // Limits themselves are fine, as is a WCET without a deadline
+        let edge = TaskConfig { time_slice: MAX_TIME_SLICE, deadline_ticks: 10, wcet_ticks: 10, ..make_config(3) };
+        assert_eq!(sched.create_task(TEST_ENTRY, edge, Strategy::Cooperative), Ok(0));
+        let best_effort = TaskConfig { deadline_ticks: 0, wcet_ticks: 50, ..make_config(3) };
+        assert_eq!(sched.create_task(TEST_ENTRY, best_effort, Strategy::Cooperative), Ok(1));
+    }
+
+    #[cfg(feature = "stack-pool")]
+    #[test]
+    fn test_stack_too_small_for_frame_rejected() {
+        let mut sched = new_scheduler();
+        let mut tiny = [0u64; 4];
+        sched.tasks[0].stack = (tiny.as_mut_ptr() as *mut u8, 32);
+        assert_eq!(
+            sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative),
+            Err(KernelError::InvalidConfig)
+        );
+    }
+
+    extern "C" fn spin_again() -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    const OTHER_ENTRY: TaskEntry = TaskEntry::new(spin_again);
+
+    /// Read the PC slot of a task's initial stack frame.
+    fn frame_pc(tcb: &TaskControlBlock) -> u32 {
+        unsafe { *tcb.stack_pointer.add(SW_FRAME_WORDS + 6) }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_set_priorities_applied_together() {
+        let mut sched = new_scheduler();
+        let a = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        let b = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let c = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), a);
+
+        // One bad entry rejects the whole batch
+        let over = PRIORITY_LEVELS as u8;
+        assert_eq!(sched.set_priorities(&[(c, 9), (b, over)]), Err(KernelError::InvalidPriority));
+        assert_eq!(sched.set_priorities(&[(c, 9), (MAX_TASKS, 2)]), Err(KernelError::InvalidTaskId));
+        assert_eq!(sched.tasks[c].config.priority, 1);
+        assert!(!sched.needs_reschedule);
+
+        // Invert the order in one update
+        sched.set_priorities(&[(a, 1), (b, 3), (c, 5)]).unwrap();
+        assert_eq!(sched.tasks[a].config.priority, 1);
+        assert_eq!(sched.tasks[c].config.priority, 5);
+        assert!(sched.needs_reschedule);
+
+        // A single reschedule sees the complete new set
+        assert_eq!(sched.schedule(), c);
+        assert!(!sched.needs_reschedule);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "sim", ignore = "no exception frame under sim")]
+    fn test_set_entry_applies_on_restart() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let id = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        assert_eq!(frame_pc(&sched.tasks[id]), TEST_ENTRY.address() as u32);
+
+        // Swapping the entry alone does not touch the live stack
+        sched.set_entry(id, OTHER_ENTRY).unwrap();
+        assert_eq!(frame_pc(&sched.tasks[id]), TEST_ENTRY.address() as u32);
+
+        sched.restart_task(id).unwrap();
+        assert_eq!(frame_pc(&sched.tasks[id]), OTHER_ENTRY.address() as u32);
+        assert_eq!(sched.tasks[id].state, TaskState::Ready);
+
+        assert_eq!(sched.set_entry(MAX_TASKS, OTHER_ENTRY), Err(KernelError::InvalidTaskId));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_delay_times_out() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let sleeper = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), sleeper);
+
+        sched.delay_current(20);
+        sched.schedule();
+        assert_eq!(sched.tasks[sleeper].state, TaskState::Blocked);
+
+        for _ in 0..19 {
+            step(&mut sched);
+            assert_eq!(sched.tasks[sleeper].state, TaskState::Blocked);
+        }
+        step(&mut sched);
+        assert_eq!(sched.current(), sleeper);
+        assert_eq!(sched.tasks[sleeper].wake_reason, WakeReason::Timeout);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_delay_until_wakes_at_absolute_tick() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let sleeper = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), sleeper);
+        for _ in 0..7 {
+            step(&mut sched);
+        }
+
+        // Already reached: no block
+        assert!(!sched.delay_until(7));
+        assert_eq!(sched.tasks[sleeper].state, TaskState::Running);
+
+        assert!(sched.delay_until(30));
+        assert_eq!(sched.tasks[sleeper].wake_tick, Some(30));
+        sched.schedule();
+        while sched.tick_count < 29 {
+            step(&mut sched);
+            assert_eq!(sched.tasks[sleeper].state, TaskState::Blocked);
+        }
+        step(&mut sched);
+        assert_eq!(sched.current(), sleeper);
+        assert_eq!(sched.tasks[sleeper].wake_reason, WakeReason::Timeout);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_delay_until_loop_meets_every_period() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let config = TaskConfig { deadline_ticks: 20, ..make_config(5) };
+        let sleeper = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), sleeper);
+
+        // Releases out of phase with creation: each job straddles the
+        // period boundaries counted from tick 0
+        let mut release = PeriodicRelease::new(10, 20);
+        for _ in 0..5 {
+            for _ in 0..15 {
+                step(&mut sched);
+            }
+            assert_eq!(sched.current(), sleeper);
+            assert_eq!(release.advance(sched.tick_count), 0);
+            assert!(sched.delay_until(release.next_wake()));
+            sched.schedule();
+            while sched.current() != sleeper {
+                step(&mut sched);
+            }
+            assert_eq!(sched.tick_count, release.next_wake());
+        }
+        assert_eq!(sched.tasks[sleeper].payoff.deadlines_met, 5);
+        assert_eq!(sched.tasks[sleeper].payoff.deadlines_missed, 0);
+    }
+
+    #[test]
+    fn test_zero_delay_yields() {
+        let mut sched = new_scheduler();
+        let other = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let sleeper = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.set_tie_break(TieBreak::RoundRobin);
+        sched.current_task[0] = other;
+        assert_eq!(sched.schedule(), sleeper);
+
+        sched.delay_current(0);
+        assert_eq!(sched.tasks[sleeper].state, TaskState::Ready);
+        assert_eq!(sched.tasks[sleeper].wake_tick, None);
+        assert_eq!(sched.tasks[sleeper].payoff.voluntary_yields, 1);
+        assert_eq!(sched.last_switch_cause, None);
+        assert_eq!(sched.schedule(), other);
+        assert_eq!(sched.last_switch_cause, Some(SwitchCause::Yield));
+    }
+
+    #[test]
+    fn test_sleeping_task_does_not_starve() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Selfish).unwrap();
+        let sleeper = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), sleeper);
+
+        sched.delay_current(STARVATION_THRESHOLD_MAX * 2);
+        sched.schedule();
+        for _ in 0..STARVATION_THRESHOLD_MAX {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[sleeper].payoff.ticks_since_last_run, 0);
+        assert_eq!(sched.starvation_boost(sleeper), 0);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_cancel_delay_wakes_early() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let sleeper = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        sched.schedule();
+        sched.delay_current(1000);
+        sched.schedule();
+
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.cancel_delay(sleeper), Ok(()));
+        assert_eq!(sched.tasks[sleeper].state, TaskState::Ready);
+        assert_eq!(sched.tasks[sleeper].wake_tick, None);
+        assert_eq!(sched.tasks[sleeper].wake_reason, WakeReason::Cancelled);
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.schedule(), sleeper);
+
+        // Not delayed any more
+        assert_eq!(sched.cancel_delay(sleeper), Err(KernelError::InvalidState));
+    }
+
+    #[test]
+    fn test_max_run_streak_uninterrupted() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Selfish).unwrap();
+        sched.schedule();
+
+        for _ in 0..35 {
+            step(&mut sched);
+        }
+        // Slice expiry re-selects the same task, so the streak spans windows
+        assert_eq!(sched.task(hog).unwrap().payoff.max_run_streak, 35);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_max_run_streak_preempted() {
+        let mut sched = new_scheduler();
+        let low = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Selfish).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        sched.schedule();
+
+        for _ in 0..40 {
+            if sched.current() == high {
+                sched.delay_current(3);
+                sched.schedule();
+            }
+            step(&mut sched);
+        }
+        let streak = sched.task(low).unwrap().payoff.max_run_streak;
+        assert!(streak > 0 && streak <= 3, "preempted streak too long: {}", streak);
+    }
+
+    #[test]
+    fn test_starvation_threshold_scales_with_load() {
+        assert_eq!(scaled_starvation_threshold(STARVATION_BASE_TASKS), STARVATION_THRESHOLD);
+        assert!(scaled_starvation_threshold(2) < scaled_starvation_threshold(4));
+        assert!(scaled_starvation_threshold(4) < scaled_starvation_threshold(8));
+
+        // Clamped at both ends
+        assert_eq!(scaled_starvation_threshold(0), STARVATION_THRESHOLD_MIN);
+        assert_eq!(scaled_starvation_threshold(1000), STARVATION_THRESHOLD_MAX);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_starvation_boost_uses_scaled_threshold() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(10), Strategy::Selfish).unwrap();
+        let starved = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        sched.schedule();
+
+        // Run one evaluation to pick up the two-task threshold
+        for _ in 0..EVAL_FREQUENCY {
+            step(&mut sched);
+        }
+        let threshold = sched.starvation_threshold;
+        assert_eq!(threshold, scaled_starvation_threshold(2));
+
+        let mut boosted_at = None;
+        for t in 0..STARVATION_THRESHOLD_MAX {
+            step(&mut sched);
+            if sched.current() == starved {
+                boosted_at = Some(t);
+                break;
+            }
+        }
+        assert!(boosted_at.is_some());
+        assert_eq!(sched.tasks[hog].state, TaskState::Ready);
+    }
+
+    #[test]
+    fn test_per_task_starvation_thresholds() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Selfish).unwrap();
+        let control = sched
+            .create_task(
+                TEST_ENTRY,
+                TaskConfig { starvation_threshold: 10, ..make_config(1) },
+                Strategy::Cooperative,
+            )
+            .unwrap();
+        let logger = sched
+            .create_task(
+                TEST_ENTRY,
+                TaskConfig { starvation_threshold: 500, ..make_config(1) },
+                Strategy::Cooperative,
+            )
+            .unwrap();
+        let plain = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), hog);
+
+        // Keep the hog on the CPU and note when each boost starts
+        sched.preempt_disable();
+        let mut boosted_at = [None; MAX_TASKS];
+        for t in 1..=500 {
+            sched.tick();
+            for id in [control, logger, plain] {
+                if boosted_at[id].is_none() && sched.starvation_boost(id) > 0 {
+                    boosted_at[id] = Some(t);
+                }
+            }
+        }
+        assert_eq!(boosted_at[control], Some(10));
+        assert_eq!(boosted_at[logger], Some(500));
+        assert_eq!(boosted_at[plain], Some(sched.starvation_threshold));
+        assert_eq!(sched.task_starvation_threshold(plain), sched.starvation_threshold);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_evaluation_boost_uses_task_threshold() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Selfish).unwrap();
+        let control = sched
+            .create_task(
+                TEST_ENTRY,
+                TaskConfig { starvation_threshold: 10, ..make_config(1) },
+                Strategy::Cooperative,
+            )
+            .unwrap();
+        let logger = sched
+            .create_task(
+                TEST_ENTRY,
+                TaskConfig { starvation_threshold: 500, ..make_config(1) },
+                Strategy::Cooperative,
+            )
+            .unwrap();
+        assert_eq!(sched.schedule(), hog);
+
+        // Well past the global threshold, but short of the logger's own
+        sched.tasks[logger].payoff.ticks_since_last_run = 499;
+        sched.tasks[control].payoff.ticks_since_last_run = 9;
+        sched.evaluate_now();
+        assert!(!sched.needs_reschedule);
+
+        sched.tasks[control].payoff.ticks_since_last_run = 10;
+        sched.evaluate_now();
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.schedule(), control);
+        assert_eq!(sched.last_switch_cause(), Some(SwitchCause::Starvation));
+    }
+
+    #[test]
+    fn test_switch_sched_policy() {
+        let mut sched = new_scheduler();
+        let urgent = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Selfish).unwrap();
+        let favoured = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.tasks[favoured].payoff.payoff = 1000;
+
+        assert_eq!(sched.sched_policy(), SchedPolicy::GameTheoretic);
+        assert_eq!(sched.schedule(), favoured);
+
+        sched.set_sched_policy(SchedPolicy::StrictPriority);
+        assert_eq!(sched.sched_policy(), SchedPolicy::StrictPriority);
+        assert!(sched.needs_reschedule);
+        sched.tasks[favoured].state = TaskState::Ready;
+        assert_eq!(sched.schedule(), urgent);
+
+        sched.set_sched_policy(SchedPolicy::GameTheoretic);
+        sched.tasks[urgent].state = TaskState::Ready;
+        assert_eq!(sched.schedule(), favoured);
+    }
+
+    #[test]
+    fn test_tie_break_policies() {
+        let mut sched = new_scheduler();
+        let ids: [usize; 4] = core::array::from_fn(|i| {
+            let config = TaskConfig { priority: 3, deadline_ticks: 50 + 10 * i as u32, ..TaskConfig::DEFAULT };
+            sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap()
+        });
+        sched.tasks[ids[3]].config.deadline_ticks = 0;
+
+        assert_eq!(sched.tie_break(), TieBreak::LeastRecentlyRun);
+        sched.set_tie_break(TieBreak::LowestId);
+        assert_eq!(sched.schedule(), ids[0]);
+
+        // Round-robin: the next tied task after the current one, wrapping
+        sched.set_tie_break(TieBreak::RoundRobin);
+        sched.yield_current();
+        assert_eq!(sched.schedule(), ids[1]);
+        sched.current_task[0] = ids[3];
+        sched.tasks[ids[1]].state = TaskState::Ready;
+        assert_eq!(sched.schedule(), ids[0]);
+
+        sched.set_tie_break(TieBreak::HighestCooperation);
+        sched.tasks[ids[2]].payoff.cooperation_score = 300;
+        sched.yield_current();
+        assert_eq!(sched.schedule(), ids[2]);
+
+        // Task 1's deadline is the nearest
+        sched.set_tie_break(TieBreak::EarliestDeadline);
+        sched.tasks[ids[1]].deadline_tick = 20;
+        sched.yield_current();
+        assert_eq!(sched.schedule(), ids[1]);
+
+        sched.set_tie_break(TieBreak::LeastRecentlyRun);
+        for (id, at) in ids.into_iter().zip([6, 8, 7, 4]) {
+            sched.tasks[id].last_scheduled_at = Some(at);
+        }
+        sched.yield_current();
+        assert_eq!(sched.schedule(), ids[3]);
+
+        // Applies under strict priority too
+        sched.set_sched_policy(SchedPolicy::StrictPriority);
+        sched.set_tie_break(TieBreak::HighestCooperation);
+        sched.yield_current();
+        assert_eq!(sched.schedule(), ids[2]);
+    }
+
+    #[test]
+    fn test_equal_priority_tasks_share_cpu_evenly() {
+        let mut sched = new_scheduler();
+        let ids: [usize; 3] = core::array::from_fn(|_| {
+            let config = TaskConfig { priority: 3, ..TaskConfig::DEFAULT };
+            sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap()
+        });
+        sched.start_first();
+
+        let mut runs = [0u32; 3];
+        for _ in 0..300 {
+            sched.tick();
+            sched.yield_current();
+            // Hold the tasks tied, as if their payoffs had converged
+            for &id in &ids {
+                sched.tasks[id].payoff.payoff = 0;
+            }
+            let next = sched.schedule();
+            runs[ids.iter().position(|&id| id == next).unwrap()] += 1;
+        }
+
+        // Least-recently-run tie-breaking rotates through the tied tasks
+        let (min, max) = (*runs.iter().min().unwrap(), *runs.iter().max().unwrap());
+        assert!(max - min <= 1, "uneven selection counts: {:?}", runs);
+    }
+
+    #[test]
+    fn test_create_tasks_batch() {
+        let mut sched = new_scheduler();
+        let ids = sched
+            .create_tasks(&[
+                (TEST_ENTRY, make_config(1), Strategy::Cooperative),
+                (TEST_ENTRY, make_config(2), Strategy::Selfish),
+                (TEST_ENTRY, make_config(3), Strategy::Cooperative),
+            ])
+            .unwrap();
+        assert_eq!(ids, [0, 1, 2]);
+        assert_eq!(sched.task_count, 3);
+        assert_eq!(sched.tasks[1].strategy, Strategy::Selfish);
+        assert_eq!(sched.tasks[2].config.priority, 3);
+    }
+
+    #[test]
+    fn test_deleted_slots_are_reused() {
+        let mut sched = new_scheduler();
+        for _ in 0..MAX_TASKS {
+            sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        }
+        assert_eq!(
+            sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative),
+            Err(KernelError::TooManyTasks)
+        );
+
+        for id in [1, 4, 6] {
+            sched.delete_task(id).unwrap();
+            assert_eq!(sched.tasks[id].state, TaskState::Terminated);
+            assert!(!sched.tasks[id].active);
+        }
+        assert_eq!(sched.delete_task(4), Err(KernelError::InvalidTaskId));
+        assert_eq!(sched.task_count, MAX_TASKS);
+
+        // Freed slots are filled lowest first, with fresh state
+        for expected in [1, 4, 6] {
+            let id = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Selfish).unwrap();
+            assert_eq!(id, expected);
+            assert_eq!(sched.tasks[id].config.priority, 5);
+            assert_eq!(sched.tasks[id].strategy, Strategy::Selfish);
+            assert_eq!(sched.tasks[id].state, TaskState::Ready);
+        }
+        assert_eq!(sched.task_count, MAX_TASKS);
+        assert_eq!(
+            sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative),
+            Err(KernelError::TooManyTasks)
+        );
+    }
+
+    #[test]
+    fn test_deleting_current_task_switches_away() {
+        let mut sched = new_scheduler();
+        let low = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(6), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), high);
+
+        sched.delete_task(high).unwrap();
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.schedule(), low);
+        assert_eq!(sched.tasks[high].state, TaskState::Terminated);
+
+        // With nothing left the scheduler never resumes the dead context
+        sched.delete_task(low).unwrap();
+        sched.schedule();
+        assert!(!sched.current_is_running());
+        assert!(sched.tasks.iter().all(|t| t.state != TaskState::Running));
+    }
+
+    #[test]
+    fn test_task_specs_macro_fills_defaults() {
+        let mut sched = new_scheduler();
+        let ids = sched
+            .create_tasks(&crate::task_specs! {
+                spin: Selfish { priority: 2, wcet_ticks: 15, time_slice: 10 },
+                spin: Cooperative { priority: 3, deadline_ticks: 100 },
+                spin: Cooperative { priority: 1 },
+            })
+            .unwrap();
+        assert_eq!(ids, [0, 1, 2]);
+
+        assert_eq!(sched.tasks[0].strategy, Strategy::Selfish);
+        assert_eq!(sched.tasks[0].config.priority, 2);
+        assert_eq!(sched.tasks[0].config.wcet_ticks, 15);
+        assert_eq!(sched.tasks[0].config.time_slice, 10);
+        assert_eq!(sched.tasks[0].config.deadline_ticks, 0);
+
+        assert_eq!(sched.tasks[1].strategy, Strategy::Cooperative);
+        assert_eq!(sched.tasks[1].config.deadline_ticks, 100);
+        assert_eq!(sched.tasks[1].config.wcet_ticks, 0);
+
+        // Omitted fields take the defaults
+        let bare = sched.tasks[2].config;
+        assert_eq!(bare.priority, 1);
+        assert_eq!(bare.affinity_mask, TaskConfig::DEFAULT.affinity_mask);
+        assert_eq!(bare.time_slice, 0);
+        assert!(!bare.anchor);
+        assert_eq!(sched.tasks[2].entry.map(TaskEntry::address), Some(TEST_ENTRY.address()));
+    }
+
+    #[test]
+    fn test_create_tasks_batch_rolls_back() {
+        let mut sched = new_scheduler();
+        for _ in 0..MAX_TASKS - 2 {
+            sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        }
+
+        let result = sched.create_tasks(&[
+            (TEST_ENTRY, make_config(4), Strategy::Cooperative),
+            (TEST_ENTRY, make_config(4), Strategy::Cooperative),
+            (TEST_ENTRY, make_config(4), Strategy::Cooperative),
+        ]);
+        assert_eq!(result, Err(KernelError::TooManyTasks));
+        assert_eq!(sched.task_count, MAX_TASKS - 2);
+        assert!(!sched.tasks[MAX_TASKS - 2].active);
+        assert!(!sched.tasks[MAX_TASKS - 1].active);
+
+        // A batch that reuses a deleted slot leaves it deleted
+        sched.delete_task(1).unwrap();
+        let result = sched.create_tasks(&[
+            (TEST_ENTRY, make_config(4), Strategy::Cooperative),
+            (TEST_ENTRY, make_config(4), Strategy::Cooperative),
+            (TEST_ENTRY, make_config(4), Strategy::Cooperative),
+            (TEST_ENTRY, make_config(4), Strategy::Cooperative),
+        ]);
+        assert_eq!(result, Err(KernelError::TooManyTasks));
+        assert_eq!(sched.task_count, MAX_TASKS - 2);
+        assert!(!sched.tasks[1].active);
+        assert_eq!(sched.tasks[1].state, TaskState::Terminated);
+        assert_eq!(sched.tasks[MAX_TASKS - 1].state, TaskState::Suspended);
+        assert_eq!(sched.delete_task(1), Err(KernelError::InvalidTaskId));
+        assert_eq!(sched.create_task(TEST_ENTRY, make_config(4), Strategy::Cooperative), Ok(1));
+    }
+
+    #[test]
+    fn test_equilibrium_flag_debounced() {
+        let mut sched = new_scheduler();
+        assert!(!sched.in_equilibrium);
+
+        // Converge: needs EQUILIBRIUM_DEBOUNCE agreeing windows
+        for _ in 0..EQUILIBRIUM_DEBOUNCE - 1 {
+            sched.debounce_equilibrium(true);
+            assert!(!sched.in_equilibrium);
+        }
+        sched.debounce_equilibrium(true);
+        assert!(sched.in_equilibrium);
+
+        // Noisy single-window perturbations never flip the flag
+        for _ in 0..10 {
+            sched.debounce_equilibrium(false);
+            sched.debounce_equilibrium(true);
+            assert!(sched.in_equilibrium);
+        }
+
+        // A sustained change does
+        for _ in 0..EQUILIBRIUM_DEBOUNCE {
+            sched.debounce_equilibrium(false);
+        }
+        assert!(!sched.in_equilibrium);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_dependent_waits_for_predecessor() {
+        let mut sched = new_scheduler();
+        let producer = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let consumer = sched
+            .create_task(
+                TEST_ENTRY,
+                TaskConfig { priority: 5, deadline_ticks: 20, ..TaskConfig::DEFAULT },
+                Strategy::Cooperative,
+            )
+            .unwrap();
+        sched.set_predecessor(consumer, Some(producer)).unwrap();
+        assert_eq!(sched.tasks[consumer].state, TaskState::Blocked);
+
+        // Despite its higher priority the consumer cannot run first
+        assert_eq!(sched.schedule(), producer);
+
+        let mut consumer_runs = 0;
+        for t in 0..200 {
+            if sched.current() == producer && t % 25 == 5 {
+                sched.signal_done();
+                if sched.needs_reschedule {
+                    sched.schedule();
+                }
+            }
+            step(&mut sched);
+            if sched.current() == consumer {
+                assert!(sched.tasks[consumer].predecessor_signalled,
+                    "consumer ran before its predecessor signalled at tick {}", t);
+                consumer_runs += 1;
+            }
+        }
+        assert!(consumer_runs > 0);
+    }
+
+    #[test]
+    fn test_decline_streak_read_and_reset() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+
+        for i in 0..2 {
+            sched.tasks[id].payoff.previous_payoff = 200 - i * 50;
+            sched.tasks[id].payoff.payoff = 150 - i * 50;
+            game::update_strategies(&mut sched.tasks, sched.task_count, &sched.metrics);
+        }
+        assert_eq!(sched.decline_streak(id), Some(2));
+
+        sched.reset_decline_streak(id).unwrap();
+        assert_eq!(sched.decline_streak(id), Some(0));
+
+        assert_eq!(sched.decline_streak(MAX_TASKS), None);
+        assert_eq!(sched.reset_decline_streak(MAX_TASKS), Err(KernelError::InvalidTaskId));
+    }
+
+    #[test]
+    fn test_pinned_strategy_survives_decline() {
+        let mut sched = new_scheduler();
+        let safety = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Selfish).unwrap();
+        let other = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Selfish).unwrap();
+        sched.set_strategy(safety, Strategy::Cooperative).unwrap();
+        sched.set_strategy(other, Strategy::Cooperative).unwrap();
+        sched.unlock_strategy(other).unwrap();
+
+        for i in 0..STRATEGY_HYSTERESIS as i32 {
+            for id in [safety, other] {
+                sched.tasks[id].payoff.previous_payoff = 200 - i * 50;
+                sched.tasks[id].payoff.payoff = 150 - i * 50;
+            }
+            game::update_strategies(&mut sched.tasks, sched.task_count, &sched.metrics);
+        }
+        assert_eq!(sched.strategy(safety), Ok(Strategy::Cooperative));
+        assert_eq!(sched.strategy(other), Ok(Strategy::Selfish));
+
+        // The circuit-breaker skips a pinned defector
+        sched.set_strategy(safety, Strategy::Selfish).unwrap();
+        assert_eq!(game::nudge_to_cooperation(&mut sched.tasks, sched.task_count), Some(other));
+        assert_eq!(game::nudge_to_cooperation(&mut sched.tasks, sched.task_count), None);
+
+        assert_eq!(sched.set_strategy(MAX_TASKS, Strategy::Cooperative), Err(KernelError::InvalidTaskId));
+        assert_eq!(sched.strategy(MAX_TASKS), Err(KernelError::InvalidTaskId));
+    }
+
+    #[test]
+    fn test_defection_circuit_breaker() {
+        let mut sched = new_scheduler();
+        for _ in 0..3 {
+            sched.create_task(TEST_ENTRY, make_config(3), Strategy::Selfish).unwrap();
+        }
+        sched.update_system_metrics();
+        assert_eq!(sched.metrics.global_cooperation_ratio, 0);
+
+        for _ in 0..DEFECTION_RECOVERY_WINDOWS - 1 {
+            sched.defection_circuit_breaker();
+        }
+        assert!(sched.tasks[..3].iter().all(|t| t.strategy == Strategy::Selfish));
+
+        sched.defection_circuit_breaker();
+        assert!(sched.tasks[..3].iter().any(|t| t.strategy == Strategy::Cooperative));
+
+        // Disabled breaker never intervenes
+        sched.set_defection_recovery(false);
+        for i in 0..3 {
+            sched.tasks[i].strategy = Strategy::Selfish;
+        }
+        for _ in 0..DEFECTION_RECOVERY_WINDOWS * 2 {
+            sched.defection_circuit_breaker();
+        }
+        assert!(sched.tasks[..3].iter().all(|t| t.strategy == Strategy::Selfish));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_remaining_slice() {
+        let mut sched = new_scheduler();
+        let config = TaskConfig { priority: 3, time_slice: 8, ..TaskConfig::DEFAULT };
+        sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.schedule();
+        assert_eq!(sched.remaining_slice(), 8);
+
+        sched.tasks[0].ticks_remaining = 5;
+        assert_eq!(sched.remaining_slice(), 5);
+        step(&mut sched);
+        assert_eq!(sched.remaining_slice(), 4);
+
+        // Reloaded after a voluntary yield
+        sched.yield_current();
+        sched.schedule();
+        assert_eq!(sched.remaining_slice(), 8);
+    }
+
+    #[test]
+    fn test_idle_ticks_and_load() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.load_percent(), 0);
+        sched.schedule();
+
+        // Busy for 75 ticks, then asleep for 25
+        for _ in 0..75 {
+            step(&mut sched);
+        }
+        sched.delay_current(25);
+        sched.schedule();
+        for _ in 0..25 {
+            step(&mut sched);
+        }
+
+        assert_eq!(sched.tick_count, 100);
+        assert_eq!(sched.idle_ticks, 25);
+        assert_eq!(sched.load_percent(), 75);
+        assert_eq!(sched.tasks[id].state, TaskState::Running);
+    }
+
+    #[test]
+    fn test_cpu_utilization_per_mille() {
+        let mut sched = new_scheduler();
+        let idle = sched.create_idle_task(TEST_ENTRY).unwrap();
+        let busy = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.cpu_utilization(busy), Some(0));
+        sched.schedule();
+
+        // Busy for 30 ticks, then asleep for 10
+        for _ in 0..30 {
+            step(&mut sched);
+        }
+        sched.delay_current(10);
+        assert_eq!(sched.schedule(), idle);
+        for _ in 0..10 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tick_count, 40);
+        assert_eq!(sched.cpu_utilization(busy), Some(750));
+        assert_eq!(sched.cpu_utilization(idle), Some(250));
+        assert_eq!(sched.cpu_utilization(MAX_TASKS), None);
+
+        // The window restarts at every evaluation (EVAL_FREQUENCY = 10),
+        // so it only covers the 5 busy ticks since tick 40
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.window_start_tick, 40);
+        assert_eq!(sched.window_cpu_utilization(busy), Some(1000));
+        assert_eq!(sched.window_cpu_utilization(idle), Some(0));
+        assert_eq!(sched.cpu_utilization(busy), Some(35 * 1000 / 45));
+    }
+
+    #[cfg(feature = "cooperative-only")]
+    #[test]
+    fn test_cooperative_only_switches_on_yield_or_block() {
+        let mut sched = new_scheduler();
+        let low = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), high);
+
+        // Far beyond any time slice or starvation threshold: no preemption
+        for _ in 0..STARVATION_THRESHOLD_MAX * 2 {
+            step(&mut sched);
+            assert_eq!(sched.current(), high);
+        }
+
+        // Blocking switches
+        sched.delay_current(10);
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.schedule(), low);
+
+        // Waking the higher-priority task does not preempt the runner
+        for _ in 0..20 {
+            step(&mut sched);
+            assert_eq!(sched.current(), low);
+        }
+        assert_eq!(sched.tasks[high].state, TaskState::Ready);
+        assert!(!sched.needs_reschedule);
+
+        // Yielding hands the decision back to the scheduler
+        sched.yield_current();
+        assert!(sched.needs_reschedule);
+    }
+
+    #[test]
+    fn test_yield_within_deadline_counts_as_met() {
+        let mut sched = new_scheduler();
+        let config = TaskConfig { priority: 3, deadline_ticks: 10, ..TaskConfig::DEFAULT };
+        let id = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), id);
+
+        // Yields 3 ticks into the period, then keeps yielding: one met job
+        for t in 0..10 {
+            if t >= 3 {
+                sched.yield_current();
+                sched.schedule();
+            }
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[id].payoff.deadlines_met, 1);
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 0);
+
+        // Still running when the next deadline passes: missed
+        for _ in 0..10 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[id].payoff.deadlines_met, 1);
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 1);
+    }
+
+    #[test]
+    fn test_deadline_jitter_tracks_completion_offsets() {
+        let mut sched = new_scheduler();
+        let config = TaskConfig { priority: 3, deadline_ticks: 20, ..TaskConfig::DEFAULT };
+        let id = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.schedule();
+        assert_eq!(sched.deadline_jitter(id), None);
+
+        // Complete 15, 5 and 18 ticks into successive periods
+        for done_at in [15, 5, 18] {
+            for t in 0..20 {
+                if t == done_at {
+                    sched.job_complete();
+                }
+                step(&mut sched);
+            }
+        }
+        assert_eq!(sched.deadline_jitter(id), Some((-15, -2)));
+        assert_eq!(sched.tasks[id].payoff.deadlines_met, 3);
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 0);
+
+        // A job that overruns its deadline by 4 ticks
+        for _ in 0..24 {
+            step(&mut sched);
+        }
+        sched.job_complete();
+        assert_eq!(sched.deadline_jitter(id), Some((-15, 4)));
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 1);
+
+        sched.reset_deadline_jitter(id).unwrap();
+        assert_eq!(sched.deadline_jitter(id), None);
+        assert_eq!(sched.deadline_jitter(MAX_TASKS), None);
+    }
+
+    #[test]
+    fn test_pause_freezes_counters() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.schedule();
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+
+        sched.pause();
+        let slice = sched.remaining_slice();
+        for _ in 0..50 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tick_count, 5);
+        assert_eq!(sched.tasks[id].total_ticks, 5);
+        assert_eq!(sched.remaining_slice(), slice);
+
+        sched.single_tick();
+        assert_eq!(sched.tick_count, 6);
+        assert_eq!(sched.tasks[id].total_ticks, 6);
+        assert!(sched.paused);
+
+        sched.resume();
+        step(&mut sched);
+        assert_eq!(sched.tick_count, 7);
+    }
+
+    fn nop() {}
+
+    #[test]
+    fn test_for_each_task_visits_allocated_tasks() {
+        let mut sched = new_scheduler();
+        for priority in [2, 4, 6] {
+            sched.create_task(TEST_ENTRY, make_config(priority), Strategy::Cooperative).unwrap();
+        }
+        sched.delete_task(1).unwrap();
+
+        let mut seen = [None; MAX_TASKS];
+        let mut count = 0;
+        sched.for_each_task(|tcb| {
+            seen[count] = Some((tcb.id, tcb.config.priority));
+            count += 1;
+        });
+        assert_eq!(count, 2);
+        assert_eq!(seen[..2], [Some((0, 2)), Some((2, 6))]);
+    }
+
+    #[test]
+    fn test_collect_task_info_copies_out() {
+        let mut sched = new_scheduler();
+        let named = TaskConfig { name: "sensor", ..make_config(5) };
+        let a = sched.create_task(TEST_ENTRY, named, Strategy::Cooperative).unwrap();
+        let b = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Selfish).unwrap();
+        sched.schedule();
+        step(&mut sched);
+        sched.delay_current(5);
+        sched.schedule();
+
+        let mut info = [TaskInfo::EMPTY; MAX_TASKS];
+        assert_eq!(sched.collect_task_info(&mut info), 2);
+        assert_eq!(info[0].id, a);
+        assert_eq!(info[0].name, "sensor");
+        assert_eq!(info[0].state, TaskState::Blocked);
+        assert_eq!(info[0].block_reason, Some(BlockReason::Delay));
+        assert_eq!(info[0].total_ticks, 1);
+        assert_eq!(info[1].id, b);
+        assert_eq!(info[1].state, TaskState::Running);
+        assert_eq!(info[1].strategy, Strategy::Selfish);
+        assert_eq!(info[1].effective_priority, sched.tasks[b].effective_priority());
+        assert_eq!(info[2], TaskInfo::EMPTY);
+
+        // A short buffer takes the lowest ids
+        let mut one = [TaskInfo::EMPTY; 1];
+        assert_eq!(sched.collect_task_info(&mut one), 1);
+        assert_eq!(one[0].id, a);
+    }
+
+    #[test]
+    fn test_payoff_scales_time_slice_within_bounds() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.schedule();
+        let refill = |sched: &mut Scheduler, payoff: i32| {
+            sched.tasks[id].payoff.payoff = payoff;
+            sched.yield_current();
+            sched.schedule();
+            sched.remaining_slice()
+        };
+
+        // Configured slice of 10 ticks, scaled 50%..=200%
+        assert_eq!(refill(&mut sched, 0), 10);
+        assert_eq!(refill(&mut sched, 40 * QUANTUM_PAYOFF_STEP), 14);
+        assert_eq!(refill(&mut sched, i32::MAX), 20);
+        assert_eq!(refill(&mut sched, i32::MIN), 5);
+
+        // Never below one tick
+        sched.set_quantum_scale(1, 100).unwrap();
+        assert_eq!(refill(&mut sched, i32::MIN), 1);
+
+        sched.set_quantum_scale(100, 100).unwrap();
+        assert_eq!(refill(&mut sched, i32::MAX), 10);
+        assert_eq!(sched.set_quantum_scale(0, 100), Err(KernelError::InvalidConfig));
+        assert_eq!(sched.set_quantum_scale(150, 120), Err(KernelError::InvalidConfig));
+        assert_eq!((sched.quantum_scale_min, sched.quantum_scale_max), (100, 100));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_tick_rate_change_keeps_wall_clock_durations() {
+        let mut sched = new_scheduler();
+        let timed = TaskConfig { deadline_ticks: 100, wcet_ticks: 20, ..make_config(3) };
+        let periodic = sched.create_task(TEST_ENTRY, timed, Strategy::Cooperative).unwrap();
+        let sleeper = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        let timer = sched.create_timer(30, true, nop).unwrap();
+        sched.start_timer(timer).unwrap();
+        assert_eq!(sched.schedule(), sleeper);
+        sched.delay_current(50);
+        sched.schedule();
+        for _ in 0..10 {
+            step(&mut sched);
+        }
+
+        // 1 kHz → 100 Hz: every pending duration shrinks tenfold
+        assert_eq!(sched.set_tick_hz(100), Ok(()));
+        assert_eq!(sched.tasks[sleeper].wake_tick, Some(10 + 4));
+        assert_eq!(sched.tasks[periodic].config.deadline_ticks, 10);
+        assert_eq!(sched.tasks[periodic].config.wcet_ticks, 2);
+        assert_eq!(sched.tasks[periodic].period_ticks, 1);
+        assert_eq!(sched.timers[timer].period, 3);
+        assert_eq!(sched.timers[timer].remaining, 2);
+
+        for _ in 0..4 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[sleeper].state, TaskState::Running);
+
+        // And back: durations round-trip, nothing collapses to 0
+        assert_eq!(sched.set_tick_hz(1000), Ok(()));
+        assert_eq!(sched.tasks[periodic].config.deadline_ticks, 100);
+        assert_eq!(sched.tasks[periodic].config.wcet_ticks, 20);
+        assert_eq!(sched.set_tick_hz(0), Err(KernelError::InvalidConfig));
+        assert_eq!(sched.tick_hz, 1000);
+    }
+
+    #[test]
+    fn test_uptime_survives_tick_rate_changes() {
+        let mut sched = new_scheduler();
+        for _ in 0..1500 {
+            sched.tick();
+        }
+        assert_eq!(sched.uptime_ms(), 1500);
+
+        // 25 ticks at 100 Hz are 250 ms, not 25
+        sched.set_tick_hz(100).unwrap();
+        for _ in 0..25 {
+            sched.tick();
+        }
+        assert_eq!(sched.uptime_ms(), 1750);
+
+        sched.set_tick_hz(1000).unwrap();
+        sched.tick();
+        assert_eq!(sched.uptime_ms(), 1751);
+        assert_eq!(sched.tick_count, 1526);
+    }
+
+    #[test]
+    fn test_account_cycles_per_segment() {
+        let mut sched = new_scheduler();
+        let a = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        let b = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+
+        // First reading only opens a segment
+        assert_eq!(sched.schedule(), a);
+        sched.account_cycles(1_000);
+        assert_eq!(sched.metrics.total_cycles, 0);
+
+        // `a` yields after 200 cycles — a fraction of a tick
+        sched.delay_current(5);
+        assert_eq!(sched.schedule(), b);
+        sched.account_cycles(1_200);
+        assert_eq!(sched.tasks[a].payoff.cpu_cycles_used, 200);
+
+        // `b` runs across a counter wrap, then blocks: idle
+        sched.delay_current(5);
+        sched.schedule();
+        sched.account_cycles(u32::MAX - 99);
+        let b_cycles = (u32::MAX - 99 - 1_200) as u64;
+        assert_eq!(sched.tasks[b].payoff.cpu_cycles_used, b_cycles);
+        assert_eq!(sched.segment_owner, None);
+
+        sched.account_cycles(400);
+        assert_eq!(sched.tasks[a].payoff.cpu_cycles_used, 200);
+        assert_eq!(sched.tasks[b].payoff.cpu_cycles_used, b_cycles);
+        assert_eq!(sched.metrics.total_cycles, 200 + b_cycles + 500);
+    }
+
+    #[test]
+    fn test_priority_donation_boosts_and_reverts() {
+        let mut sched = new_scheduler();
+        let producer = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let consumer = sched.create_task(TEST_ENTRY, make_config(8), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), consumer);
+
+        assert_eq!(sched.donate_priority(consumer, 4, 0), Err(KernelError::InvalidState));
+        assert_eq!(sched.donate_priority(MAX_TASKS, 4, 0), Err(KernelError::InvalidTaskId));
+
+        // Capped at the donor's own base priority
+        sched.donate_priority(producer, 20, 0).unwrap();
+        assert_eq!(sched.tasks[producer].boosted_priority(), 10);
+        assert_eq!(sched.tasks[producer].config.priority, 2);
+
+        sched.revoke_donation().unwrap();
+        assert_eq!(sched.tasks[producer].boosted_priority(), 2);
+        assert_eq!(sched.revoke_donation(), Err(KernelError::InvalidState));
+
+        // Timed donation lapses on its own
+        sched.donate_priority(producer, 4, 15).unwrap();
+        assert_eq!(sched.tasks[producer].boosted_priority(), 6);
+        for _ in 0..14 {
+            sched.tick();
+        }
+        assert!(sched.tasks[producer].donation.is_some());
+        sched.tick();
+        assert_eq!(sched.tasks[producer].donation, None);
+        assert_eq!(sched.tasks[producer].boosted_priority(), 2);
+    }
+
+    fn breakdown_sum(b: PriorityBreakdown) -> i32 {
+        b.base + b.inheritance_boost + b.payoff_adjustment + b.starvation_boost
+    }
+
+    #[test]
+    #[cfg_attr(not(feature = "dual-core"), ignore = "needs two cores")]
+    fn test_two_cores_honor_affinity() {
+        let mut sched = new_scheduler();
+        let idle = sched.create_idle_task(TEST_ENTRY).unwrap();
+        let pinned0 = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        let pinned1 = sched
+            .create_task(TEST_ENTRY, TaskConfig { affinity_mask: 0b10, ..make_config(7) }, Strategy::Cooperative)
+            .unwrap();
+        let either = sched
+            .create_task(TEST_ENTRY, TaskConfig { affinity_mask: 0b11, ..make_config(3) }, Strategy::Cooperative)
+            .unwrap();
+
+        // The higher-priority task is pinned elsewhere, so each core gets its own
+        assert_eq!(sched.schedule_for_core(0), pinned0);
+        assert_eq!(sched.schedule_for_core(1), pinned1);
+        assert_eq!(sched.current(), pinned1);
+        assert!(sched.is_running_elsewhere(pinned0));
+
+        // Core 1 blocks: the floating task runs there, never on both cores
+        sched.delay_current(10);
+        assert_eq!(sched.schedule_for_core(1), either);
+        sched.enter_core(0);
+        sched.delay_current(10);
+        assert_eq!(sched.schedule_for_core(0), idle);
+        assert_eq!(crate::invariant::check(&sched), Ok(()));
+
+        // Nothing left for core 1 and the idle task is taken: it parks
+        sched.enter_core(1);
+        sched.delay_current(10);
+        assert_eq!(sched.schedule_for_core(1), either);
+        assert_eq!(sched.tasks[either].state, TaskState::Blocked);
+        assert_eq!(sched.tasks[idle].state, TaskState::Running);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_preempt_lock_defers_reschedule() {
+        let mut sched = new_scheduler();
+        let low = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(6), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), high);
+        sched.delay_current(3);
+        assert_eq!(sched.schedule(), low);
+
+        // Nested lock; the wakeup (tick ISR) requests a switch that waits
+        sched.preempt_disable();
+        sched.preempt_disable();
+        for _ in 0..3 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[high].state, TaskState::Ready);
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.current(), low);
+        assert_eq!(sched.tasks[low].state, TaskState::Running);
+
+        // Neither a yield nor slice expiry gets past the lock
+        sched.yield_current();
+        assert_eq!(sched.tasks[low].payoff.voluntary_yields, 0);
+        for _ in 0..DEFAULT_TIME_SLICE {
+            step(&mut sched);
+        }
+        assert_eq!(sched.current(), low);
+
+        // Only the outermost enable applies the deferred switch
+        assert!(!sched.preempt_enable());
+        assert_eq!(sched.schedule(), low);
+        assert!(sched.preempt_enable());
+        assert_eq!(sched.tasks[low].payoff.voluntary_yields, 1);
+        assert_eq!(sched.schedule(), high);
+        assert!(!sched.preempt_enable(), "unbalanced enable is ignored");
+    }
+
+    #[test]
+    fn test_task_names() {
+        let mut sched = new_scheduler();
+        let named = |name| TaskConfig { name, ..make_config(3) };
+        let sensor = sched.create_task(TEST_ENTRY, named("sensor"), Strategy::Cooperative).unwrap();
+        let unnamed = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let logger = sched.create_task(TEST_ENTRY, named("logger"), Strategy::Cooperative).unwrap();
+
+        assert_eq!(sched.task_name(sensor), Some("sensor"));
+        assert_eq!(sched.task_name(unnamed), Some(""));
+        assert_eq!(sched.task_name(MAX_TASKS), None);
+        assert_eq!(sched.find_by_name("logger"), Some(logger));
+        assert_eq!(sched.find_by_name("missing"), None);
+        assert_eq!(sched.find_by_name(""), None);
+        assert_eq!(sched.stats().tasks[sensor].unwrap().name, "sensor");
+
+        sched.delete_task(logger).unwrap();
+        assert_eq!(sched.find_by_name("logger"), None);
+    }
+
+    #[test]
+    fn test_stats_snapshot() {
+        let mut sched = new_scheduler();
+        let coop = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let gone = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let hog = sched.create_task(TEST_ENTRY, make_config(4), Strategy::Selfish).unwrap();
+        sched.delete_task(gone).unwrap();
+        sched.schedule();
+        for _ in 0..EVAL_FREQUENCY {
+            step(&mut sched);
+        }
+        sched.context_switches = 7;
+
+        let stats = sched.stats();
+        assert_eq!(stats.tick_count, EVAL_FREQUENCY as u64);
+        assert_eq!(stats.active_tasks, 2);
+        assert_eq!(stats.global_cooperation_ratio, 50);
+        assert_eq!(stats.context_switches, 7);
+        assert_eq!(stats.in_equilibrium, sched.in_equilibrium);
+        assert_eq!(stats.tasks[gone], None);
+        assert!(stats.tasks[sched.task_count..].iter().all(Option::is_none));
+
+        let row = stats.tasks[hog].unwrap();
+        assert_eq!(row.id, hog);
+        assert_eq!(row.strategy, Strategy::Selfish);
+        assert_eq!(row.effective_priority, sched.tasks[hog].effective_priority());
+        assert_eq!(row.payoff, sched.tasks[hog].payoff.payoff);
+        assert_eq!(row.cpu_ticks_used, EVAL_FREQUENCY);
+        assert_eq!(stats.tasks[coop].unwrap().cpu_ticks_used, 0);
+
+        // Reading does not perturb the scheduler
+        assert_eq!(sched.stats(), stats);
+    }
+
+    #[test]
+    fn test_priority_breakdown_components_sum_to_effective() {
+        let mut sched = new_scheduler();
+        let producer = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let consumer = sched.create_task(TEST_ENTRY, make_config(8), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), consumer);
+
+        let plain = sched.priority_breakdown(producer).unwrap();
+        assert_eq!(plain, PriorityBreakdown {
+            base: 2,
+            inheritance_boost: 0,
+            payoff_adjustment: 0,
+            starvation_boost: 0,
+            effective: 2,
+        });
+
+        sched.donate_priority(producer, 3, 0).unwrap();
+        sched.tasks[producer].payoff.payoff = 400;
+        sched.tasks[producer].payoff.ticks_since_last_run = sched.starvation_threshold;
+        let boosted = sched.priority_breakdown(producer).unwrap();
+        assert_eq!(boosted.inheritance_boost, 3);
+        assert_eq!(boosted.payoff_adjustment, 4);
+        assert_eq!(boosted.starvation_boost, sched.starvation_boost(producer));
+        assert!(boosted.starvation_boost > 0);
+        assert_eq!(breakdown_sum(boosted), boosted.effective);
+        assert_eq!(
+            boosted.effective,
+            sched.tasks[producer].effective_priority() + sched.starvation_boost(producer)
+        );
+
+        // A large penalty is reported as applied, i.e. clamped at zero
+        sched.tasks[consumer].payoff.payoff = -5000;
+        let penalized = sched.priority_breakdown(consumer).unwrap();
+        assert_eq!(penalized.payoff_adjustment, -8);
+        assert_eq!(penalized.effective, 0);
+        assert_eq!(breakdown_sum(penalized), penalized.effective);
+
+        assert_eq!(sched.priority_breakdown(MAX_TASKS), None);
+    }
+
+    /// Run one 30-tick period of a task with a 5-tick WCET, completing the
+    /// job at `done_at` if given.
+    fn run_overrunning_job(policy: OverrunPolicy, done_at: Option<u32>) -> Scheduler {
+        let mut sched = new_scheduler();
+        sched.set_overrun_policy(policy);
+        let config = TaskConfig {
+            priority: 3,
+            deadline_ticks: 30,
+            wcet_ticks: 5,
+            ..TaskConfig::DEFAULT
+        };
+        sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.schedule();
+        for t in 0..30 {
+            if Some(t) == done_at {
+                sched.job_complete();
+            }
+            step(&mut sched);
+        }
+        sched
+    }
+
+    /// Run a task with a 10-tick period whose jobs each need `work` ticks.
+    /// A job starts at a release and the task idles once it completes.
+    fn run_overloaded_task(policy: DegradePolicy, work: u32, ticks: u32) -> Scheduler {
+        let mut sched = new_scheduler();
+        sched.set_degrade_policy(policy);
+        sched.set_overload_hook(Some(record_overload));
+        let config = TaskConfig {
+            priority: 3,
+            deadline_ticks: 10,
+            wcet_ticks: 5,
+            ..TaskConfig::DEFAULT
+        };
+        sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.schedule();
+
+        let (mut worked, mut released, mut last_period) = (0, true, 0);
+        for _ in 0..ticks {
+            step(&mut sched);
+            let period = sched.tasks[0].period_ticks;
+            released |= period < last_period;
+            last_period = period;
+            if released {
+                worked += 1;
+                if worked == work {
+                    sched.job_complete();
+                    worked = 0;
+                    released = false;
+                }
+            }
+        }
+        sched
+    }
+
+    static OVERLOAD_ALARMS: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+    fn record_overload(_id: usize) {
+        OVERLOAD_ALARMS.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_overloaded_task_misses_forever_without_degradation() {
+        let early = run_overloaded_task(DegradePolicy::None, 25, 500).tasks[0].payoff.deadlines_missed;
+        let late = run_overloaded_task(DegradePolicy::None, 25, 1000).tasks[0].payoff.deadlines_missed;
+        assert!(late > early && early > 0);
+    }
+
+    #[test]
+    fn test_skip_releases_stops_miss_storm() {
+        let early = run_overloaded_task(DegradePolicy::SkipReleases, 25, 500);
+        let late = run_overloaded_task(DegradePolicy::SkipReleases, 25, 1000);
+        assert_eq!(late.tasks[0].payoff.deadlines_missed, early.tasks[0].payoff.deadlines_missed);
+        assert!(late.tasks[0].payoff.deadlines_met > early.tasks[0].payoff.deadlines_met);
+        // Reduced rate; the configured period is untouched
+        assert!(late.tasks[0].release_stride * 10 >= 25);
+        assert_eq!(late.tasks[0].config.deadline_ticks, 10);
+    }
+
+    #[test]
+    fn test_widen_period_stops_miss_storm() {
+        let early = run_overloaded_task(DegradePolicy::WidenPeriod, 25, 500);
+        let late = run_overloaded_task(DegradePolicy::WidenPeriod, 25, 1000);
+        assert_eq!(late.tasks[0].payoff.deadlines_missed, early.tasks[0].payoff.deadlines_missed);
+        assert!(late.tasks[0].config.deadline_ticks >= 25);
+        assert_eq!(late.tasks[0].release_stride, 1);
+    }
+
+    #[test]
+    fn test_alarm_reports_overload_without_retiming() {
+        OVERLOAD_ALARMS.store(0, core::sync::atomic::Ordering::SeqCst);
+        let sched = run_overloaded_task(DegradePolicy::Alarm, 25, 300);
+        let missed = sched.tasks[0].payoff.deadlines_missed as usize;
+        assert_eq!(OVERLOAD_ALARMS.load(core::sync::atomic::Ordering::SeqCst), missed / OVERLOAD_PERIODS as usize);
+        assert!(missed >= OVERLOAD_PERIODS as usize);
+        assert_eq!(sched.tasks[0].config.deadline_ticks, 10);
+        assert_eq!(sched.tasks[0].release_stride, 1);
+    }
+
+    #[test]
+    fn test_strict_overrun_penalized_even_when_deadline_met() {
+        let sched = run_overrunning_job(OverrunPolicy::Strict, Some(12));
+        assert_eq!(sched.tasks[0].payoff.deadlines_met, 1);
+        // Every slice expiry past the WCET (ticks 10, 20, 30) counts
+        assert_eq!(sched.tasks[0].payoff.overruns, 3);
+        assert_eq!(sched.tasks[0].payoff.soft_overruns, 0);
+    }
+
+    #[test]
+    fn test_soft_overrun_when_deadline_met() {
+        let sched = run_overrunning_job(OverrunPolicy::SoftIfMet, Some(12));
+        let payoff = &sched.tasks[0].payoff;
+        assert_eq!(payoff.deadlines_met, 1);
+        assert_eq!(payoff.overruns, 0);
+        assert_eq!(payoff.consecutive_overruns, 0);
+        assert_eq!(payoff.soft_overruns, 1);
+        assert!(!sched.tasks[0].job_overran);
+    }
+
+    #[test]
+    fn test_soft_policy_keeps_full_penalty_when_deadline_missed() {
+        let met = run_overrunning_job(OverrunPolicy::SoftIfMet, Some(12));
+        let missed = run_overrunning_job(OverrunPolicy::SoftIfMet, None);
+        let payoff = &missed.tasks[0].payoff;
+        assert_eq!(payoff.deadlines_missed, 1);
+        assert_eq!(payoff.overruns, 1);
+        assert_eq!(payoff.consecutive_overruns, 1);
+        assert_eq!(payoff.soft_overruns, 0);
+        assert!(payoff.cooperation_score < met.tasks[0].payoff.cooperation_score);
+    }
+
+    /// Run one 20-tick period of a two-task bundle under round-robin,
+    /// each member completing a job every `job_ticks[i]` ticks of CPU.
+    fn run_bundle(job_ticks: [u32; 2]) -> (Scheduler, usize, [usize; 2]) {
+        let mut sched = new_scheduler();
+        sched.set_sched_policy(SchedPolicy::StrictPriority);
+        sched.set_tie_break(TieBreak::RoundRobin);
+        let a = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let b = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let bundle = sched.create_bundle(20).unwrap();
+        sched.set_bundle(a, Some(bundle)).unwrap();
+        sched.set_bundle(b, Some(bundle)).unwrap();
+        sched.schedule();
+
+        let mut worked = [0; 2];
+        for _ in 0..20 {
+            let current = sched.current();
+            step(&mut sched);
+            worked[current] += 1;
+            if worked[current] == job_ticks[current] {
+                worked[current] = 0;
+                sched.job_complete();
+                sched.yield_current();
+                sched.schedule();
+            }
+        }
+        (sched, bundle, [a, b])
+    }
+
+    #[test]
+    fn test_bundle_met_only_when_all_members_finish() {
+        let (sched, bundle, [a, b]) = run_bundle([4, 4]);
+        assert_eq!(sched.bundles[bundle].met, 1);
+        assert_eq!(sched.bundles[bundle].missed, 0);
+        assert_eq!(sched.tasks[a].payoff.deadlines_met, 1);
+        assert_eq!(sched.tasks[b].payoff.deadlines_met, 1);
+        assert_eq!(sched.bundle_of(a), Some(bundle));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_bundle_miss_attributed_to_unfinished_member() {
+        // The second member's 30-tick job cannot finish before the
+        // shared deadline, even though the first completes twice
+        let (sched, bundle, [a, b]) = run_bundle([4, 30]);
+        assert_eq!(sched.bundles[bundle].met, 0);
+        assert_eq!(sched.bundles[bundle].missed, 1);
+        assert_eq!(sched.tasks[a].payoff.deadlines_missed, 0);
+        assert_eq!(sched.tasks[a].payoff.deadlines_met, 0);
+        assert_eq!(sched.tasks[b].payoff.deadlines_missed, 1);
+    }
+
+    #[test]
+    fn test_bundle_membership_errors() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.create_bundle(0), Err(KernelError::InvalidState));
+        for _ in 0..MAX_BUNDLES {
+            sched.create_bundle(10).unwrap();
+        }
+        assert_eq!(sched.create_bundle(10), Err(KernelError::TooManyBundles));
+
+        sched.set_bundle(id, Some(0)).unwrap();
+        sched.set_bundle(id, Some(1)).unwrap();
+        assert_eq!(sched.bundle_of(id), Some(1));
+        assert_eq!(sched.bundles[0].members, 0);
+        sched.set_bundle(id, None).unwrap();
+        assert_eq!(sched.bundle_of(id), None);
+        assert_eq!(sched.set_bundle(MAX_TASKS, Some(0)), Err(KernelError::InvalidTaskId));
+    }
+
+    /// Two equal-priority tasks under strict priority and round-robin, so
+    /// every requested reschedule switches to the other task. The task
+    /// configured with `runs_first` is selected first. Tasks are created in
+    /// place: moving a scheduler moves their stacks.
+    fn start_round_robin_pair(sched: &mut Scheduler, runs_first: TaskConfig) {
+        sched.set_sched_policy(SchedPolicy::StrictPriority);
+        sched.set_tie_break(TieBreak::RoundRobin);
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let first = sched.create_task(TEST_ENTRY, runs_first, Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), first);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_switch_cause_slice_yield_block() {
+        assert_eq!(new_scheduler().last_switch_cause(), None);
+        let mut sched = new_scheduler();
+        start_round_robin_pair(&mut sched, make_config(3));
+
+        let first = sched.current();
+        for _ in 0..DEFAULT_TIME_SLICE {
+            step(&mut sched);
+        }
+        assert_ne!(sched.current(), first);
+        assert_eq!(sched.last_switch_cause(), Some(SwitchCause::SliceExpiry));
+
+        sched.yield_current();
+        sched.schedule();
+        assert_eq!(sched.current(), first);
+        assert_eq!(sched.last_switch_cause(), Some(SwitchCause::Yield));
+
+        sched.delay_current(5);
+        sched.schedule();
+        assert_ne!(sched.current(), first);
+        assert_eq!(sched.last_switch_cause(), Some(SwitchCause::Block));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_switch_cause_preempt() {
+        let mut sched = new_scheduler();
+        let low = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), high);
+        sched.delay_current(100);
+        assert_eq!(sched.schedule(), low);
+
+        sched.cancel_delay(high).unwrap();
+        assert_eq!(sched.schedule(), high);
+        assert_eq!(sched.last_switch_cause(), Some(SwitchCause::Preempt));
+    }
+
+    #[test]
+    fn test_starvation_boost_curves_and_cap() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let threshold = sched.starvation_threshold;
+
+        sched.tasks[id].payoff.ticks_since_last_run = threshold - 1;
+        assert_eq!(sched.starvation_boost(id), 0);
+
+        // Halfway between the first and second threshold
+        sched.tasks[id].payoff.ticks_since_last_run = threshold * 3 / 2;
+        assert_eq!(sched.starvation_boost(id), 2);
+        sched.set_starvation_boost(BoostCurve::Linear, STARVATION_BOOST_MAX);
+        assert_eq!(sched.starvation_boost(id), 3);
+
+        // Saturates however long the task starves
+        for curve in [BoostCurve::Stepped, BoostCurve::Linear] {
+            sched.set_starvation_boost(curve, 6);
+            for starved in [threshold * 3, threshold * 100, u32::MAX] {
+                sched.tasks[id].payoff.ticks_since_last_run = starved;
+                assert_eq!(sched.starvation_boost(id), 6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_capped_starvation_boost_cannot_take_over() {
+        let mut sched = new_scheduler();
+        let low = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Cooperative).unwrap();
+        sched.tasks[low].payoff.ticks_since_last_run = u32::MAX / 2;
+
+        sched.set_starvation_boost(BoostCurve::Stepped, 4);
+        assert_eq!(sched.schedule(), high);
+
+        // A ceiling that reaches past the gap lets the starving task in
+        sched.set_starvation_boost(BoostCurve::Stepped, 10);
+        sched.tasks[low].payoff.ticks_since_last_run = u32::MAX / 2;
+        assert_eq!(sched.schedule(), low);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_preemptions_attributed_to_causer() {
+        let mut sched = new_scheduler();
+        let runner = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), high);
+
+        // The wake-up never lands on the runner's slice expiry, which
+        // would switch away from it anyway
+        for round in 1..=3 {
+            sched.delay_current(7);
+            assert_eq!(sched.schedule(), runner);
+            for _ in 0..7 {
+                step(&mut sched);
+            }
+            assert_eq!(sched.current(), high);
+            assert_eq!(sched.preemptions_caused(high), Some(round));
+        }
+        // Blocking hands the CPU over without preempting anyone
+        assert_eq!(sched.preemptions_caused(runner), Some(0));
+        assert_eq!(sched.preemptions_caused(MAX_TASKS), None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_isr_wake_switches_to_target() {
+        let mut sched = new_scheduler();
+        let runner = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let consumer = sched.create_task(TEST_ENTRY, make_config(7), Strategy::Cooperative).unwrap();
+        let other = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), consumer);
+        sched.delay_current(1000);
+        assert_eq!(sched.schedule(), other);
+        sched.delay_current(1000);
+        assert_eq!(sched.schedule(), runner);
+        step(&mut sched);
+
+        // The "ISR" picks the consumer out of two sleepers
+        sched.wake_and_switch(consumer).unwrap();
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.tasks[other].state, TaskState::Blocked);
+        assert_eq!(sched.schedule(), consumer);
+        assert_eq!(sched.tasks[consumer].wake_reason, WakeReason::Interrupt);
+        assert_eq!(sched.tasks[consumer].wake_tick, None);
+    }
+
+    #[test]
+    fn test_isr_wake_of_lower_priority_task_waits() {
+        let mut sched = new_scheduler();
+        let low = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(7), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), high);
+        sched.tasks[low].state = TaskState::Blocked;
+        sched.tasks[low].block_reason = Some(BlockReason::Deferral);
+
+        sched.wake_and_switch(low).unwrap();
+        assert_eq!(sched.tasks[low].state, TaskState::Ready);
+        assert!(!sched.needs_reschedule);
+
+        sched.tasks[low].state = TaskState::Suspended;
+        assert_eq!(sched.wake_and_switch(low), Err(KernelError::InvalidState));
+        assert_eq!(sched.wake_and_switch(MAX_TASKS), Err(KernelError::InvalidTaskId));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_budget_caps_cpu_bound_task() {
+        let mut sched = new_scheduler();
+        let budgeted = TaskConfig { budget_ticks: 3, budget_period: 10, ..make_config(5) };
+        let hog = sched.create_task(TEST_ENTRY, budgeted, Strategy::Selfish).unwrap();
+        let other = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), hog);
+
+        for period in 0..5 {
+            let mut ran = 0;
+            for _ in 0..10 {
+                if sched.current() == hog && sched.tasks[hog].state == TaskState::Running {
+                    ran += 1;
+                }
+                step(&mut sched);
+            }
+            assert!(ran <= 3, "period {}: ran {} ticks", period, ran);
+            assert!(ran > 0, "period {}: never ran", period);
+        }
+        assert_eq!(sched.tasks[hog].payoff.cpu_ticks_used, 15);
+        assert_eq!(sched.tasks[other].payoff.cpu_ticks_used, 35);
+        assert_eq!(sched.tasks[hog].payoff.timeouts, 0);
+    }
+
+    #[test]
+    fn test_budget_must_fit_its_period() {
+        let mut sched = new_scheduler();
+        let no_period = TaskConfig { budget_ticks: 3, ..make_config(5) };
+        let too_big = TaskConfig { budget_ticks: 11, budget_period: 10, ..make_config(5) };
+        let unlimited = TaskConfig { budget_period: 10, ..make_config(5) };
+        assert_eq!(sched.create_task(TEST_ENTRY, no_period, Strategy::Cooperative), Err(KernelError::InvalidConfig));
+        assert_eq!(sched.create_task(TEST_ENTRY, too_big, Strategy::Cooperative), Err(KernelError::InvalidConfig));
+        let id = sched.create_task(TEST_ENTRY, unlimited, Strategy::Cooperative).unwrap();
+
+        // A zero budget never blocks
+        sched.schedule();
+        for _ in 0..30 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[id].payoff.cpu_ticks_used, 30);
+    }
+
+    /// A priority-5 task with a 5-tick deadline that never finishes its
+    /// job, next to a priority-1 bystander, returning both ids. Tasks are
+    /// created in place: moving a scheduler moves their stacks.
+    fn start_overrunning_pair(sched: &mut Scheduler, enforce_deadline: bool) -> (usize, usize) {
+        let config = TaskConfig { deadline_ticks: 5, time_slice: 20, enforce_deadline, ..make_config(5) };
+        let hog = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        let other = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), hog);
+        (hog, other)
+    }
+
+    #[test]
+    fn test_enforced_deadline_holds_task_until_next_period() {
+        let mut sched = new_scheduler();
+        let (hog, other) = start_overrunning_pair(&mut sched, true);
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+
+        // Blocked at the miss; the bystander gets the CPU
+        assert_eq!(sched.tasks[hog].payoff.deadlines_missed, 1);
+        assert_eq!(sched.tasks[hog].state, TaskState::Blocked);
+        assert_eq!(sched.tasks[hog].block_reason, Some(BlockReason::DeadlineOverrun));
+        assert_eq!(sched.current(), other);
+        assert_eq!(sched.cancel_delay(hog), Err(KernelError::InvalidState));
+        for _ in 0..4 {
+            step(&mut sched);
+            assert_eq!(sched.tasks[hog].state, TaskState::Blocked);
+        }
+
+        // Released at the next period boundary, with a fresh period
+        step(&mut sched);
+        assert_ne!(sched.tasks[hog].state, TaskState::Blocked);
+        assert_eq!(sched.tasks[hog].period_ticks, 0);
+        assert_eq!(sched.tasks[hog].payoff.deadlines_missed, 1);
+        assert_eq!(sched.tasks[hog].payoff.timeouts, 0);
+        assert_eq!(sched.tasks[other].payoff.cpu_ticks_used, 5);
     }
 
-    tcb.stack_pointer = frame_ptr;
-}
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_unenforced_deadline_keeps_running() {
+        let mut sched = new_scheduler();
+        let (hog, other) = start_overrunning_pair(&mut sched, false);
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[hog].payoff.deadlines_missed, 1);
 
-/// Fallback for tasks that return (they shouldn't — entry is `fn() -> !`).
-/// Loops forever to prevent undefined behavior.
-extern "C" fn task_exit() -> ! {
-    loop {
-        cortex_m::asm::wfi();
+        // Never held: it keeps sharing the CPU with the bystander, missing
+        // each deadline it is still unfinished at
+        for _ in 0..10 {
+            assert_ne!(sched.tasks[hog].state, TaskState::Blocked);
+            assert_eq!(sched.tasks[hog].block_reason, None);
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[hog].payoff.deadlines_missed, 3);
+        assert_eq!(sched.tasks[hog].payoff.cpu_ticks_used, 10);
+        assert_eq!(sched.tasks[other].payoff.cpu_ticks_used, 5);
+    }
+
+    #[test]
+    fn test_starved_task_misses_deadline() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Selfish).unwrap();
+        let config = TaskConfig { deadline_ticks: 20, ..make_config(1) };
+        let starved = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), hog);
+
+        // Its period runs out on the release clock, not on its own CPU time
+        for _ in 0..20 {
+            step(&mut sched);
+        }
+        assert!(sched.tasks[starved].payoff.cpu_ticks_used < 20);
+        assert_eq!(sched.tasks[starved].payoff.deadlines_missed, 1);
+        assert_eq!(sched.next_deadline(starved), Some(40));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_switch_cause_starvation() {
+        let mut sched = new_scheduler();
+        let low = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Selfish).unwrap();
+        assert_eq!(sched.schedule(), high);
+
+        sched.tasks[low].payoff.ticks_since_last_run = 10 * STARVATION_THRESHOLD_MAX;
+        sched.evaluate_now();
+        assert_eq!(sched.schedule(), low);
+        assert_eq!(sched.last_switch_cause(), Some(SwitchCause::Starvation));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_switch_cause_deadline() {
+        let mut sched = new_scheduler();
+        start_round_robin_pair(&mut sched, TaskConfig { priority: 3, deadline_ticks: 5, ..TaskConfig::DEFAULT });
+        let first = sched.current();
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_ne!(sched.current(), first);
+        assert_eq!(sched.last_switch_cause(), Some(SwitchCause::Deadline));
+    }
+
+    #[test]
+    fn test_anchor_keeps_cpu_under_injected_overload() {
+        let mut sched = new_scheduler();
+        for _ in 0..3 {
+            sched.create_task(TEST_ENTRY, make_config(9), Strategy::Cooperative).unwrap();
+        }
+        let anchor = sched
+            .create_task(
+                TEST_ENTRY,
+                TaskConfig { priority: 1, anchor: true, ..TaskConfig::DEFAULT },
+                Strategy::Selfish,
+            )
+            .unwrap();
+
+        // Overload: every other task is starving and the anchor's payoff
+        // has collapsed
+        sched.metrics.overload = true;
+        for id in 0..anchor {
+            sched.tasks[id].payoff.ticks_since_last_run = 10 * STARVATION_THRESHOLD;
+        }
+        sched.tasks[anchor].payoff.payoff = -10_000;
+
+        assert_eq!(sched.schedule(), anchor);
+        for _ in 0..50 {
+            step(&mut sched);
+            assert_eq!(sched.current(), anchor);
+            assert!(sched.tasks[anchor].effective_priority() >= 1);
+        }
+        assert_eq!(sched.tasks[anchor].total_ticks, 50);
+
+        // Once the anchor blocks, the rest of the system runs
+        sched.delay_current(5);
+        assert_ne!(sched.schedule(), anchor);
+    }
+
+    #[test]
+    fn test_cooperation_trend_follows_ratio() {
+        let mut sched = new_scheduler();
+        for _ in 0..4 {
+            sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        }
+        let mut trend = [0u8; COOPERATION_TREND_LEN];
+        assert_eq!(sched.cooperation_trend(&mut trend), 0);
+
+        // Defect one more task each window
+        for selfish in 0..=4 {
+            for id in 0..4 {
+                sched.tasks[id].strategy =
+                    if id < selfish { Strategy::Selfish } else { Strategy::Cooperative };
+            }
+            sched.evaluate_game();
+        }
+        assert_eq!(sched.cooperation_trend(&mut trend), 5);
+        assert_eq!(trend[..5], [100, 75, 50, 25, 0]);
+
+        // A short buffer gets the most recent windows
+        let mut recent = [0u8; 2];
+        assert_eq!(sched.cooperation_trend(&mut recent), 2);
+        assert_eq!(recent, [25, 0]);
+
+        // Once full, the oldest windows are dropped
+        for id in 0..4 {
+            sched.tasks[id].strategy = Strategy::Cooperative;
+            sched.tasks[id].payoff.strategy_lock = u32::MAX;
+        }
+        for _ in 0..COOPERATION_TREND_LEN - 1 {
+            sched.evaluate_game();
+        }
+        assert_eq!(sched.cooperation_trend(&mut trend), COOPERATION_TREND_LEN);
+        assert_eq!(trend[0], 0);
+        assert!(trend[1..].iter().all(|&ratio| ratio == 100));
+
+        sched.reset_cooperation_trend();
+        assert_eq!(sched.cooperation_trend(&mut trend), 0);
+    }
+
+    #[test]
+    fn test_fairness_epoch_resets_accounting_keeps_strategies() {
+        let mut sched = new_scheduler();
+        for strategy in [Strategy::Cooperative, Strategy::Selfish, Strategy::Cooperative] {
+            sched.create_task(TEST_ENTRY, make_config(3), strategy).unwrap();
+        }
+        sched.schedule();
+        for _ in 0..95 {
+            step(&mut sched);
+        }
+        let strategies = [0, 1, 2].map(|id| sched.tasks[id].strategy);
+        let payoffs = [0, 1, 2].map(|id| sched.tasks[id].payoff.payoff);
+        assert!(sched.tasks[..3].iter().any(|t| t.payoff.cpu_ticks_used > 0));
+
+        sched.begin_fairness_epoch(false);
+        for id in 0..3 {
+            assert_eq!(sched.tasks[id].payoff.cpu_ticks_used, 0);
+            assert_eq!(sched.tasks[id].payoff.cpu_cycles_used, 0);
+            assert_eq!(sched.tasks[id].strategy, strategies[id]);
+            assert_eq!(sched.tasks[id].payoff.payoff, payoffs[id]);
+        }
+        assert_eq!(sched.metrics.total_ticks, 0);
+
+        // The fair-share baseline counts from the boundary
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.evaluations, 10);
+        assert_eq!(sched.metrics.total_ticks, 5);
+
+        let strategies = [0, 1, 2].map(|id| sched.tasks[id].strategy);
+        sched.tasks[1].payoff.cooperation_score = 7;
+        sched.begin_fairness_epoch(true);
+        for (tcb, strategy) in sched.tasks[..3].iter().zip(strategies) {
+            assert_eq!(tcb.payoff.payoff, 0);
+            assert_eq!(tcb.payoff.cooperation_score, 100);
+            assert_eq!(tcb.strategy, strategy);
+        }
+    }
+
+    #[test]
+    fn test_dense_evaluation_runs_every_tick_then_reverts() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.schedule();
+        for _ in 0..3 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.evaluations, 0);
+
+        sched.request_dense_evaluation(2);
+        for t in 1..=2 * EVAL_FREQUENCY as u64 {
+            step(&mut sched);
+            assert_eq!(sched.evaluations, t);
+        }
+        assert_eq!(sched.dense_eval_ticks, 0);
+
+        // Back to one evaluation per window (ticks 30 and 40)
+        let before = sched.evaluations;
+        for _ in 0..2 * EVAL_FREQUENCY {
+            step(&mut sched);
+        }
+        assert_eq!(sched.evaluations, before + 2);
+
+        // A shorter request does not cut an ongoing burst
+        sched.request_dense_evaluation(3);
+        sched.request_dense_evaluation(1);
+        assert_eq!(sched.dense_eval_ticks, 3 * EVAL_FREQUENCY);
+    }
+
+    #[test]
+    fn test_dense_evaluation_keeps_window_history() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.schedule();
+        for _ in 0..3 {
+            step(&mut sched);
+        }
+
+        sched.request_dense_evaluation(1);
+        while sched.tick_count < EVAL_FREQUENCY as u64 - 1 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.evaluations, EVAL_FREQUENCY as u64 - 4);
+
+        // Payoffs are fresh, but the window is neither closed nor decayed
+        let payoff = game::compute_payoff_with(
+            &sched.tasks[id],
+            &sched.metrics,
+            &sched.payoff_config,
+            sched.fair_share_policy,
+        );
+        assert_eq!(sched.tasks[id].payoff.payoff, payoff);
+        assert_eq!(sched.tasks[id].window_ticks_used, EVAL_FREQUENCY - 1);
+        assert_eq!(sched.tasks[id].recent_ticks_used, EVAL_FREQUENCY - 1);
+        assert_eq!(sched.cooperation_trend_len, 0);
+
+        // The boundary closes it as usual
+        step(&mut sched);
+        assert_eq!(sched.tasks[id].window_ticks_used, 0);
+        assert_eq!(sched.cooperation_trend_len, 1);
+    }
+
+    #[test]
+    fn test_set_timing_restarts_period_without_false_miss() {
+        let mut sched = new_scheduler();
+        let config = TaskConfig { priority: 3, deadline_ticks: 20, wcet_ticks: 2, ..TaskConfig::DEFAULT };
+        let id = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.schedule();
+
+        // Slow the loop down mid-period, with the job still outstanding
+        for _ in 0..15 {
+            step(&mut sched);
+        }
+        sched.set_timing(id, 50, 0, 5).unwrap();
+        assert_eq!(sched.tasks[id].config.deadline_ticks, 50);
+        assert_eq!(sched.next_deadline(id), Some(65));
+
+        // The old deadline (tick 20) passes without a miss; the new one
+        // is checked at tick 65
+        for _ in 0..49 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 0);
+        step(&mut sched);
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 1);
+
+        // The late job finishes, taking the period it ran into; releases
+        // follow the new period
+        sched.job_complete();
+        for _ in 0..60 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 1);
+        sched.job_complete();
+        assert_eq!(sched.tasks[id].payoff.deadlines_met, 1);
+        assert_eq!(sched.next_deadline(id), Some(165));
+    }
+
+    #[test]
+    fn test_set_timing_rejects_overload() {
+        let mut sched = new_scheduler();
+        let config = TaskConfig { priority: 3, deadline_ticks: 20, wcet_ticks: 2, ..TaskConfig::DEFAULT };
+        let id = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        let busy = TaskConfig { priority: 3, deadline_ticks: 10, wcet_ticks: 9, ..TaskConfig::DEFAULT };
+        sched.create_task(TEST_ENTRY, busy, Strategy::Cooperative).unwrap();
+        assert_eq!(sched.set_timing(id, 10, 0, 2), Err(KernelError::Unschedulable));
+        assert_eq!(sched.tasks[id].config.deadline_ticks, 20);
+        assert_eq!(sched.tasks[id].config.wcet_ticks, 2);
+        assert_eq!(sched.set_timing(MAX_TASKS, 10, 0, 1), Err(KernelError::InvalidTaskId));
+    }
+
+    #[test]
+    fn test_set_timing_rejects_invalid_config() {
+        let mut sched = new_scheduler();
+        let config = TaskConfig { priority: 3, deadline_ticks: 20, wcet_ticks: 2, ..TaskConfig::DEFAULT };
+        let id = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+
+        // A period apart from the deadline has nowhere to go
+        assert_eq!(sched.set_timing(id, 50, 40, 2), Err(KernelError::InvalidConfig));
+        assert_eq!(sched.set_timing(id, 0, 0, 0), Err(KernelError::InvalidConfig));
+        assert_eq!(sched.set_timing(id, 0, 10, 11), Err(KernelError::InvalidConfig));
+        assert_eq!(sched.tasks[id].config.deadline_ticks, 20);
+        assert_eq!(sched.tasks[id].config.wcet_ticks, 2);
+
+        assert_eq!(sched.set_timing(id, 40, 40, 2), Ok(()));
+        assert_eq!(sched.tasks[id].config.deadline_ticks, 40);
+    }
+
+    #[test]
+    fn test_set_timing_releases_held_task() {
+        let mut sched = new_scheduler();
+        let (hog, _) = start_overrunning_pair(&mut sched, true);
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[hog].block_reason, Some(BlockReason::DeadlineOverrun));
+
+        sched.set_timing(hog, 10, 0, 0).unwrap();
+        assert_eq!(sched.tasks[hog].state, TaskState::Ready);
+        assert_eq!(sched.tasks[hog].block_reason, None);
+        assert_eq!(sched.tasks[hog].wake_tick, None);
+        assert_eq!(sched.next_deadline(hog), Some(15));
+    }
+
+    #[test]
+    fn test_next_deadline_advances_each_period() {
+        let mut sched = new_scheduler();
+        let config = TaskConfig { priority: 3, deadline_ticks: 20, ..TaskConfig::DEFAULT };
+        let id = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.schedule();
+        assert_eq!(sched.next_deadline(id), Some(20));
+
+        for _ in 0..7 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.next_deadline(id), Some(20));
+
+        for period in 2..5u64 {
+            for _ in 0..20 {
+                step(&mut sched);
+            }
+            assert_eq!(sched.next_deadline(id), Some(period * 20));
+        }
+
+        let aperiodic = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.next_deadline(aperiodic), None);
+        assert_eq!(sched.next_deadline(MAX_TASKS), None);
+    }
+
+    #[test]
+    fn test_next_deadline_holds_while_ready() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Selfish).unwrap();
+        let config = TaskConfig { deadline_ticks: 20, ..make_config(1) };
+        let waiting = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), hog);
+
+        // Waiting for the CPU does not push the deadline out
+        for _ in 0..10 {
+            step(&mut sched);
+            assert_eq!(sched.tasks[waiting].state, TaskState::Ready);
+            assert_eq!(sched.next_deadline(waiting), Some(20));
+        }
+    }
+
+    #[test]
+    fn test_earliest_hard_deadline_picks_outstanding_hard_job() {
+        let mut sched = new_scheduler();
+        let hard = |deadline_ticks| TaskConfig {
+            priority: 3,
+            deadline_ticks,
+            hard_deadline: true,
+            ..TaskConfig::DEFAULT
+        };
+        let slow = sched.create_task(TEST_ENTRY, hard(30), Strategy::Cooperative).unwrap();
+        let fast = sched.create_task(TEST_ENTRY, hard(20), Strategy::Cooperative).unwrap();
+        // Soft deadlines never drive the compare value
+        let soft = TaskConfig { priority: 3, deadline_ticks: 5, ..TaskConfig::DEFAULT };
+        sched.create_task(TEST_ENTRY, soft, Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.earliest_hard_deadline(), Some((fast, 20)));
+
+        // A completed job drops out until its next period
+        sched.current_task[0] = fast;
+        sched.job_complete();
+        assert_eq!(sched.earliest_hard_deadline(), Some((slow, 30)));
+
+        // Ties go to the lowest id
+        sched.tasks[fast].job_done = false;
+        sched.tasks[slow].deadline_tick = 20;
+        assert_eq!(sched.earliest_hard_deadline(), Some((slow, 20)));
+
+        sched.tasks[slow].config.hard_deadline = false;
+        sched.tasks[fast].config.hard_deadline = false;
+        assert_eq!(sched.earliest_hard_deadline(), None);
+    }
+
+    #[test]
+    fn test_enforce_hard_deadlines_records_miss_once() {
+        let mut sched = new_scheduler();
+        let config = TaskConfig {
+            priority: 3,
+            deadline_ticks: 20,
+            hard_deadline: true,
+            ..TaskConfig::DEFAULT
+        };
+        let id = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.schedule();
+
+        for _ in 0..19 {
+            step(&mut sched);
+        }
+        // Not due yet
+        assert_eq!(sched.enforce_hard_deadlines(19), 0);
+
+        // The compare fires at the deadline instant, before tick 20
+        assert_eq!(sched.enforce_hard_deadlines(20), 1);
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 1);
+        assert!(sched.tasks[id].job_late);
+        assert_eq!(sched.earliest_hard_deadline(), None);
+
+        // The tick closing the period does not count it again
+        step(&mut sched);
+        assert_eq!(sched.tasks[id].payoff.deadlines_missed, 1);
+        assert_eq!(sched.earliest_hard_deadline(), Some((id, 40)));
+    }
+
+    #[test]
+    fn test_hard_deadline_holds_while_preempted() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(9), Strategy::Selfish).unwrap();
+        let config = TaskConfig {
+            deadline_ticks: 20,
+            hard_deadline: true,
+            ..make_config(1)
+        };
+        let hard = sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), hog);
+
+        // The compare value stays put while the hog has the CPU
+        for _ in 0..19 {
+            step(&mut sched);
+            assert_eq!(sched.earliest_hard_deadline(), Some((hard, 20)));
+        }
+        assert_eq!(sched.tasks[hard].state, TaskState::Ready);
+        assert_eq!(sched.enforce_hard_deadlines(20), 1);
+        assert_eq!(sched.tasks[hard].payoff.deadlines_missed, 1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_deferral_wakes_handler_and_preempts() {
+        let mut sched = new_scheduler();
+        let worker = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let handler = sched.create_task(TEST_ENTRY, make_config(7), Strategy::Cooperative).unwrap();
+
+        // Handler runs first and waits for work
+        assert_eq!(sched.schedule(), handler);
+        assert!(!sched.wait_for_deferral(None));
+        assert_eq!(sched.schedule(), worker);
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.current(), worker);
+
+        // The "ISR" defers: handler is woken and preempts the worker
+        sched.defer_to(handler).unwrap();
+        assert_eq!(sched.tasks[handler].state, TaskState::Ready);
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.schedule(), handler);
+        assert_eq!(sched.tasks[handler].deferrals_pending, 0);
+
+        // Deferrals arriving while it is busy are queued, not lost
+        sched.defer_to(handler).unwrap();
+        sched.defer_to(handler).unwrap();
+        assert!(sched.wait_for_deferral(None));
+        assert!(sched.wait_for_deferral(None));
+        assert!(!sched.wait_for_deferral(None));
+
+        assert_eq!(sched.defer_to(MAX_TASKS), Err(KernelError::InvalidTaskId));
+    }
+
+    #[test]
+    fn test_yield_to_overrides_priority_once() {
+        let mut sched = new_scheduler();
+        let producer = sched.create_task(TEST_ENTRY, make_config(7), Strategy::Cooperative).unwrap();
+        let high = sched.create_task(TEST_ENTRY, make_config(6), Strategy::Cooperative).unwrap();
+        let worker = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), producer);
+
+        // The low-priority worker runs next despite `high`
+        sched.yield_to(worker).unwrap();
+        assert_eq!(sched.forced_next, Some(worker));
+        assert_eq!(sched.schedule(), worker);
+        assert_eq!(sched.forced_next, None);
+        assert_eq!(sched.tasks[producer].payoff.voluntary_yields, 1);
+
+        // Cleared afterwards: the next switch is by priority again
+        sched.yield_current();
+        assert_eq!(sched.schedule(), producer);
+
+        // A target that cannot run falls back to normal selection
+        sched.tasks[worker].state = TaskState::Suspended;
+        sched.yield_to(worker).unwrap();
+        assert_eq!(sched.schedule(), producer);
+        assert_eq!(sched.forced_next, None);
+        assert_eq!(sched.yield_to(MAX_TASKS), Err(KernelError::InvalidTaskId));
+        assert_eq!(sched.tasks[producer].payoff.voluntary_yields, 2);
+        assert_ne!(sched.current(), high);
+    }
+
+    #[test]
+    fn test_notify_before_wait_does_not_block() {
+        let mut sched = new_scheduler();
+        let rx = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), rx);
+
+        // Both "interrupts" land before the task waits: taken in one go
+        sched.notify(rx).unwrap();
+        sched.notify(rx).unwrap();
+        assert!(!sched.needs_reschedule);
+        assert_eq!(sched.wait_notification(None), Some(2));
+        assert_eq!(sched.tasks[rx].state, TaskState::Running);
+
+        // Taking clears it, so the next wait blocks
+        assert_eq!(sched.wait_notification(None), None);
+        assert_eq!(sched.tasks[rx].state, TaskState::Blocked);
+        assert_eq!(sched.notify(MAX_TASKS), Err(KernelError::InvalidTaskId));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_wait_before_notify_blocks_until_woken() {
+        let mut sched = new_scheduler();
+        let worker = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let rx = sched.create_task(TEST_ENTRY, make_config(7), Strategy::Cooperative).unwrap();
+
+        assert_eq!(sched.schedule(), rx);
+        assert_eq!(sched.wait_notification(None), None);
+        assert_eq!(sched.schedule(), worker);
+        for _ in 0..20 {
+            step(&mut sched);
+        }
+        // No timeout: still waiting
+        assert_eq!(sched.tasks[rx].state, TaskState::Blocked);
+        assert_eq!(sched.current(), worker);
+
+        // The "ISR" notifies: rx wakes, preempts, and takes the value
+        sched.notify(rx).unwrap();
+        assert_eq!(sched.tasks[rx].state, TaskState::Ready);
+        assert!(sched.needs_reschedule);
+        assert_eq!(sched.schedule(), rx);
+        assert_eq!(sched.tasks[rx].wake_reason, WakeReason::Signalled);
+        assert_eq!(sched.wait_notification(None), Some(1));
+        assert!(!sched.tasks[rx].notify_pending);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_notification_wait_times_out_exactly() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let rx = sched.create_task(TEST_ENTRY, make_config(7), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), rx);
+
+        assert_eq!(sched.wait_notification(Some(5)), None);
+        sched.schedule();
+        for _ in 0..4 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[rx].state, TaskState::Blocked);
+        step(&mut sched);
+        assert_eq!(sched.current(), rx);
+        assert_eq!(sched.tasks[rx].wake_reason, WakeReason::Timeout);
+        assert_eq!(sched.timeout_count(rx), Some(1));
+
+        // Notified first: no timeout, and the stale one never fires
+        assert_eq!(sched.wait_notification(Some(5)), None);
+        sched.schedule();
+        step(&mut sched);
+        sched.notify(rx).unwrap();
+        assert_eq!(sched.schedule(), rx);
+        assert_eq!(sched.tasks[rx].wake_reason, WakeReason::Signalled);
+        assert_eq!(sched.wait_notification(Some(5)), Some(1));
+        for _ in 0..10 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.timeout_count(rx), Some(1));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_deferral_wait_times_out_exactly() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let handler = sched.create_task(TEST_ENTRY, make_config(7), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), handler);
+
+        assert!(!sched.wait_for_deferral(Some(5)));
+        sched.schedule();
+        for _ in 0..4 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[handler].state, TaskState::Blocked);
+        step(&mut sched);
+        assert_eq!(sched.current(), handler);
+        assert_eq!(sched.tasks[handler].wake_reason, WakeReason::Timeout);
+        assert_eq!(sched.timeout_count(handler), Some(1));
+
+        // A plain delay running out is not a timeout
+        sched.delay_current(3);
+        for _ in 0..3 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.current(), handler);
+        assert_eq!(sched.timeout_count(handler), Some(1));
+        assert_eq!(sched.timeout_count(MAX_TASKS), None);
+    }
+
+    #[test]
+    fn test_deferral_wait_signalled_before_timeout() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        let handler = sched.create_task(TEST_ENTRY, make_config(7), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), handler);
+
+        assert!(!sched.wait_for_deferral(Some(5)));
+        sched.schedule();
+        step(&mut sched);
+        step(&mut sched);
+        sched.defer_to(handler).unwrap();
+        assert_eq!(sched.tasks[handler].wake_reason, WakeReason::Signalled);
+        assert_eq!(sched.tasks[handler].wake_tick, None);
+
+        // The stale timeout never fires
+        for _ in 0..10 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.timeout_count(handler), Some(0));
+    }
+
+    #[test]
+    fn test_stack_check_levels() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let sp = sched.tasks[id].stack_pointer as *const u32;
+        let outside = unsafe { sched.tasks[id].stack_base().add(STACK_SIZE + 64) } as *const u32;
+
+        // None trusts everything
+        sched.set_stack_check(StackCheck::None);
+        assert_eq!(sched.validate_stack(id, outside), Ok(()));
+
+        // BoundsCheck catches a stray SP but not a clobbered canary
+        sched.set_stack_check(StackCheck::BoundsCheck);
+        assert_eq!(sched.validate_stack(id, sp), Ok(()));
+        assert_eq!(sched.validate_stack(id, outside), Err(StackFault::OutOfBounds));
+        unsafe { *sched.tasks[id].stack_base_mut() ^= 0xFF };
+        assert_eq!(sched.validate_stack(id, sp), Ok(()));
+
+        // CanaryCheck catches the overflow even with a plausible SP
+        sched.set_stack_check(StackCheck::CanaryCheck);
+        assert_eq!(sched.validate_stack(id, sp), Err(StackFault::CanaryCorrupted));
+        unsafe { *sched.tasks[id].stack_base_mut() ^= 0xFF };
+        assert_eq!(sched.validate_stack(id, sp), Ok(()));
+    }
+
+    fn panic_stack_overflow(task: usize, fault: StackFault) -> ! {
+        panic!("overflow in task {}: {:?}", task, fault);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in task 1: CanaryCorrupted")]
+    fn test_clobbered_canary_runs_overflow_hook() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.set_stack_check(StackCheck::CanaryCheck);
+        sched.set_stack_overflow_hook(panic_stack_overflow);
+        let sp = sched.tasks[id].stack_pointer as *const u32;
+
+        sched.enforce_stack(id, sp);
+        unsafe { *sched.tasks[id].stack_base_mut() ^= 0xFF };
+        sched.enforce_stack(id, sp);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "sim", ignore = "no exception frame under sim")]
+    fn test_initial_frame_layout() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let sp = sched.tasks[id].stack_pointer;
+        let top = sched.tasks[id].stack_base() as usize + sched.tasks[id].stack_size();
+
+        // Software context, then the 8-byte aligned hardware frame ending
+        // at the stack top
+        let hw = unsafe { sp.add(SW_FRAME_WORDS) };
+        assert_eq!(hw as usize % 8, 0);
+        assert_eq!(hw as usize + 8 * 4, top & !0x07);
+        assert_eq!(unsafe { *hw.add(6) }, TEST_ENTRY.address() as u32);
+        assert_eq!(unsafe { *hw.add(7) }, 0x0100_0000);
+        #[cfg(feature = "fpu")]
+        assert_eq!(unsafe { *sp.add(8) }, EXC_RETURN_THREAD_PSP);
+    }
+
+    #[test]
+    #[cfg(feature = "stack-pool")]
+    fn test_stacks_carved_from_pool() {
+        let region = leaked_region(2 * STACK_SIZE + 8);
+        let (start, end) = (region.as_ptr() as usize, region.as_ptr() as usize + region.len());
+
+        let mut sched = Scheduler::new();
+        sched.set_stack_pool(region);
+        let a = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let b = sched.create_task(TEST_ENTRY, make_config(2), Strategy::Cooperative).unwrap();
+        assert_eq!(
+            sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative),
+            Err(KernelError::StackPoolExhausted)
+        );
+
+        let (a_base, b_base) = (sched.tasks[a].stack_base() as usize, sched.tasks[b].stack_base() as usize);
+        assert_eq!(a_base % 8, 0);
+        assert_eq!(b_base % 8, 0);
+        assert!(a_base + sched.tasks[a].stack_size() <= b_base);
+        assert!(a_base >= start && b_base + sched.tasks[b].stack_size() <= end);
+
+        // The initial frame lies inside the task's own stack
+        let sp = sched.tasks[b].stack_pointer as usize;
+        assert!(sp > b_base && sp < b_base + sched.tasks[b].stack_size());
+    }
+
+    static QUANTUM_EXPIRED: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+    fn record_quantum(_id: usize) {
+        QUANTUM_EXPIRED.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_quantum_hook_fires_on_expiry_only() {
+        use core::sync::atomic::Ordering;
+
+        let mut sched = new_scheduler();
+        let config = TaskConfig { priority: 3, time_slice: 4, ..TaskConfig::DEFAULT };
+        sched.create_task(TEST_ENTRY, config, Strategy::Cooperative).unwrap();
+        sched.set_quantum_hook(Some(record_quantum));
+        sched.schedule();
+
+        // Yielding before the slice runs out never fires the hook
+        for _ in 0..5 {
+            step(&mut sched);
+            step(&mut sched);
+            sched.yield_current();
+            sched.schedule();
+        }
+        assert_eq!(QUANTUM_EXPIRED.load(Ordering::SeqCst), 0);
+
+        // Running the full quantum does
+        for _ in 0..4 {
+            step(&mut sched);
+        }
+        assert_eq!(QUANTUM_EXPIRED.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_equilibrium_reported_once_stable() {
+        let mut sched = new_scheduler();
+        let a = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.schedule();
+        assert!(!sched.last_equilibrium);
+
+        // Two well-behaved tasks taking turns every other tick
+        for _ in 0..100 {
+            step(&mut sched);
+            step(&mut sched);
+            sched.yield_current();
+            sched.schedule();
+        }
+        assert!(sched.last_equilibrium);
+        let settled = sched.evaluations_since_change;
+        assert_eq!(settled as u64, sched.evaluations);
+
+        for _ in 0..EVAL_FREQUENCY / 2 {
+            step(&mut sched);
+            step(&mut sched);
+            sched.yield_current();
+            sched.schedule();
+        }
+        let stats = sched.stats();
+        assert!(stats.last_equilibrium);
+        assert_eq!(stats.evaluations_since_change, settled + 1);
+        assert_eq!(stats.tasks[a].unwrap().strategy, Strategy::Cooperative);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cycle-accounting", ignore = "no cycle counter on the host")]
+    fn test_strategy_switch_restarts_stability_count() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(sched.schedule(), hog);
+        sched.evaluate_now();
+        sched.evaluate_now();
+        assert_eq!(sched.evaluations_since_change, 2);
+
+        // A silent hog is reclassified at the end of the window
+        sched.preempt_disable();
+        for _ in 0..EVAL_FREQUENCY {
+            sched.tick();
+        }
+        assert_eq!(sched.tasks[hog].strategy, Strategy::Selfish);
+        assert_eq!(sched.evaluations_since_change, 0);
+    }
+
+    extern crate std;
+    static SWITCH_TRACE: std::sync::Mutex<std::vec::Vec<(usize, usize, u64)>> =
+        std::sync::Mutex::new(std::vec::Vec::new());
+
+    fn record_switch(from: usize, to: usize, at_tick: u64) {
+        SWITCH_TRACE.lock().unwrap().push((from, to, at_tick));
+    }
+
+    #[test]
+    fn test_switch_hook_traces_each_switch() {
+        let mut sched = new_scheduler();
+        let a = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        let b = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.set_switch_hook(record_switch);
+        assert_eq!(sched.context_switch(0), a);
+
+        // Yield back and forth, a few ticks apart
+        for _ in 0..3 {
+            sched.tick();
+            sched.tick();
+            sched.yield_current();
+            sched.context_switch(0);
+        }
+
+        let trace = SWITCH_TRACE.lock().unwrap();
+        assert_eq!(*trace, [(a, b, 2), (b, a, 4), (a, b, 6)]);
+        assert_eq!(sched.context_switches, 3);
+    }
+
+    static RECLASSIFIED: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(usize::MAX);
+
+    fn record_reclassify(id: usize, strategy: Strategy) {
+        assert_eq!(strategy, Strategy::Selfish);
+        RECLASSIFIED.store(id, core::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cycle-accounting", ignore = "no cycle counter on the host")]
+    fn test_reclassify_hook_reports_silent_hog() {
+        use core::sync::atomic::Ordering;
+
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        sched.set_reclassify_hook(Some(record_reclassify));
+        assert_eq!(sched.schedule(), hog);
+
+        // The hog holds the CPU for a whole window without yielding
+        sched.preempt_disable();
+        for _ in 0..EVAL_FREQUENCY {
+            sched.tick();
+        }
+        assert_eq!(RECLASSIFIED.load(Ordering::SeqCst), hog);
+        assert_eq!(sched.tasks[hog].strategy, Strategy::Selfish);
+        assert_eq!(sched.reclassifications(hog), Some(1));
+        assert_eq!(sched.reclassifications(MAX_TASKS), None);
+    }
+
+    #[test]
+    fn test_payoff_declines_after_task_stops_yielding() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        sched.schedule();
+
+        // A long cooperative history: a yield every tick
+        for _ in 0..20 * EVAL_FREQUENCY {
+            sched.yield_current();
+            step(&mut sched);
+        }
+        let cooperative = sched.tasks[id].payoff.payoff;
+        let lifetime_yields = sched.tasks[id].payoff.voluntary_yields;
+
+        // It stops yielding; each evaluation scores it lower
+        let mut previous = cooperative;
+        for _ in 0..5 {
+            for _ in 0..EVAL_FREQUENCY {
+                step(&mut sched);
+            }
+            let payoff = sched.tasks[id].payoff.payoff;
+            assert!(payoff < previous, "payoff {} did not drop below {}", payoff, previous);
+            previous = payoff;
+        }
+        assert!(previous < cooperative / 4);
+        assert_eq!(sched.tasks[id].payoff.voluntary_yields, lifetime_yields);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cycle-accounting", ignore = "no cycle counter on the host")]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_fairness_penalty_recovers_once_hog_shares() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(6), Strategy::Selfish).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        // Score the fairness term alone
+        sched.set_payoff_config(PayoffConfig {
+            deadline_met: 0,
+            deadline_missed: 0,
+            voluntary_yield: 0,
+            overrun: 0,
+            fairness_penalty: 2,
+            modesty_bonus: 0,
+            cooperation_multiplier: 100,
+            defection_penalty: 0,
+        });
+        let fairness = |sched: &Scheduler| {
+            let payoff = &sched.tasks[hog].payoff;
+            payoff.payoff - payoff.cooperation_score / 2
+        };
+        assert_eq!(sched.schedule(), hog);
+
+        // Window 1: the hog keeps the CPU, 10 ticks against a share of 3
+        for _ in 0..EVAL_FREQUENCY {
+            step(&mut sched);
+        }
+        assert_eq!(fairness(&sched), -(333 - 200) * 2);
+
+        // From then on it runs its share, then sleeps while the others
+        // run. Window 1 fades instead of weighing on it for good.
+        let mut penalties = [0; 2];
+        for penalty in penalties.iter_mut() {
+            for _ in 0..3 {
+                assert_eq!(sched.current(), hog);
+                step(&mut sched);
+            }
+            sched.delay_current(6);
+            sched.schedule();
+            for _ in 3..EVAL_FREQUENCY {
+                step(&mut sched);
+            }
+            *penalty = fairness(&sched);
+        }
+        assert!(penalties[0] > -(333 - 200) * 2 && penalties[0] < 0);
+        assert_eq!(penalties[1], 0);
+    }
+
+    static RESPONSE_ALARMS: core::sync::atomic::AtomicU32 = core::sync::atomic::AtomicU32::new(0);
+
+    fn record_response_alarm(_id: usize, response: u32) {
+        RESPONSE_ALARMS.store(response, core::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_response_alarm_fires_only_above_threshold() {
+        use core::sync::atomic::Ordering;
+
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        sched.set_response_alarm_hook(Some(record_response_alarm));
+        sched.set_response_threshold(id, 5).unwrap();
+        sched.schedule();
+        assert_eq!(sched.event_handled(), None);
+
+        // Fast response: 3 ticks
+        sched.event_arrived(id).unwrap();
+        for _ in 0..3 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.event_handled(), Some(3));
+        assert_eq!(RESPONSE_ALARMS.load(Ordering::SeqCst), 0);
+
+        // Exactly at the threshold is still on time
+        sched.event_arrived(id).unwrap();
+        for _ in 0..5 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.event_handled(), Some(5));
+        assert_eq!(RESPONSE_ALARMS.load(Ordering::SeqCst), 0);
+
+        // Slow response: a second arrival does not reset the clock
+        sched.event_arrived(id).unwrap();
+        for _ in 0..4 {
+            step(&mut sched);
+        }
+        sched.event_arrived(id).unwrap();
+        for _ in 0..4 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.event_handled(), Some(8));
+        assert_eq!(RESPONSE_ALARMS.load(Ordering::SeqCst), 8);
+        assert_eq!(sched.response_times(id), Some((8, 8)));
+
+        sched.event_arrived(id).unwrap();
+        step(&mut sched);
+        sched.event_handled();
+        assert_eq!(sched.response_times(id), Some((1, 8)));
+        assert_eq!(sched.event_arrived(MAX_TASKS), Err(KernelError::InvalidTaskId));
+    }
+
+    #[test]
+    fn test_waiters_on_reason() {
+        let mut sched = new_scheduler();
+        let producer = sched.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        let sleeper_a = sched.create_task(TEST_ENTRY, make_config(6), Strategy::Cooperative).unwrap();
+        let consumer = sched.create_task(TEST_ENTRY, make_config(4), Strategy::Cooperative).unwrap();
+        let sleeper_b = sched.create_task(TEST_ENTRY, make_config(5), Strategy::Cooperative).unwrap();
+        sched.set_predecessor(consumer, Some(producer)).unwrap();
+
+        // Both sleepers delay themselves
+        assert_eq!(sched.schedule(), sleeper_a);
+        sched.delay_current(100);
+        assert_eq!(sched.schedule(), sleeper_b);
+        sched.delay_current(100);
+        assert_eq!(sched.schedule(), producer);
+
+        let mut out = [usize::MAX; MAX_TASKS];
+        assert_eq!(sched.waiters_on(BlockReason::Delay, &mut out), 2);
+        assert_eq!(&out[..2], &[sleeper_a, sleeper_b]);
+        assert_eq!(sched.waiters_on(BlockReason::Predecessor, &mut out), 1);
+        assert_eq!(out[0], consumer);
+
+        // Truncated to the buffer
+        let mut one = [usize::MAX; 1];
+        assert_eq!(sched.waiters_on(BlockReason::Delay, &mut one), 1);
+        assert_eq!(one[0], sleeper_a);
+
+        sched.cancel_delay(sleeper_a).unwrap();
+        assert_eq!(sched.waiters_on(BlockReason::Delay, &mut out), 1);
+        assert_eq!(out[0], sleeper_b);
+    }
+
+    #[test]
+    fn test_non_yielding_task_flagged() {
+        let mut sched = new_scheduler();
+        let hog = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Selfish).unwrap();
+        sched.schedule();
+
+        for _ in 0..NON_YIELD_THRESHOLD {
+            step(&mut sched);
+        }
+        let mut out = [0usize; MAX_TASKS];
+        assert_eq!(sched.non_yielding_tasks(&mut out), 0);
+
+        step(&mut sched);
+        assert_eq!(sched.non_yielding_tasks(&mut out), 1);
+        assert_eq!(out[0], hog);
+    }
+
+    #[test]
+    fn test_yielding_task_never_flagged() {
+        let mut sched = new_scheduler();
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.schedule();
+
+        let mut out = [0usize; MAX_TASKS];
+        for t in 0..(NON_YIELD_THRESHOLD * 3) {
+            step(&mut sched);
+            if t % 5 == 0 {
+                sched.yield_current();
+                sched.schedule();
+            }
+            assert_eq!(sched.non_yielding_tasks(&mut out), 0);
+        }
+    }
+
+    #[test]
+    fn test_delaying_task_never_flagged() {
+        let mut sched = new_scheduler();
+        let id = sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.schedule();
+
+        // Never yields, but sleeps between bursts of work
+        let mut out = [0usize; MAX_TASKS];
+        while sched.tick_count < 3 * NON_YIELD_THRESHOLD as u64 {
+            for _ in 0..3 {
+                step(&mut sched);
+            }
+            sched.delay_current(2);
+            sched.schedule();
+            while sched.current() != id {
+                step(&mut sched);
+            }
+            assert_eq!(sched.non_yielding_tasks(&mut out), 0);
+        }
+        assert_eq!(sched.tasks[id].payoff.voluntary_yields, 0);
     }
 }
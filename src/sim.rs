@@ -0,0 +1,319 @@
+//! # Host Simulation
+//!
+//! Drives a [`Scheduler`] on the host with synthetic task behaviours in
+//! place of task code, to experiment with payoff tuning and check that
+//! the game converges before anything is flashed. Available in tests and
+//! with the `sim` feature; nothing here touches Cortex-M hardware, and
+//! under `sim` task stacks get no exception frame.
+//!
+//! Each tick the running task's behaviour decides what it does with that
+//! tick ([`Action`]); the simulator then ticks the scheduler, reschedules
+//! if asked, and hands a [`TickRecord`] of the outcome to an observer.
+//!
+//! ```ignore
+//! let mut hog = sim::cpu_bound();
+//! let mut sensor = sim::periodic(5);
+//! let mut sim = Simulator::new();
+//! sim.add_task(hog_config, Strategy::Selfish, &mut hog)?;
+//! sim.add_task(sensor_config, Strategy::Cooperative, &mut sensor)?;
+//! sim.run(10_000, |record| println!("{:?}", record.ran));
+//! assert!(sim.scheduler().in_equilibrium);
+//! ```
+
+use crate::config::MAX_TASKS;
+use crate::error::KernelError;
+use crate::scheduler::Scheduler;
+use crate::task::{Strategy, TaskConfig, TaskEntry, TaskState};
+
+/// What a running task does with the current tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Keep working.
+    Run,
+    /// Give up the CPU (`yield_current()`).
+    Yield,
+    /// Finish this period's job (`job_complete()`) and sleep until the
+    /// next release; tasks without a deadline just yield.
+    Finish,
+    /// Block for this many ticks (`delay_current()`).
+    Sleep(u32),
+}
+
+/// What a behaviour sees when asked for its next action.
+#[derive(Debug, Clone, Copy)]
+pub struct TaskTick {
+    /// Scheduler tick about to be taken.
+    pub tick: u64,
+    /// Ticks worked since the task last yielded, finished or slept.
+    pub work: u32,
+}
+
+/// Synthetic behaviour of one task.
+pub type Behavior<'a> = &'a mut dyn FnMut(&TaskTick) -> Action;
+
+/// Outcome of one simulated tick.
+#[derive(Debug, Clone, Copy)]
+pub struct TickRecord {
+    /// Tick number after this step.
+    pub tick: u64,
+    /// Task that ran during the tick, or `None` if the CPU was idle.
+    pub ran: Option<usize>,
+    /// Task selected to run next.
+    pub next: usize,
+    /// Every task's strategy after the tick.
+    pub strategies: [Strategy; MAX_TASKS],
+    /// Every task's current payoff after the tick.
+    pub payoffs: [i32; MAX_TASKS],
+}
+
+/// A CPU hog: works forever and never yields.
+pub fn cpu_bound() -> impl FnMut(&TaskTick) -> Action {
+    |_| Action::Run
+}
+
+/// Works `k` ticks, then yields.
+pub fn yields_every(k: u32) -> impl FnMut(&TaskTick) -> Action {
+    move |t| if t.work + 1 >= k { Action::Yield } else { Action::Run }
+}
+
+/// A periodic job of `work` ticks that completes on time and sleeps until
+/// its next release.
+pub fn periodic(work: u32) -> impl FnMut(&TaskTick) -> Action {
+    move |t| if t.work + 1 >= work { Action::Finish } else { Action::Run }
+}
+
+/// A [`Scheduler`] with synthetic tasks, stepped one tick at a time.
+pub struct Simulator<'a> {
+    sched: Scheduler,
+    behaviors: [Option<Behavior<'a>>; MAX_TASKS],
+    work: [u32; MAX_TASKS],
+    started: bool,
+}
+
+/// Entry point recorded for every simulated task. Never run: behaviours
+/// stand in for task code.
+extern "C" fn sim_entry() -> ! {
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+impl<'a> Simulator<'a> {
+    /// An empty simulation at tick 0.
+    pub fn new() -> Self {
+        Self {
+            sched: Scheduler::new(),
+            behaviors: core::array::from_fn(|_| None),
+            work: [0; MAX_TASKS],
+            started: false,
+        }
+    }
+
+    /// Add a task driven by `behavior`.
+    ///
+    /// # Returns
+    /// The task id, or the error `Scheduler::create_task()` reports.
+    pub fn add_task(
+        &mut self,
+        config: TaskConfig,
+        strategy: Strategy,
+        behavior: Behavior<'a>,
+    ) -> Result<usize, KernelError> {
+        let id = self.sched.create_task(TaskEntry::new(sim_entry), config, strategy)?;
+        self.behaviors[id] = Some(behavior);
+        self.work[id] = 0;
+        Ok(id)
+    }
+
+    /// The simulated scheduler, for assertions and tuning.
+    pub fn scheduler(&self) -> &Scheduler {
+        &self.sched
+    }
+
+    /// Mutable access to the simulated scheduler, e.g. to change the
+    /// policy or payoff weights mid-run.
+    pub fn scheduler_mut(&mut self) -> &mut Scheduler {
+        &mut self.sched
+    }
+
+    /// Simulate one tick.
+    pub fn step(&mut self) -> TickRecord {
+        if !self.started {
+            self.sched.schedule();
+            self.started = true;
+        }
+
+        // The running task acts on this tick
+        let current = self.sched.current();
+        let ran = (self.sched.tasks[current].state == TaskState::Running).then_some(current);
+        let mut action = Action::Run;
+        if let Some(id) = ran {
+            if let Some(behavior) = self.behaviors[id].as_mut() {
+                let view = TaskTick { tick: self.sched.tick_count, work: self.work[id] };
+                action = behavior(&view);
+            }
+        }
+
+        self.sched.tick();
+        if let Some(id) = ran {
+            self.work[id] += 1;
+            if action != Action::Run {
+                self.work[id] = 0;
+            }
+            // A task preempted by the tick no longer holds the CPU
+            if self.sched.current() == id {
+                match action {
+                    Action::Run => {}
+                    Action::Yield => self.sched.yield_current(),
+                    Action::Finish => {
+                        self.sched.job_complete();
+                        match self.sched.next_deadline(id) {
+                            Some(release) if release > self.sched.tick_count => {
+                                let ticks = release - self.sched.tick_count;
+                                self.sched.delay_current(ticks as u32);
+                            }
+                            _ => self.sched.yield_current(),
+                        }
+                    }
+                    Action::Sleep(ticks) => self.sched.delay_current(ticks),
+                }
+            }
+        }
+
+        let next = if self.sched.needs_reschedule {
+            self.sched.schedule()
+        } else {
+            self.sched.current()
+        };
+
+        let mut record = TickRecord {
+            tick: self.sched.tick_count,
+            ran,
+            next,
+            strategies: [Strategy::Cooperative; MAX_TASKS],
+            payoffs: [0; MAX_TASKS],
+        };
+        for id in 0..self.sched.task_count {
+            record.strategies[id] = self.sched.tasks[id].strategy;
+            record.payoffs[id] = self.sched.tasks[id].payoff.payoff;
+        }
+        record
+    }
+
+    /// Simulate `ticks` ticks, passing each tick's record to `observer`.
+    pub fn run(&mut self, ticks: u64, mut observer: impl FnMut(&TickRecord)) {
+        for _ in 0..ticks {
+            let record = self.step();
+            observer(&record);
+        }
+    }
+}
+
+impl Default for Simulator<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "stack-pool")]
+    use crate::config::STACK_SIZE;
+    #[cfg(feature = "stack-pool")]
+    use crate::stack_pool::tests::leaked_region;
+
+    fn config(priority: u8) -> TaskConfig {
+        TaskConfig { priority, time_slice: 10, ..TaskConfig::DEFAULT }
+    }
+
+    #[test]
+    fn test_trace_follows_behaviors() {
+        let mut every3 = yields_every(3);
+        let mut every2 = yields_every(2);
+        let mut sim = Simulator::new();
+        #[cfg(feature = "stack-pool")]
+        sim.scheduler_mut().set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        let a = sim.add_task(config(3), Strategy::Cooperative, &mut every3).unwrap();
+        let b = sim.add_task(config(3), Strategy::Cooperative, &mut every2).unwrap();
+
+        let mut ran = [None; 10];
+        let mut i = 0;
+        sim.run(10, |record| {
+            ran[i] = record.ran;
+            i += 1;
+        });
+        // Equal priorities alternate at each yield
+        let (a, b) = (Some(a), Some(b));
+        assert_eq!(ran, [a, a, a, b, b, a, a, a, b, b]);
+        assert_eq!(sim.scheduler().tasks[0].payoff.voluntary_yields, 2);
+    }
+
+    #[test]
+    fn test_sleeping_task_leaves_cpu_idle() {
+        let mut sleeper = |t: &TaskTick| if t.work == 0 { Action::Sleep(4) } else { Action::Run };
+        let mut sim = Simulator::new();
+        #[cfg(feature = "stack-pool")]
+        sim.scheduler_mut().set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        let id = sim.add_task(config(3), Strategy::Cooperative, &mut sleeper).unwrap();
+
+        let first = sim.step();
+        assert_eq!(first.ran, Some(id));
+        assert_eq!(sim.scheduler().tasks[id].state, TaskState::Blocked);
+        for _ in 0..4 {
+            assert_eq!(sim.step().ran, None);
+        }
+        assert_eq!(sim.scheduler().tasks[id].state, TaskState::Running);
+        assert_eq!(sim.scheduler().idle_ticks, 4);
+    }
+
+    #[test]
+    fn test_mixed_workload_settles_into_equilibrium() {
+        #[cfg(not(feature = "cooperative-only"))]
+        let mut hog = cpu_bound();
+        // Nothing takes the CPU from a task that never yields
+        #[cfg(feature = "cooperative-only")]
+        let mut hog = yields_every(20);
+        let mut sensor = periodic(2);
+        let mut logger = yields_every(1);
+        let mut sim = Simulator::new();
+        #[cfg(feature = "stack-pool")]
+        sim.scheduler_mut().set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        let hog_id = sim.add_task(config(2), Strategy::Selfish, &mut hog).unwrap();
+        let sensor_id = sim
+            .add_task(
+                TaskConfig { deadline_ticks: 50, wcet_ticks: 3, ..config(20) },
+                Strategy::Cooperative,
+                &mut sensor,
+            )
+            .unwrap();
+        let logger_id = sim.add_task(config(1), Strategy::Cooperative, &mut logger).unwrap();
+
+        // Strategies stop moving well before the end of the run
+        let mut ticks_run = [0u32; MAX_TASKS];
+        let mut late_switches = 0;
+        let mut previous = [Strategy::Cooperative; MAX_TASKS];
+        sim.run(5000, |record| {
+            if let Some(id) = record.ran {
+                ticks_run[id] += 1;
+            }
+            if record.tick > 2500 && record.strategies != previous {
+                late_switches += 1;
+            }
+            previous = record.strategies;
+        });
+
+        let sched = sim.scheduler();
+        assert!(sched.in_equilibrium);
+        assert_eq!(late_switches, 0);
+        assert_eq!(previous[sensor_id], Strategy::Cooperative);
+        assert_eq!(previous[logger_id], Strategy::Cooperative);
+        assert!(sched.metrics.global_cooperation_ratio >= 50);
+        assert_eq!(sched.tasks[sensor_id].payoff.deadlines_missed, 0);
+        assert!(ticks_run[hog_id] > ticks_run[sensor_id]);
+    }
+}
@@ -0,0 +1,921 @@
+//! # Scheduler Snapshots
+//!
+//! Serializes the complete scheduler state into a byte buffer and restores
+//! it later, for hibernation and checkpoint/restore (save to flash, power
+//! down, resume).
+//!
+//! Live stack pointers and stack contents are not saved. On restore every
+//! task is restarted from its entry function with a fresh stack frame,
+//! while its configuration, strategy, payoff metrics, and timing state are
+//! reinstated. The application must therefore re-create the same tasks, in
+//! the same order, before restoring.
+//!
+//! ## Format
+//!
+//! Little-endian, starting with a header:
+//!
+//! ```text
+//! magic "EQSN" (4) · version u16 · task_count u8 · scheduler state · tasks
+//! ```
+//!
+//! The version is bumped whenever the layout changes; `restore` rejects
+//! any other version.
+
+use crate::config::{MAX_BUNDLES, MAX_CORES, MAX_TASKS};
+use crate::error::KernelError;
+use crate::game::{PayoffConfig, SystemMetrics};
+use crate::scheduler::{
+    BoostCurve, DeadlineBundle, DegradePolicy, OverrunPolicy, SchedPolicy, Scheduler, TieBreak,
+};
+use crate::task::{
+    BlockReason, Donation, PayoffMetrics, StackCheck, Strategy, TaskConfig, TaskState, WakeReason,
+};
+
+/// Leading bytes of every snapshot.
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"EQSN";
+
+/// Current snapshot format version.
+pub const SNAPSHOT_VERSION: u16 = 34;
+
+/// Encoded size of the header plus scheduler-wide state.
+const HEADER_SIZE: usize = 4 + 2 + 1;
+const SCHEDULER_SIZE: usize =
+    8 + 8 + 1 + 1 + 1 + 1 + 1 + 4 + 1 + 4 + 1 + 4 + 4 + 1 + 4 + 4 + 4 + 4 + 1 + 8 + 4 + 4 + 1 + 8 + 8 + 8 + 4 + 8 + 8 + 8 + 8 + 8
+        + PAYOFF_CONFIG_SIZE + MAX_BUNDLES * BUNDLE_SIZE;
+
+/// Encoded size of the payoff weights.
+const PAYOFF_CONFIG_SIZE: usize = 8 * 4;
+
+/// Encoded size of one deadline bundle.
+const BUNDLE_SIZE: usize = 1 + 6 * 4;
+
+/// Encoded size of one task record.
+const TASK_SIZE: usize = 1 + 1 + 1 + 1 + 1 + 36 + 24 * 4 + 8 + 4 * 4 + 4 + 8 + 2 * 4 + 8 + 1 + 8 + 8 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 8 + 1 + 1 + 1 + 8 + 4 + 8 + 1 + 4 + 4 + 4 + 4 + 4 + 8 + 4 + 4 + 1;
+
+/// Buffer size needed to snapshot a scheduler with `task_count` tasks.
+pub const fn snapshot_size(task_count: usize) -> usize {
+    HEADER_SIZE + SCHEDULER_SIZE + task_count * TASK_SIZE
+}
+
+/// Write a snapshot of `sched` into `buf`.
+///
+/// # Returns
+/// The number of bytes written, or 0 if `buf` is shorter than
+/// `snapshot_size(task_count)`.
+pub fn save(sched: &Scheduler, buf: &mut [u8]) -> usize {
+    let size = snapshot_size(sched.task_count);
+    if buf.len() < size {
+        return 0;
+    }
+
+    let mut w = Writer { buf, pos: 0 };
+    w.bytes(&SNAPSHOT_MAGIC);
+    w.u16(SNAPSHOT_VERSION);
+    w.u8(sched.task_count as u8);
+
+    w.u64(sched.tick_count);
+    w.u64(sched.idle_ticks);
+    w.u8(sched.policy as u8);
+    w.u8(sched.tie_break as u8);
+    w.u8(sched.overrun_policy as u8);
+    w.u8(sched.degrade_policy as u8);
+    w.bool(sched.in_equilibrium);
+    w.u32(sched.equilibrium_streak);
+    w.bool(sched.last_equilibrium);
+    w.u32(sched.evaluations_since_change);
+    w.bool(sched.defection_recovery);
+    w.u32(sched.defection_windows);
+    w.u32(sched.starvation_threshold);
+    w.u8(sched.boost_curve as u8);
+    w.u32(sched.starvation_boost_max);
+    w.u32(sched.quantum_scale_min);
+    w.u32(sched.quantum_scale_max);
+    w.u32(sched.tick_hz);
+    w.u8(sched.stack_check as u8);
+    w.u64(sched.metrics.total_ticks);
+    w.u32(sched.metrics.active_tasks);
+    w.u32(sched.metrics.global_cooperation_ratio);
+    w.bool(sched.metrics.overload);
+    w.u64(sched.metrics.total_cycles);
+    w.u64(sched.metrics.recent_ticks);
+    w.u64(sched.metrics.recent_cycles);
+    w.u32(sched.dense_eval_ticks);
+    w.u64(sched.evaluations);
+    w.u64(sched.epoch_start_tick);
+    w.u64(sched.window_start_tick);
+    w.u64(sched.uptime_base_tick);
+    w.u64(sched.uptime_base_ms);
+    let weights = &sched.payoff_config;
+    w.i32(weights.deadline_met);
+    w.i32(weights.deadline_missed);
+    w.i32(weights.voluntary_yield);
+    w.i32(weights.overrun);
+    w.i32(weights.fairness_penalty);
+    w.i32(weights.modesty_bonus);
+    w.i32(weights.cooperation_multiplier);
+    w.i32(weights.defection_penalty);
+    for bundle in &sched.bundles {
+        w.bool(bundle.active);
+        w.u32(bundle.deadline_ticks);
+        w.u32(bundle.elapsed);
+        w.u32(bundle.members);
+        w.u32(bundle.done);
+        w.u32(bundle.met);
+        w.u32(bundle.missed);
+    }
+
+    for tcb in &sched.tasks[..sched.task_count] {
+        w.bool(tcb.active);
+        w.u8(tcb.state as u8);
+        w.u8(tcb.strategy as u8);
+        w.bool(tcb.tft_cooperating);
+        w.bool(tcb.strategy_locked);
+
+        w.u8(tcb.config.priority);
+        w.u32(tcb.config.deadline_ticks);
+        w.u32(tcb.config.wcet_ticks);
+        w.u32(tcb.config.affinity_mask);
+        w.u32(tcb.config.time_slice);
+        w.u32(tcb.config.time_slice_ms);
+        w.bool(tcb.config.anchor);
+        w.bool(tcb.config.hard_deadline);
+        w.bool(tcb.config.enforce_deadline);
+        w.u32(tcb.config.budget_ticks);
+        w.u32(tcb.config.budget_period);
+        w.u32(tcb.config.starvation_threshold);
+
+        let p = &tcb.payoff;
+        w.u32(p.cpu_ticks_used);
+        w.u32(p.deadlines_met);
+        w.u32(p.deadlines_missed);
+        w.u32(p.voluntary_yields);
+        w.u32(p.overruns);
+        w.u32(p.consecutive_overruns);
+        w.u32(p.soft_overruns);
+        w.u32(p.preemptions_caused);
+        w.u32(p.timeouts);
+        w.u32(p.recent_deadlines_met);
+        w.u32(p.recent_deadlines_missed);
+        w.u32(p.recent_yields);
+        w.i32(p.cooperation_score);
+        w.i32(p.payoff);
+        w.i32(p.previous_payoff);
+        w.u32(p.decline_streak);
+        w.u32(p.ticks_since_last_run);
+        w.u32(p.ticks_since_last_yield);
+        w.u32(p.run_streak);
+        w.u32(p.max_run_streak);
+        w.u32(p.recent_switches);
+        w.u32(p.switch_window);
+        w.u32(p.strategy_lock);
+        w.u32(p.reclassifications);
+        w.u64(p.cpu_cycles_used);
+
+        w.u32(tcb.ticks_remaining);
+        w.u32(tcb.total_ticks);
+        w.u32(tcb.window_ticks_used);
+        w.u32(tcb.window_yields);
+        w.u32(tcb.recent_ticks_used);
+        w.u64(tcb.recent_cycles_used);
+        w.u32(tcb.budget_used);
+        w.u32(tcb.budget_elapsed);
+        w.u32(tcb.period_ticks);
+        w.u64(tcb.deadline_tick);
+        w.u8(match tcb.block_reason {
+            None => 0,
+            Some(BlockReason::Delay) => 1,
+            Some(BlockReason::Predecessor) => 2,
+            Some(BlockReason::Deferral) => 3,
+            Some(BlockReason::Notification) => 4,
+            Some(BlockReason::DeadlineOverrun) => 5,
+            Some(BlockReason::BudgetExhausted) => 6,
+            Some(BlockReason::EventGroup(_)) => 7,
+        });
+        w.u64(match tcb.block_reason {
+            Some(BlockReason::EventGroup(group)) => group as u64,
+            _ => 0,
+        });
+        w.u64(tcb.wake_tick.unwrap_or(u64::MAX));
+        w.u8(tcb.wake_reason as u8);
+        w.u8(tcb.predecessor.map_or(u8::MAX, |p| p as u8));
+        w.bool(tcb.predecessor_signalled);
+        w.bool(tcb.job_done);
+        w.bool(tcb.job_late);
+        w.bool(tcb.deadline_enforced);
+        w.bool(tcb.job_overran);
+        w.u32(tcb.overload_streak);
+        w.u32(tcb.release_stride);
+        w.bool(tcb.completion_jitter.is_some());
+        let (min, max) = tcb.completion_jitter.unwrap_or((0, 0));
+        w.i32(min);
+        w.i32(max);
+        w.bool(tcb.donation.is_some());
+        let donation = tcb.donation.unwrap_or(Donation { donor: 0, amount: 0, expires_tick: None });
+        w.u8(donation.donor as u8);
+        w.u8(donation.amount);
+        w.u64(donation.expires_tick.unwrap_or(u64::MAX));
+        w.u32(tcb.deferrals_pending);
+        w.u64(tcb.event_arrival.unwrap_or(u64::MAX));
+        w.bool(tcb.last_response.is_some());
+        w.u32(tcb.last_response.unwrap_or(0));
+        w.u32(tcb.max_response);
+        w.u32(tcb.response_threshold);
+        w.u64(tcb.last_scheduled_at.unwrap_or(u64::MAX));
+        w.u32(tcb.notification);
+        w.bool(tcb.notify_pending);
+    }
+
+    w.pos
+}
+
+/// Restore `sched` from a snapshot previously produced by [`save`].
+///
+/// The whole snapshot is decoded and validated before anything is changed,
+/// so a rejected snapshot leaves the scheduler untouched. Tasks that were
+/// Running become Ready, and every task restarts from its entry function.
+///
+/// # Returns
+/// - `Err(KernelError::InvalidSnapshot)` — bad magic or version, truncated
+///   buffer, or an out-of-range field
+/// - `Err(KernelError::InvalidState)` — the snapshot's task count does not
+///   match the tasks currently created
+pub fn restore(sched: &mut Scheduler, buf: &[u8]) -> Result<(), KernelError> {
+    let mut r = Reader { buf, pos: 0 };
+    if r.bytes(4)? != SNAPSHOT_MAGIC || r.u16()? != SNAPSHOT_VERSION {
+        return Err(KernelError::InvalidSnapshot);
+    }
+    let task_count = r.u8()? as usize;
+    if task_count > MAX_TASKS {
+        return Err(KernelError::InvalidSnapshot);
+    }
+    if task_count != sched.task_count {
+        return Err(KernelError::InvalidState);
+    }
+
+    let mut global = SchedulerImage {
+        tick_count: r.u64()?,
+        idle_ticks: r.u64()?,
+        policy: match r.u8()? {
+            0 => SchedPolicy::GameTheoretic,
+            1 => SchedPolicy::StrictPriority,
+            2 => SchedPolicy::RateMonotonic,
+            3 => SchedPolicy::EarliestDeadlineFirst,
+            _ => return Err(KernelError::InvalidSnapshot),
+        },
+        tie_break: match r.u8()? {
+            0 => TieBreak::LowestId,
+            1 => TieBreak::RoundRobin,
+            2 => TieBreak::HighestCooperation,
+            3 => TieBreak::EarliestDeadline,
+            4 => TieBreak::LeastRecentlyRun,
+            _ => return Err(KernelError::InvalidSnapshot),
+        },
+        overrun_policy: match r.u8()? {
+            0 => OverrunPolicy::Strict,
+            1 => OverrunPolicy::SoftIfMet,
+            _ => return Err(KernelError::InvalidSnapshot),
+        },
+        degrade_policy: match r.u8()? {
+            0 => DegradePolicy::None,
+            1 => DegradePolicy::SkipReleases,
+            2 => DegradePolicy::WidenPeriod,
+            3 => DegradePolicy::Alarm,
+            _ => return Err(KernelError::InvalidSnapshot),
+        },
+        in_equilibrium: r.bool()?,
+        equilibrium_streak: r.u32()?,
+        last_equilibrium: r.bool()?,
+        evaluations_since_change: r.u32()?,
+        defection_recovery: r.bool()?,
+        defection_windows: r.u32()?,
+        starvation_threshold: r.u32()?,
+        boost_curve: match r.u8()? {
+            0 => BoostCurve::Stepped,
+            1 => BoostCurve::Linear,
+            _ => return Err(KernelError::InvalidSnapshot),
+        },
+        starvation_boost_max: r.u32()?,
+        quantum_scale_min: r.u32()?,
+        quantum_scale_max: r.u32()?,
+        tick_hz: r.u32()?,
+        stack_check: match r.u8()? {
+            0 => StackCheck::None,
+            1 => StackCheck::BoundsCheck,
+            2 => StackCheck::CanaryCheck,
+            _ => return Err(KernelError::InvalidSnapshot),
+        },
+        metrics: SystemMetrics {
+            total_ticks: r.u64()?,
+            active_tasks: r.u32()?,
+            global_cooperation_ratio: r.u32()?,
+            overload: r.bool()?,
+            total_cycles: r.u64()?,
+            recent_ticks: r.u64()?,
+            recent_cycles: r.u64()?,
+        },
+        dense_eval_ticks: r.u32()?,
+        evaluations: r.u64()?,
+        epoch_start_tick: r.u64()?,
+        window_start_tick: r.u64()?,
+        uptime_base_tick: r.u64()?,
+        uptime_base_ms: r.u64()?,
+        payoff_config: PayoffConfig {
+            deadline_met: r.i32()?,
+            deadline_missed: r.i32()?,
+            voluntary_yield: r.i32()?,
+            overrun: r.i32()?,
+            fairness_penalty: r.i32()?,
+            modesty_bonus: r.i32()?,
+            cooperation_multiplier: r.i32()?,
+            defection_penalty: r.i32()?,
+        },
+        bundles: [DeadlineBundle::EMPTY; MAX_BUNDLES],
+    };
+    if global.quantum_scale_min == 0
+        || global.quantum_scale_min > global.quantum_scale_max
+        || global.tick_hz == 0
+    {
+        return Err(KernelError::InvalidSnapshot);
+    }
+    for bundle in global.bundles.iter_mut() {
+        *bundle = DeadlineBundle {
+            active: r.bool()?,
+            deadline_ticks: r.u32()?,
+            elapsed: r.u32()?,
+            members: r.u32()?,
+            done: r.u32()?,
+            met: r.u32()?,
+            missed: r.u32()?,
+        };
+        if bundle.members.checked_shr(task_count as u32).unwrap_or(0) != 0 {
+            return Err(KernelError::InvalidSnapshot);
+        }
+    }
+
+    let mut images = [TaskImage::EMPTY; MAX_TASKS];
+    for (id, image) in images[..task_count].iter_mut().enumerate() {
+        *image = TaskImage::decode(&mut r, task_count)?;
+        if image.active != sched.tasks[id].active {
+            return Err(KernelError::InvalidState);
+        }
+    }
+
+    // Validated: apply
+    sched.tick_count = global.tick_count;
+    sched.idle_ticks = global.idle_ticks;
+    sched.policy = global.policy;
+    sched.tie_break = global.tie_break;
+    sched.overrun_policy = global.overrun_policy;
+    sched.degrade_policy = global.degrade_policy;
+    sched.in_equilibrium = global.in_equilibrium;
+    sched.equilibrium_streak = global.equilibrium_streak;
+    sched.last_equilibrium = global.last_equilibrium;
+    sched.evaluations_since_change = global.evaluations_since_change;
+    sched.defection_recovery = global.defection_recovery;
+    sched.defection_windows = global.defection_windows;
+    sched.starvation_threshold = global.starvation_threshold;
+    sched.boost_curve = global.boost_curve;
+    sched.starvation_boost_max = global.starvation_boost_max;
+    sched.quantum_scale_min = global.quantum_scale_min;
+    sched.quantum_scale_max = global.quantum_scale_max;
+    sched.tick_hz = global.tick_hz;
+    sched.stack_check = global.stack_check;
+    sched.metrics = global.metrics;
+    sched.bundles = global.bundles;
+    sched.dense_eval_ticks = global.dense_eval_ticks;
+    sched.evaluations = global.evaluations;
+    sched.epoch_start_tick = global.epoch_start_tick;
+    sched.window_start_tick = global.window_start_tick;
+    sched.uptime_base_tick = global.uptime_base_tick;
+    sched.uptime_base_ms = global.uptime_base_ms;
+    sched.payoff_config = global.payoff_config;
+    // The trend is monitoring history, not scheduling state
+    sched.reset_cooperation_trend();
+
+    for (id, image) in images[..task_count].iter().enumerate() {
+        if image.active {
+            sched.restore_task(id, image);
+        }
+    }
+    sched.current_task = [0; MAX_CORES];
+    sched.forced_next = None;
+    sched.needs_reschedule = true;
+    Ok(())
+}
+
+/// Decoded scheduler-wide state, held until the whole snapshot validates.
+struct SchedulerImage {
+    tick_count: u64,
+    idle_ticks: u64,
+    policy: SchedPolicy,
+    tie_break: TieBreak,
+    overrun_policy: OverrunPolicy,
+    degrade_policy: DegradePolicy,
+    in_equilibrium: bool,
+    equilibrium_streak: u32,
+    last_equilibrium: bool,
+    evaluations_since_change: u32,
+    defection_recovery: bool,
+    defection_windows: u32,
+    starvation_threshold: u32,
+    boost_curve: BoostCurve,
+    starvation_boost_max: u32,
+    quantum_scale_min: u32,
+    quantum_scale_max: u32,
+    tick_hz: u32,
+    stack_check: StackCheck,
+    metrics: SystemMetrics,
+    dense_eval_ticks: u32,
+    evaluations: u64,
+    epoch_start_tick: u64,
+    window_start_tick: u64,
+    uptime_base_tick: u64,
+    uptime_base_ms: u64,
+    payoff_config: PayoffConfig,
+    bundles: [DeadlineBundle; MAX_BUNDLES],
+}
+
+/// Decoded per-task state: everything in a TCB except the stack, the
+/// stack pointer, and the entry function.
+#[derive(Clone, Copy)]
+pub(crate) struct TaskImage {
+    pub active: bool,
+    pub state: TaskState,
+    pub strategy: Strategy,
+    pub tft_cooperating: bool,
+    pub strategy_locked: bool,
+    pub config: TaskConfig,
+    pub payoff: PayoffMetrics,
+    pub ticks_remaining: u32,
+    pub total_ticks: u32,
+    pub window_ticks_used: u32,
+    pub window_yields: u32,
+    pub recent_ticks_used: u32,
+    pub recent_cycles_used: u64,
+    pub budget_used: u32,
+    pub budget_elapsed: u32,
+    pub period_ticks: u32,
+    pub deadline_tick: u64,
+    pub block_reason: Option<BlockReason>,
+    pub wake_tick: Option<u64>,
+    pub wake_reason: WakeReason,
+    pub predecessor: Option<usize>,
+    pub predecessor_signalled: bool,
+    pub job_done: bool,
+    pub job_late: bool,
+    pub deadline_enforced: bool,
+    pub job_overran: bool,
+    pub overload_streak: u32,
+    pub release_stride: u32,
+    pub completion_jitter: Option<(i32, i32)>,
+    pub donation: Option<Donation>,
+    pub deferrals_pending: u32,
+    pub event_arrival: Option<u64>,
+    pub last_response: Option<u32>,
+    pub max_response: u32,
+    pub response_threshold: u32,
+    pub last_scheduled_at: Option<u64>,
+    pub notification: u32,
+    pub notify_pending: bool,
+}
+
+impl TaskImage {
+    const EMPTY: Self = Self {
+        active: false,
+        state: TaskState::Suspended,
+        strategy: Strategy::Cooperative,
+        tft_cooperating: true,
+        strategy_locked: false,
+        config: TaskConfig::DEFAULT,
+        payoff: PayoffMetrics::new(),
+        ticks_remaining: 0,
+        total_ticks: 0,
+        window_ticks_used: 0,
+        window_yields: 0,
+        recent_ticks_used: 0,
+        recent_cycles_used: 0,
+        budget_used: 0,
+        budget_elapsed: 0,
+        period_ticks: 0,
+        deadline_tick: 0,
+        block_reason: None,
+        wake_tick: None,
+        wake_reason: WakeReason::Timeout,
+        predecessor: None,
+        predecessor_signalled: false,
+        job_done: false,
+        job_late: false,
+        deadline_enforced: false,
+        job_overran: false,
+        overload_streak: 0,
+        release_stride: 1,
+        completion_jitter: None,
+        donation: None,
+        deferrals_pending: 0,
+        event_arrival: None,
+        last_response: None,
+        max_response: 0,
+        response_threshold: 0,
+        last_scheduled_at: None,
+        notification: 0,
+        notify_pending: false,
+    };
+
+    fn decode(r: &mut Reader, task_count: usize) -> Result<Self, KernelError> {
+        let active = r.bool()?;
+        let state = match r.u8()? {
+            // Stacks are rebuilt, so nothing is mid-run after a restore
+            0 | 1 => TaskState::Ready,
+            2 => TaskState::Blocked,
+            3 => TaskState::Suspended,
+            4 => TaskState::Terminated,
+            _ => return Err(KernelError::InvalidSnapshot),
+        };
+        let strategy = match r.u8()? {
+            0 => Strategy::Cooperative,
+            1 => Strategy::Selfish,
+            2 => Strategy::TitForTat,
+            _ => return Err(KernelError::InvalidSnapshot),
+        };
+        let tft_cooperating = r.bool()?;
+        let strategy_locked = r.bool()?;
+        let config = TaskConfig {
+            priority: r.u8()?,
+            deadline_ticks: r.u32()?,
+            wcet_ticks: r.u32()?,
+            affinity_mask: r.u32()?,
+            time_slice: r.u32()?,
+            time_slice_ms: r.u32()?,
+            anchor: r.bool()?,
+            hard_deadline: r.bool()?,
+            enforce_deadline: r.bool()?,
+            budget_ticks: r.u32()?,
+            budget_period: r.u32()?,
+            starvation_threshold: r.u32()?,
+            // Restored from the live task, see `restore_task()`
+            name: "",
+        };
+        let payoff = PayoffMetrics {
+            cpu_ticks_used: r.u32()?,
+            deadlines_met: r.u32()?,
+            deadlines_missed: r.u32()?,
+            voluntary_yields: r.u32()?,
+            overruns: r.u32()?,
+            consecutive_overruns: r.u32()?,
+            soft_overruns: r.u32()?,
+            preemptions_caused: r.u32()?,
+            timeouts: r.u32()?,
+            recent_deadlines_met: r.u32()?,
+            recent_deadlines_missed: r.u32()?,
+            recent_yields: r.u32()?,
+            cooperation_score: r.i32()?,
+            payoff: r.i32()?,
+            previous_payoff: r.i32()?,
+            decline_streak: r.u32()?,
+            ticks_since_last_run: r.u32()?,
+            ticks_since_last_yield: r.u32()?,
+            run_streak: r.u32()?,
+            max_run_streak: r.u32()?,
+            recent_switches: r.u32()?,
+            switch_window: r.u32()?,
+            strategy_lock: r.u32()?,
+            reclassifications: r.u32()?,
+            cpu_cycles_used: r.u64()?,
+        };
+        let ticks_remaining = r.u32()?;
+        let total_ticks = r.u32()?;
+        let window_ticks_used = r.u32()?;
+        let window_yields = r.u32()?;
+        let recent_ticks_used = r.u32()?;
+        let recent_cycles_used = r.u64()?;
+        let budget_used = r.u32()?;
+        let budget_elapsed = r.u32()?;
+        let period_ticks = r.u32()?;
+        let deadline_tick = r.u64()?;
+        let block_tag = r.u8()?;
+        let block_group = r.u64()? as usize;
+        let block_reason = match block_tag {
+            0 => None,
+            1 => Some(BlockReason::Delay),
+            2 => Some(BlockReason::Predecessor),
+            3 => Some(BlockReason::Deferral),
+            4 => Some(BlockReason::Notification),
+            5 => Some(BlockReason::DeadlineOverrun),
+            6 => Some(BlockReason::BudgetExhausted),
+            7 => Some(BlockReason::EventGroup(block_group)),
+            _ => return Err(KernelError::InvalidSnapshot),
+        };
+        let wake_tick = match r.u64()? {
+            u64::MAX => None,
+            tick => Some(tick),
+        };
+        let wake_reason = match r.u8()? {
+            0 => WakeReason::Timeout,
+            1 => WakeReason::Cancelled,
+            2 => WakeReason::Interrupt,
+            3 => WakeReason::Signalled,
+            _ => return Err(KernelError::InvalidSnapshot),
+        };
+        let predecessor = match r.u8()? {
+            u8::MAX => None,
+            p if (p as usize) < task_count => Some(p as usize),
+            _ => return Err(KernelError::InvalidSnapshot),
+        };
+        let predecessor_signalled = r.bool()?;
+        let job_done = r.bool()?;
+        let job_late = r.bool()?;
+        let deadline_enforced = r.bool()?;
+        let job_overran = r.bool()?;
+        let overload_streak = r.u32()?;
+        let release_stride = r.u32()?;
+        if release_stride == 0 {
+            return Err(KernelError::InvalidSnapshot);
+        }
+        let has_jitter = r.bool()?;
+        let jitter = (r.i32()?, r.i32()?);
+        let has_donation = r.bool()?;
+        let donor = r.u8()? as usize;
+        let amount = r.u8()?;
+        let expires_tick = match r.u64()? {
+            u64::MAX => None,
+            tick => Some(tick),
+        };
+        if has_donation && donor >= task_count {
+            return Err(KernelError::InvalidSnapshot);
+        }
+        let deferrals_pending = r.u32()?;
+        let event_arrival = match r.u64()? {
+            u64::MAX => None,
+            tick => Some(tick),
+        };
+        let has_response = r.bool()?;
+        let last_response = r.u32()?;
+        let max_response = r.u32()?;
+        let response_threshold = r.u32()?;
+        let last_scheduled_at = match r.u64()? {
+            u64::MAX => None,
+            tick => Some(tick),
+        };
+        let notification = r.u32()?;
+        let notify_pending = r.bool()?;
+
+        Ok(Self {
+            active,
+            state,
+            strategy,
+            tft_cooperating,
+            strategy_locked,
+            config,
+            payoff,
+            ticks_remaining,
+            total_ticks,
+            window_ticks_used,
+            window_yields,
+            recent_ticks_used,
+            recent_cycles_used,
+            budget_used,
+            budget_elapsed,
+            period_ticks,
+            deadline_tick,
+            block_reason,
+            wake_tick,
+            wake_reason,
+            predecessor,
+            predecessor_signalled,
+            job_done,
+            job_late,
+            deadline_enforced,
+            job_overran,
+            overload_streak,
+            release_stride,
+            completion_jitter: if has_jitter { Some(jitter) } else { None },
+            donation: if has_donation {
+                Some(Donation { donor, amount, expires_tick })
+            } else {
+                None
+            },
+            deferrals_pending,
+            event_arrival,
+            last_response: if has_response { Some(last_response) } else { None },
+            max_response,
+            response_threshold,
+            last_scheduled_at,
+            notification,
+            notify_pending,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Byte encoding
+// ---------------------------------------------------------------------------
+
+/// Sequential little-endian writer. Callers check the buffer size first.
+struct Writer<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl Writer<'_> {
+    fn bytes(&mut self, data: &[u8]) {
+        self.buf[self.pos..self.pos + data.len()].copy_from_slice(data);
+        self.pos += data.len();
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.bytes(&[v]);
+    }
+
+    fn bool(&mut self, v: bool) {
+        self.u8(v as u8);
+    }
+
+    fn u16(&mut self, v: u16) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn i32(&mut self, v: i32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.bytes(&v.to_le_bytes());
+    }
+}
+
+/// Sequential little-endian reader. Running past the end is reported as
+/// an invalid snapshot.
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], KernelError> {
+        let end = self.pos + len;
+        let data = self.buf.get(self.pos..end).ok_or(KernelError::InvalidSnapshot)?;
+        self.pos = end;
+        Ok(data)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], KernelError> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.bytes(N)?);
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, KernelError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool, KernelError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(KernelError::InvalidSnapshot),
+        }
+    }
+
+    fn u16(&mut self) -> Result<u16, KernelError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, KernelError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, KernelError> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, KernelError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskEntry;
+    #[cfg(feature = "stack-pool")]
+    use crate::config::STACK_SIZE;
+    #[cfg(feature = "stack-pool")]
+    use crate::stack_pool::tests::leaked_region;
+
+    extern "C" fn spin() -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    const TEST_ENTRY: TaskEntry = TaskEntry::new(spin);
+
+    fn make_config(priority: u8) -> TaskConfig {
+        TaskConfig {
+            priority,
+            deadline_ticks: 30,
+            ..TaskConfig::DEFAULT
+        }
+    }
+
+    /// Create two tasks and run them for 75 ticks. Tasks are created in
+    /// place: moving a scheduler moves their stacks.
+    fn run_two_tasks(sched: &mut Scheduler) {
+        #[cfg(feature = "stack-pool")]
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        sched.create_task(TEST_ENTRY, make_config(3), Strategy::Cooperative).unwrap();
+        sched.create_task(TEST_ENTRY, make_config(5), Strategy::Selfish).unwrap();
+        sched.schedule();
+        for _ in 0..75 {
+            sched.tick();
+            if sched.needs_reschedule {
+                sched.schedule();
+            }
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut sched = Scheduler::new();
+        run_two_tasks(&mut sched);
+        let weights = PayoffConfig { deadline_missed: 900, ..PayoffConfig::DEFAULT };
+        sched.set_payoff_config(weights);
+        let mut buf = [0u8; snapshot_size(MAX_TASKS)];
+        let len = save(&sched, &mut buf);
+        assert_eq!(len, snapshot_size(2));
+
+        let tick_count = sched.tick_count;
+        let payoffs = [sched.tasks[0].payoff, sched.tasks[1].payoff];
+        let strategies = [sched.tasks[0].strategy, sched.tasks[1].strategy];
+
+        // Diverge from the snapshot
+        sched.tasks[0].strategy = Strategy::Selfish;
+        sched.tasks[1].strategy = Strategy::Cooperative;
+        sched.tasks[1].payoff.payoff += 1234;
+        sched.tasks[0].payoff.deadlines_missed += 7;
+        sched.set_payoff_config(PayoffConfig::DEFAULT);
+        for _ in 0..40 {
+            sched.tick();
+        }
+
+        restore(&mut sched, &buf[..len]).unwrap();
+        assert_eq!(sched.tick_count, tick_count);
+        assert_eq!(sched.payoff_config, weights);
+        for id in 0..2 {
+            assert_eq!(sched.tasks[id].strategy, strategies[id]);
+            assert_eq!(sched.tasks[id].payoff.payoff, payoffs[id].payoff);
+            assert_eq!(sched.tasks[id].payoff.cpu_ticks_used, payoffs[id].cpu_ticks_used);
+            assert_eq!(sched.tasks[id].payoff.deadlines_missed, payoffs[id].deadlines_missed);
+            assert_ne!(sched.tasks[id].state, TaskState::Running);
+        }
+        assert!(sched.needs_reschedule);
+    }
+
+    #[test]
+    fn test_snapshot_rejects_invalid_input() {
+        let mut sched = Scheduler::new();
+        run_two_tasks(&mut sched);
+        let mut buf = [0u8; snapshot_size(MAX_TASKS)];
+
+        // Too small to hold the snapshot
+        assert_eq!(save(&sched, &mut buf[..10]), 0);
+
+        let len = save(&sched, &mut buf);
+        let before = sched.tick_count;
+
+        assert_eq!(restore(&mut sched, &buf[..len - 1]), Err(KernelError::InvalidSnapshot));
+
+        let mut bad_version = buf;
+        bad_version[4] ^= 0xFF;
+        assert_eq!(restore(&mut sched, &bad_version[..len]), Err(KernelError::InvalidSnapshot));
+
+        let mut bad_magic = buf;
+        bad_magic[0] = b'X';
+        assert_eq!(restore(&mut sched, &bad_magic[..len]), Err(KernelError::InvalidSnapshot));
+
+        // A zero tick rate would divide by zero in the uptime
+        let tick_hz_at = HEADER_SIZE + 2 * 8 + 4 + 1 + 4 + 1 + 4 + 1 + 4 + 4 + 1 + 3 * 4;
+        let mut zero_hz = buf;
+        assert_eq!(zero_hz[tick_hz_at..tick_hz_at + 4], sched.tick_hz.to_le_bytes());
+        zero_hz[tick_hz_at..tick_hz_at + 4].fill(0);
+        assert_eq!(restore(&mut sched, &zero_hz[..len]), Err(KernelError::InvalidSnapshot));
+
+        // Snapshot taken with a different task set
+        let mut other = Scheduler::new();
+        #[cfg(feature = "stack-pool")]
+        other.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        other.create_task(TEST_ENTRY, make_config(1), Strategy::Cooperative).unwrap();
+        assert_eq!(restore(&mut other, &buf[..len]), Err(KernelError::InvalidState));
+
+        // Rejected restores leave the scheduler untouched
+        assert_eq!(sched.tick_count, before);
+    }
+}
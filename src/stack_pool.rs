@@ -0,0 +1,161 @@
+//! # External Stack Pool
+//!
+//! With the `stack-pool` feature, TCBs no longer embed a `STACK_SIZE`
+//! array. Each task instead holds a `(pointer, length)` reference into a
+//! single contiguous region supplied by the application, carved up in
+//! creation order. The TCB array then costs only its bookkeeping, and the
+//! stacks can be placed in a dedicated RAM region (e.g. CCM) with a linker
+//! section:
+//!
+//! ```ignore
+//! #[link_section = ".ccmram"]
+//! static mut STACKS: [u8; 4 * 1024] = [0; 4 * 1024];
+//!
+//! kernel::init();
+//! kernel::set_stack_pool(unsafe { &mut STACKS });
+//! kernel::create_task(...)?;
+//! ```
+//!
+//! Carved stacks are 8-byte aligned and sized in multiples of 8 bytes, as
+//! required by the AAPCS. They are never returned to the pool.
+
+/// Stack alignment required by the AAPCS.
+const STACK_ALIGN: usize = 8;
+
+/// A bump allocator over a caller-provided stack region.
+pub struct StackPool {
+    /// Start of the region.
+    base: *mut u8,
+    /// Region length in bytes.
+    len: usize,
+    /// Offset of the first unused byte.
+    next: usize,
+}
+
+// Safety: the pool only hands out disjoint sub-slices of a `'static`
+// region it owns, and is only accessed within critical sections.
+unsafe impl Send for StackPool {}
+unsafe impl Sync for StackPool {}
+
+impl StackPool {
+    /// A pool with no memory; every `carve()` fails.
+    pub const EMPTY: Self = Self {
+        base: core::ptr::null_mut(),
+        len: 0,
+        next: 0,
+    };
+
+    /// Take ownership of `mem` for task stacks.
+    pub fn new(mem: &'static mut [u8]) -> Self {
+        Self {
+            base: mem.as_mut_ptr(),
+            len: mem.len(),
+            next: 0,
+        }
+    }
+
+    /// Carve an aligned stack of at least `size` bytes.
+    ///
+    /// # Returns
+    /// The stack's base pointer and length (rounded up to a multiple of 8),
+    /// or `None` if the pool cannot fit it.
+    pub fn carve(&mut self, size: usize) -> Option<(*mut u8, usize)> {
+        if self.base.is_null() {
+            return None;
+        }
+        let addr = self.base as usize + self.next;
+        let start = align_up(addr)? - self.base as usize;
+        let size = align_up(size)?;
+        if size == 0 || start.checked_add(size)? > self.len {
+            return None;
+        }
+        self.next = start + size;
+        Some((unsafe { self.base.add(start) }, size))
+    }
+
+    /// Whether the pool was given memory, unlike `EMPTY`.
+    pub fn is_provided(&self) -> bool {
+        !self.base.is_null()
+    }
+
+    /// Bytes not yet carved (before alignment padding).
+    pub fn remaining(&self) -> usize {
+        self.len - self.next
+    }
+
+    /// Current carve position, for rolling back with `rewind()`.
+    pub(crate) fn mark(&self) -> usize {
+        self.next
+    }
+
+    /// Return every stack carved since `mark` to the pool.
+    pub(crate) fn rewind(&mut self, mark: usize) {
+        self.next = mark.min(self.next);
+    }
+}
+
+/// Round `value` up to `STACK_ALIGN`, or `None` on overflow.
+#[inline]
+fn align_up(value: usize) -> Option<usize> {
+    Some(value.checked_add(STACK_ALIGN - 1)? & !(STACK_ALIGN - 1))
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+pub(crate) mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::boxed::Box;
+    use std::vec;
+
+    /// Leak a zeroed `len`-byte region for a test to carve stacks from.
+    pub(crate) fn leaked_region(len: usize) -> &'static mut [u8] {
+        Box::leak(vec![0u8; len].into_boxed_slice())
+    }
+
+    #[test]
+    fn test_carved_stacks_aligned_and_disjoint() {
+        let region = leaked_region(1024 + 3);
+        // Start from a misaligned address to exercise the padding
+        let misaligned = if (region.as_ptr() as usize).is_multiple_of(STACK_ALIGN) {
+            &mut region[1..]
+        } else {
+            &mut region[..]
+        };
+        let mut pool = StackPool::new(misaligned);
+
+        let mut stacks = [(core::ptr::null_mut::<u8>(), 0usize); 3];
+        for (stack, size) in stacks.iter_mut().zip([100, 256, 13]) {
+            *stack = pool.carve(size).unwrap();
+            assert_eq!(stack.0 as usize % STACK_ALIGN, 0);
+            assert!(stack.1 >= size);
+            assert_eq!(stack.1 % STACK_ALIGN, 0);
+        }
+        for (i, a) in stacks.iter().enumerate() {
+            for b in &stacks[i + 1..] {
+                let (a_start, a_end) = (a.0 as usize, a.0 as usize + a.1);
+                let (b_start, b_end) = (b.0 as usize, b.0 as usize + b.1);
+                assert!(a_end <= b_start || b_end <= a_start);
+            }
+        }
+    }
+
+    #[test]
+    fn test_exhausted_pool_and_rewind() {
+        let mut pool = StackPool::new(leaked_region(512));
+        let mark = pool.mark();
+        assert!(pool.carve(256).is_some());
+        assert!(pool.carve(512).is_none());
+        pool.rewind(mark);
+        assert!(pool.carve(512 - STACK_ALIGN).is_some());
+
+        let mut empty = StackPool::EMPTY;
+        assert!(!empty.is_provided());
+        assert!(empty.carve(8).is_none());
+        assert!(pool.carve(0).is_none());
+    }
+}
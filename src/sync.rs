@@ -3,9 +3,137 @@
 //! Interrupt-safe critical section abstractions for the Cortex-M4.
 //! All shared scheduler state must be accessed within a critical section
 //! to prevent data races between the main thread and interrupt handlers.
+//! [`Signal`] covers the simplest ISR-to-task case without masking
+//! interrupts at all, [`SeqU64`] publishes a 64-bit value that any
+//! context can read without tearing, and [`EventGroup`] lets tasks block
+//! until any or all of a set of flags are raised.
+//!
+//! Critical sections nest: [`enter()`] returns a [`CriticalGuard`] that
+//! saves PRIMASK, and only the outermost guard's drop re-enables
+//! interrupts. [`critical_section()`] is a closure wrapper over the guard.
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::sync::atomic::{fence, AtomicBool, AtomicU32, Ordering};
 
 use cortex_m::interrupt;
 
+use crate::config::MAX_TASKS;
+use crate::scheduler::Scheduler;
+use crate::task::WaitResult;
+
+/// Number of live [`CriticalGuard`]s.
+static DEPTH: AtomicU32 = AtomicU32::new(0);
+
+/// RAII critical section: interrupts stay disabled while it is alive.
+///
+/// Dropping the guard restores PRIMASK to what it was at [`enter()`], so
+/// guards nest freely and an inner drop never re-enables interrupts early.
+/// Guards must be dropped in reverse order of creation.
+///
+/// # Warning
+/// Never `mem::forget` a guard (or leak it in an `Rc` cycle etc.): the
+/// saved PRIMASK is never restored, interrupts stay masked for good and
+/// [`nesting_depth()`] never returns to zero.
+#[must_use = "interrupts are re-enabled as soon as the guard is dropped"]
+pub struct CriticalGuard {
+    /// Whether interrupts were enabled before this guard masked them.
+    was_enabled: bool,
+    cs: interrupt::CriticalSection,
+    /// Tied to the current execution context: `!Send` and `!Sync`.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl CriticalGuard {
+    /// Token proving interrupts are disabled, for APIs that take one.
+    #[inline]
+    pub fn cs(&self) -> &interrupt::CriticalSection {
+        &self.cs
+    }
+}
+
+impl Drop for CriticalGuard {
+    #[inline]
+    fn drop(&mut self) {
+        #[cfg(not(feature = "cortex-m0"))]
+        DEPTH.fetch_sub(1, Ordering::Relaxed);
+        // ARMv6-M has no atomic read-modify-write; interrupts are masked
+        #[cfg(feature = "cortex-m0")]
+        DEPTH.store(DEPTH.load(Ordering::Relaxed) - 1, Ordering::Relaxed);
+        if self.was_enabled {
+            // SAFETY: this guard disabled interrupts, and every guard
+            // created after it has already been dropped.
+            unsafe { hw::unmask() };
+        }
+    }
+}
+
+/// Disable interrupts until the returned guard is dropped.
+///
+/// Safe to call with interrupts already disabled, including from inside
+/// another guard or `critical_section()`.
+#[inline]
+pub fn enter() -> CriticalGuard {
+    let was_enabled = hw::mask();
+    #[cfg(not(feature = "cortex-m0"))]
+    DEPTH.fetch_add(1, Ordering::Relaxed);
+    // ARMv6-M has no atomic read-modify-write; interrupts are masked
+    #[cfg(feature = "cortex-m0")]
+    DEPTH.store(DEPTH.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
+    // SAFETY: interrupts stay disabled until the guard owning the token
+    // is dropped.
+    let cs = unsafe { interrupt::CriticalSection::new() };
+    CriticalGuard { was_enabled, cs, _not_send: PhantomData }
+}
+
+/// Returns `true` while at least one [`CriticalGuard`] is alive.
+#[inline]
+pub fn in_critical() -> bool {
+    nesting_depth() > 0
+}
+
+/// Number of nested critical sections currently entered.
+#[inline]
+pub fn nesting_depth() -> u32 {
+    DEPTH.load(Ordering::Relaxed)
+}
+
+/// PRIMASK access. Host builds model the mask with a flag so the nesting
+/// logic can be unit tested.
+#[cfg(not(test))]
+mod hw {
+    use cortex_m::{interrupt, register::primask};
+
+    /// Disable interrupts, returning whether they were enabled.
+    #[inline]
+    pub fn mask() -> bool {
+        let was_enabled = primask::read().is_active();
+        interrupt::disable();
+        was_enabled
+    }
+
+    #[inline]
+    pub unsafe fn unmask() {
+        interrupt::enable();
+    }
+}
+
+#[cfg(test)]
+mod hw {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    /// Host stand-in for PRIMASK: `true` while interrupts are masked.
+    pub static MASKED: AtomicBool = AtomicBool::new(false);
+
+    pub fn mask() -> bool {
+        !MASKED.swap(true, Ordering::Relaxed)
+    }
+
+    pub unsafe fn unmask() {
+        MASKED.store(false, Ordering::Relaxed);
+    }
+}
+
 /// Execute a closure within a critical section (interrupts disabled).
 ///
 /// This is the primary mechanism for safely accessing shared mutable state
@@ -28,5 +156,616 @@ pub fn critical_section<F, R>(f: F) -> R
 where
     F: FnOnce(&interrupt::CriticalSection) -> R,
 {
-    interrupt::free(f)
+    let guard = enter();
+    f(guard.cs())
+}
+
+/// A one-bit event flag for signaling a polling task from an ISR.
+///
+/// Lighter than a semaphore: it never touches the scheduler or any task
+/// state, so `set()` is safe from any context. It does **not** wake a
+/// blocked task — the receiving task must poll `check_and_clear()` from a
+/// loop it already runs. Multiple `set()` calls before a check coalesce
+/// into one event.
+///
+/// ```ignore
+/// static RX_READY: Signal = Signal::new();
+///
+/// // In the UART ISR
+/// RX_READY.set();
+///
+/// // In the task's main loop
+/// if RX_READY.check_and_clear() {
+///     drain_rx_buffer();
+/// }
+/// ```
+pub struct Signal {
+    flag: AtomicBool,
+}
+
+impl Signal {
+    /// Create a cleared signal.
+    pub const fn new() -> Self {
+        Self { flag: AtomicBool::new(false) }
+    }
+
+    /// Raise the signal. ISR-safe and lock-free.
+    #[inline]
+    pub fn set(&self) {
+        self.flag.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` if the signal was raised since the last check, and
+    /// clears it in the same atomic step so no event is lost or seen twice.
+    #[cfg(not(feature = "cortex-m0"))]
+    #[inline]
+    pub fn check_and_clear(&self) -> bool {
+        self.flag.swap(false, Ordering::Acquire)
+    }
+
+    /// Returns `true` if the signal was raised since the last check, and
+    /// clears it in the same atomic step so no event is lost or seen twice.
+    #[cfg(feature = "cortex-m0")]
+    #[inline]
+    pub fn check_and_clear(&self) -> bool {
+        // ARMv6-M has no atomic swap: mask interrupts so no set() can land
+        // between the load and the store
+        critical_section(|_cs| {
+            let raised = self.flag.load(Ordering::Acquire);
+            self.flag.store(false, Ordering::Relaxed);
+            raised
+        })
+    }
+}
+
+impl Default for Signal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A `u64` with one writer and lock-free, tear-free readers, for cores
+/// without 64-bit atomics.
+///
+/// A seqlock: the writer bumps a sequence number to odd before updating
+/// the two halves and back to even after; a reader retries until it sees
+/// the same even sequence on both sides of its read. Readers never block
+/// the writer, so it suits a value the tick ISR updates and tasks read.
+///
+/// Only one context may write. On a single core a reader can only be
+/// interrupted by the writer, never the other way round, so a read
+/// retries at most once per concurrent update.
+pub struct SeqU64 {
+    seq: AtomicU32,
+    lo: AtomicU32,
+    hi: AtomicU32,
+}
+
+impl SeqU64 {
+    /// Create a cell holding `value`.
+    pub const fn new(value: u64) -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+            lo: AtomicU32::new(value as u32),
+            hi: AtomicU32::new((value >> 32) as u32),
+        }
+    }
+
+    /// Publish `value`. Must only be called from the single writer.
+    #[inline]
+    pub fn store(&self, value: u64) {
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+        self.lo.store(value as u32, Ordering::Relaxed);
+        self.hi.store((value >> 32) as u32, Ordering::Relaxed);
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+    }
+
+    /// Read the value. Safe from any context; retries while a store is in
+    /// progress.
+    #[inline]
+    pub fn load(&self) -> u64 {
+        loop {
+            let before = self.seq.load(Ordering::Acquire);
+            let lo = self.lo.load(Ordering::Relaxed);
+            let hi = self.hi.load(Ordering::Relaxed);
+            fence(Ordering::Acquire);
+            let after = self.seq.load(Ordering::Relaxed);
+            if before == after && before.is_multiple_of(2) {
+                return (hi as u64) << 32 | lo as u64;
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// 32 flag bits that tasks can block on until any, or all, of a mask are
+/// set — e.g. a task woken by whichever of several interrupt sources fires
+/// first, or one that needs every stage of a pipeline to report in.
+///
+/// Each task has one waiter slot, so any number of tasks can wait on
+/// different masks of the same group; `set_bits()` wakes exactly those
+/// whose condition it satisfies. Satisfied waits that clear on exit
+/// remove their bits only after every waiter has been checked, so one
+/// `set_bits()` can release several tasks waiting on the same flag.
+///
+/// The methods take the scheduler they block and wake tasks on; tasks
+/// and ISRs normally go through `kernel::set_event_bits()` and
+/// `kernel::wait_event_bits()` instead.
+///
+/// ```ignore
+/// static RADIO: EventGroup = EventGroup::new();
+/// const RX_DONE: u32 = 1 << 0;
+/// const TX_DONE: u32 = 1 << 1;
+///
+/// // In the radio ISR
+/// let _ = kernel::set_event_bits(&RADIO, RX_DONE);
+///
+/// // In the task: whichever completes first, clearing it
+/// let (_, bits) = kernel::wait_event_bits(&RADIO, RX_DONE | TX_DONE, false, true, None);
+/// ```
+pub struct EventGroup {
+    state: UnsafeCell<EventState>,
+}
+
+struct EventState {
+    bits: u32,
+    /// Indexed by task id.
+    waits: [EventWait; MAX_TASKS],
+}
+
+/// One task's pending `wait_bits()`.
+#[derive(Clone, Copy)]
+struct EventWait {
+    /// Bits waited for; `0` when the task is not waiting.
+    mask: u32,
+    wait_all: bool,
+    clear_on_exit: bool,
+    /// Absolute tick at which the wait times out, if it has a timeout.
+    until: Option<u64>,
+    /// Group bits at the moment the wait was satisfied, delivered by
+    /// `set_bits()` for the woken task to collect.
+    result: Option<u32>,
+}
+
+impl EventWait {
+    const NONE: Self = Self { mask: 0, wait_all: false, clear_on_exit: false, until: None, result: None };
+
+    fn satisfied_by(&self, bits: u32) -> bool {
+        if self.wait_all {
+            bits & self.mask == self.mask
+        } else {
+            bits & self.mask != 0
+        }
+    }
+}
+
+// Safety: the state is only accessed inside a critical section.
+unsafe impl Sync for EventGroup {}
+
+impl EventGroup {
+    /// A group with every bit clear and no waiters.
+    pub const fn new() -> Self {
+        Self {
+            state: UnsafeCell::new(EventState { bits: 0, waits: [EventWait::NONE; MAX_TASKS] }),
+        }
+    }
+
+    /// The group's identity in a task's block reason.
+    fn address(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut EventState) -> R) -> R {
+        critical_section(|_cs| {
+            // SAFETY: interrupts are masked, so nothing else holds the state
+            f(unsafe { &mut *self.state.get() })
+        })
+    }
+
+    /// The bits currently set.
+    pub fn bits(&self) -> u32 {
+        self.with_state(|state| state.bits)
+    }
+
+    /// Set the bits in `mask` and wake every task whose wait is now
+    /// satisfied. Safe to call from an ISR.
+    ///
+    /// # Returns
+    /// The group's bits afterwards, i.e. once satisfied waits have cleared
+    /// theirs.
+    pub fn set_bits(&self, sched: &mut Scheduler, mask: u32) -> u32 {
+        let group = self.address();
+        self.with_state(|state| {
+            state.bits |= mask;
+            let mut clear = 0;
+            for (id, wait) in state.waits.iter_mut().enumerate() {
+                // Woken some other way, e.g. timed out: the task settles
+                // the wait when it next calls `wait_bits()`. A task that no
+                // longer waits here at all left a stale record behind.
+                if wait.mask == 0 || wait.result.is_some() || !sched.waits_on_event_group(id, group) {
+                    continue;
+                }
+                if wait.satisfied_by(state.bits) {
+                    wait.result = Some(state.bits);
+                    if wait.clear_on_exit {
+                        clear |= wait.mask;
+                    }
+                    sched.wake_event_waiter(id, group);
+                }
+            }
+            state.bits &= !clear;
+            state.bits
+        })
+    }
+
+    /// Clear the bits in `mask`. Waiting tasks are not affected.
+    ///
+    /// # Returns
+    /// The group's bits before clearing.
+    pub fn clear_bits(&self, mask: u32) -> u32 {
+        self.with_state(|state| {
+            let before = state.bits;
+            state.bits &= !mask;
+            before
+        })
+    }
+
+    /// Wait, as the current task, until any bit in `mask` is set, or
+    /// with `wait_all` every bit in it. With `clear_on_exit` the bits in
+    /// `mask` are cleared when the wait is satisfied. An empty `mask` is
+    /// satisfied at once.
+    ///
+    /// With `timeout` set, the wait ends after that many ticks if it is not
+    /// satisfied first, counting against the task's timeout count; a
+    /// timeout of `0` only polls.
+    ///
+    /// # Returns
+    /// `WaitResult::Ok` and the group's bits when the wait was satisfied
+    /// (before any clearing), `WaitResult::TimedOut` and the bits at that
+    /// point if the timeout expired first, or `None` if the task was
+    /// blocked, in which case it should call again with the same arguments
+    /// once woken.
+    pub fn wait_bits(
+        &self,
+        sched: &mut Scheduler,
+        mask: u32,
+        wait_all: bool,
+        clear_on_exit: bool,
+        timeout: Option<u32>,
+    ) -> Option<(WaitResult, u32)> {
+        let group = self.address();
+        self.with_state(|state| {
+            let current = sched.current();
+            let now = sched.tick_count;
+            let Some(wait) = state.waits.get_mut(current) else {
+                return Some((WaitResult::Ok, state.bits));
+            };
+            // Resumed from a wait: satisfied by `set_bits()`, timed out, or
+            // woken some other way and still waiting out the same timeout.
+            // Anything else recorded for the task is stale.
+            let resumed = sched.take_event_wait(current) == Some(group);
+            let until = if resumed && wait.mask != 0 {
+                if let Some(bits) = wait.result {
+                    *wait = EventWait::NONE;
+                    return Some((WaitResult::Ok, bits));
+                }
+                if wait.until.is_some_and(|t| t <= now) {
+                    *wait = EventWait::NONE;
+                    return Some((WaitResult::TimedOut, state.bits));
+                }
+                wait.until
+            } else {
+                timeout.map(|t| now + t as u64)
+            };
+
+            *wait = EventWait { mask, wait_all, clear_on_exit, until, result: None };
+            if mask == 0 || wait.satisfied_by(state.bits) {
+                *wait = EventWait::NONE;
+                let bits = state.bits;
+                if clear_on_exit {
+                    state.bits &= !mask;
+                }
+                return Some((WaitResult::Ok, bits));
+            }
+            if until.is_some_and(|t| t <= now) {
+                *wait = EventWait::NONE;
+                return Some((WaitResult::TimedOut, state.bits));
+            }
+            match sched.block_on_event_group(group, until) {
+                Some(_) => None,
+                None => {
+                    *wait = EventWait::NONE;
+                    Some((WaitResult::Ok, state.bits))
+                }
+            }
+        })
+    }
+}
+
+impl Default for EventGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::sync::atomic::AtomicU32;
+    use crate::task::{BlockReason, Strategy, TaskConfig, TaskEntry, TaskState};
+    #[cfg(feature = "stack-pool")]
+    use crate::config::STACK_SIZE;
+    #[cfg(feature = "stack-pool")]
+    use crate::stack_pool::tests::leaked_region;
+
+    extern "C" fn spin() -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Create one task per priority, highest first, and start the first.
+    /// Tasks are created in place: moving a scheduler moves their stacks.
+    fn start(sched: &mut Scheduler, priorities: &[u8]) {
+        #[cfg(feature = "stack-pool")]
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        for &priority in priorities {
+            let config = TaskConfig { priority, ..TaskConfig::DEFAULT };
+            sched.create_task(TaskEntry::new(spin), config, Strategy::Cooperative).unwrap();
+        }
+        sched.schedule();
+    }
+
+    /// Advance the scheduler one tick, rescheduling if requested.
+    fn step(sched: &mut Scheduler) {
+        sched.tick();
+        if sched.needs_reschedule {
+            sched.schedule();
+        }
+    }
+
+    #[test]
+    fn test_signal_set_and_check_clear() {
+        let signal = Signal::new();
+        assert!(!signal.check_and_clear());
+
+        signal.set();
+        signal.set();
+        assert!(signal.check_and_clear());
+        assert!(!signal.check_and_clear());
+    }
+
+    #[test]
+    fn test_signal_publishes_data_written_before_set() {
+        static SIGNAL: Signal = Signal::new();
+        static DATA: AtomicU32 = AtomicU32::new(0);
+
+        let producer = std::thread::spawn(|| {
+            for i in 1..=1000 {
+                DATA.store(i, Ordering::Relaxed);
+                SIGNAL.set();
+            }
+        });
+
+        let mut last = 0;
+        while last < 1000 {
+            if SIGNAL.check_and_clear() {
+                let seen = DATA.load(Ordering::Relaxed);
+                assert!(seen >= last);
+                last = seen;
+            }
+        }
+        producer.join().unwrap();
+    }
+
+    #[test]
+    fn test_seq_u64_reads_never_tear() {
+        // Start just below a carry into the high word and flip the top
+        // bits on odd stores, so a torn read pairs mismatched halves
+        const START: u64 = 0xFFFF_FFF0;
+        const STORES: u64 = 200_000;
+        static VALUE: SeqU64 = SeqU64::new(START);
+
+        let writer = std::thread::spawn(|| {
+            for i in 1..=STORES {
+                let v = START + i;
+                VALUE.store(if i % 2 == 0 { v } else { v | 0xFFFF_0000_0000_0000 });
+            }
+        });
+
+        let mut last = START;
+        loop {
+            let raw = VALUE.load();
+            let v = raw & !0xFFFF_0000_0000_0000;
+            let flagged = raw >> 48 == 0xFFFF;
+            assert!((START..=START + STORES).contains(&v), "torn read {raw:#x}");
+            assert_eq!(flagged, v % 2 == 1, "torn read {raw:#x}");
+            assert!(v >= last, "went backwards: {v:#x} < {last:#x}");
+            last = v;
+            if v == START + STORES {
+                break;
+            }
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn test_critical_sections_nest() {
+        let masked = || hw::MASKED.load(Ordering::Relaxed);
+        assert!(!in_critical());
+
+        let outer = enter();
+        assert!(masked());
+        assert_eq!(nesting_depth(), 1);
+
+        critical_section(|_cs| {
+            let inner = enter();
+            assert_eq!(nesting_depth(), 3);
+            drop(inner);
+            // An inner exit must not unmask interrupts
+            assert!(masked());
+        });
+        assert_eq!(nesting_depth(), 1);
+        assert!(masked());
+
+        drop(outer);
+        assert!(!in_critical());
+        assert!(!masked());
+    }
+
+    #[test]
+    fn test_event_group_wakes_any_and_all_waiters() {
+        let group = EventGroup::new();
+        let mut sched = Scheduler::new();
+        start(&mut sched, &[5, 4, 1]);
+        let (any, all) = (0, 1);
+
+        assert_eq!(sched.current(), any);
+        assert_eq!(group.wait_bits(&mut sched, 0b0110, false, false, None), None);
+        assert_eq!(sched.schedule(), all);
+        assert_eq!(group.wait_bits(&mut sched, 0b0101, true, false, None), None);
+        assert_eq!(sched.tasks[all].block_reason, Some(BlockReason::EventGroup(group.address())));
+        sched.schedule();
+
+        // Neither condition holds yet
+        assert_eq!(group.set_bits(&mut sched, 0b0001), 0b0001);
+        assert_eq!(sched.tasks[any].state, TaskState::Blocked);
+        assert_eq!(sched.tasks[all].state, TaskState::Blocked);
+
+        // One of `any`'s bits; `all` still lacks 0b0100
+        group.set_bits(&mut sched, 0b0010);
+        assert_eq!(sched.tasks[any].state, TaskState::Ready);
+        assert_eq!(sched.tasks[all].state, TaskState::Blocked);
+
+        group.set_bits(&mut sched, 0b0100);
+        assert_eq!(sched.tasks[all].state, TaskState::Ready);
+
+        // Each collects the bits that satisfied it; none were cleared
+        assert_eq!(sched.schedule(), any);
+        assert_eq!(group.wait_bits(&mut sched, 0b0110, false, false, None), Some((WaitResult::Ok, 0b0011)));
+        sched.delay_current(10);
+        assert_eq!(sched.schedule(), all);
+        assert_eq!(group.wait_bits(&mut sched, 0b0101, true, false, None), Some((WaitResult::Ok, 0b0111)));
+        assert_eq!(group.bits(), 0b0111);
+    }
+
+    #[test]
+    fn test_event_group_clear_on_exit() {
+        let group = EventGroup::new();
+        let mut sched = Scheduler::new();
+        start(&mut sched, &[5, 4, 1]);
+        let (clearing, keeping) = (0, 1);
+
+        // Already satisfied: returns at once, clearing only its own bits
+        group.set_bits(&mut sched, 0b011);
+        assert_eq!(group.wait_bits(&mut sched, 0b001, true, true, None), Some((WaitResult::Ok, 0b011)));
+        assert_eq!(group.bits(), 0b010);
+
+        // Two waiters on the same bit are both released before it clears
+        assert_eq!(group.wait_bits(&mut sched, 0b100, false, true, None), None);
+        assert_eq!(sched.schedule(), keeping);
+        assert_eq!(group.wait_bits(&mut sched, 0b100, false, false, None), None);
+        sched.schedule();
+        assert_eq!(group.set_bits(&mut sched, 0b100), 0b010);
+        assert_eq!(sched.tasks[clearing].state, TaskState::Ready);
+        assert_eq!(sched.tasks[keeping].state, TaskState::Ready);
+
+        assert_eq!(sched.schedule(), clearing);
+        assert_eq!(group.wait_bits(&mut sched, 0b100, false, true, None), Some((WaitResult::Ok, 0b110)));
+        sched.delay_current(10);
+        assert_eq!(sched.schedule(), keeping);
+        assert_eq!(group.wait_bits(&mut sched, 0b100, false, false, None), Some((WaitResult::Ok, 0b110)));
+
+        // Clearing by hand reports the bits beforehand
+        assert_eq!(group.clear_bits(0b010), 0b010);
+        assert_eq!(group.bits(), 0);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_event_group_wait_times_out_exactly() {
+        let group = EventGroup::new();
+        let mut sched = Scheduler::new();
+        start(&mut sched, &[5, 1]);
+        let waiter = 0;
+
+        assert_eq!(group.wait_bits(&mut sched, 0b1, false, false, Some(5)), None);
+        sched.schedule();
+        for _ in 0..4 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.tasks[waiter].state, TaskState::Blocked);
+        step(&mut sched);
+        assert_eq!(sched.current(), waiter);
+
+        // Set after the timeout but before the task resumes: still late
+        group.set_bits(&mut sched, 0b1);
+        assert_eq!(group.wait_bits(&mut sched, 0b1, false, false, Some(5)), Some((WaitResult::TimedOut, 0b1)));
+        assert_eq!(sched.timeout_count(waiter), Some(1));
+
+        // A zero timeout only polls
+        assert_eq!(group.wait_bits(&mut sched, 0b10, false, false, Some(0)), Some((WaitResult::TimedOut, 0b1)));
+        assert_eq!(sched.tasks[waiter].state, TaskState::Running);
+        assert_eq!(sched.timeout_count(waiter), Some(1));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_event_group_set_before_timeout() {
+        let group = EventGroup::new();
+        let mut sched = Scheduler::new();
+        start(&mut sched, &[5, 1]);
+        let waiter = 0;
+
+        assert_eq!(group.wait_bits(&mut sched, 0b1, false, true, Some(5)), None);
+        sched.schedule();
+        step(&mut sched);
+        step(&mut sched);
+        group.set_bits(&mut sched, 0b1);
+        assert_eq!(sched.tasks[waiter].wake_tick, None);
+        sched.schedule();
+        assert_eq!(group.wait_bits(&mut sched, 0b1, false, true, Some(5)), Some((WaitResult::Ok, 0b1)));
+
+        // The stale timeout never fires
+        for _ in 0..10 {
+            step(&mut sched);
+        }
+        assert_eq!(sched.timeout_count(waiter), Some(0));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "cooperative-only", ignore = "relies on preemption")]
+    fn test_event_group_forgets_wait_of_restarted_task() {
+        let (old, new) = (EventGroup::new(), EventGroup::new());
+        let mut sched = Scheduler::new();
+        start(&mut sched, &[5, 1]);
+        let waiter = 0;
+
+        assert_eq!(old.wait_bits(&mut sched, 0b1, false, false, None), None);
+        sched.schedule();
+        sched.restart_task(waiter).unwrap();
+        assert_eq!(sched.schedule(), waiter);
+
+        // Now waiting on another group: setting the old one leaves it be
+        assert_eq!(new.wait_bits(&mut sched, 0b1, false, false, None), None);
+        sched.schedule();
+        old.set_bits(&mut sched, 0b1);
+        assert_eq!(sched.tasks[waiter].state, TaskState::Blocked);
+        new.set_bits(&mut sched, 0b1);
+        assert_eq!(sched.schedule(), waiter);
+        assert_eq!(new.wait_bits(&mut sched, 0b1, false, false, None), Some((WaitResult::Ok, 0b1)));
+
+        // Nor does a fresh wait on the old group find a stale outcome
+        old.clear_bits(0b1);
+        assert_eq!(old.wait_bits(&mut sched, 0b1, false, false, None), None);
+        assert_eq!(sched.tasks[waiter].block_reason, Some(BlockReason::EventGroup(old.address())));
+    }
 }
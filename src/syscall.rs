@@ -0,0 +1,133 @@
+//! # Supervisor Calls
+//!
+//! Kernel entry from Thread mode via `svc #n`. Tasks never touch the
+//! scheduler themselves: the SVC number (encoded in the instruction) picks
+//! the operation, an argument travels in R0, and the `SVCall` handler in
+//! the arch port runs [`dispatch`] in Handler mode.
+//!
+//! | SVC | Call                 | R0      |
+//! |-----|----------------------|---------|
+//! | #0  | [`Syscall::Yield`]   | unused  |
+//! | #1  | [`Syscall::Delay`]   | ticks   |
+//! | #2  | [`Syscall::YieldTo`] | task id |
+
+use crate::scheduler::Scheduler;
+
+/// A kernel operation reachable through `svc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Syscall {
+    /// Give up the CPU, as `Scheduler::yield_current()`.
+    Yield = 0,
+    /// Block for R0 ticks, as `Scheduler::delay_current()`.
+    Delay = 1,
+    /// Yield to task R0, as `Scheduler::yield_to()`.
+    YieldTo = 2,
+}
+
+impl Syscall {
+    /// Decode an SVC immediate. Unknown numbers yield `None`.
+    pub const fn from_number(number: u8) -> Option<Self> {
+        match number {
+            0 => Some(Self::Yield),
+            1 => Some(Self::Delay),
+            2 => Some(Self::YieldTo),
+            _ => None,
+        }
+    }
+
+    /// The SVC immediate that invokes this call.
+    pub const fn number(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Perform supervisor call `number` with argument `arg`.
+///
+/// Unknown numbers, and a `YieldTo` naming no task, are ignored.
+///
+/// # Returns
+/// `true` if a context switch should be pended.
+pub fn dispatch(scheduler: &mut Scheduler, number: u8, arg: u32) -> bool {
+    match Syscall::from_number(number) {
+        Some(Syscall::Yield) => scheduler.yield_current(),
+        Some(Syscall::Delay) => scheduler.delay_current(arg),
+        Some(Syscall::YieldTo) => {
+            if scheduler.yield_to(arg as usize).is_err() {
+                return false;
+            }
+        }
+        None => return false,
+    }
+    // Deferred while preemption is locked; `preempt_enable()` pends it
+    scheduler.needs_reschedule && !scheduler.preemption_locked()
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{BlockReason, Strategy, TaskConfig, TaskEntry, TaskState};
+    #[cfg(feature = "stack-pool")]
+    use crate::config::{MAX_TASKS, STACK_SIZE};
+    #[cfg(feature = "stack-pool")]
+    use crate::stack_pool::tests::leaked_region;
+
+    extern "C" fn spin() -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    const TEST_ENTRY: TaskEntry = TaskEntry::new(spin);
+
+    fn started() -> (Scheduler, usize) {
+        let mut sched = Scheduler::new();
+        #[cfg(feature = "stack-pool")]
+        sched.set_stack_pool(leaked_region(MAX_TASKS * STACK_SIZE + 8));
+        let id = sched.create_task(TEST_ENTRY, TaskConfig::DEFAULT, Strategy::Cooperative).unwrap();
+        sched.start_first();
+        (sched, id)
+    }
+
+    #[test]
+    fn test_svc_numbers_round_trip() {
+        for call in [Syscall::Yield, Syscall::Delay, Syscall::YieldTo] {
+            assert_eq!(Syscall::from_number(call.number()), Some(call));
+        }
+        assert_eq!(Syscall::from_number(3), None);
+        assert_eq!(Syscall::from_number(0xFF), None);
+    }
+
+    #[test]
+    fn test_dispatch_routes_by_number() {
+        let (mut sched, id) = started();
+        assert!(dispatch(&mut sched, 0, 0));
+        assert_eq!(sched.tasks[id].state, TaskState::Ready);
+        assert_eq!(sched.tasks[id].payoff.voluntary_yields, 1);
+
+        let (mut sched, id) = started();
+        assert!(dispatch(&mut sched, 1, 7));
+        assert_eq!(sched.tasks[id].state, TaskState::Blocked);
+        assert_eq!(sched.tasks[id].block_reason, Some(BlockReason::Delay));
+        assert_eq!(sched.tasks[id].wake_tick, Some(7));
+
+        // Unknown calls change nothing
+        let (mut sched, id) = started();
+        assert!(!dispatch(&mut sched, 9, 7));
+        assert_eq!(sched.tasks[id].state, TaskState::Running);
+        assert!(!sched.needs_reschedule);
+    }
+
+    #[test]
+    fn test_dispatch_defers_yield_under_preempt_lock() {
+        let (mut sched, id) = started();
+        sched.preempt_disable();
+        assert!(!dispatch(&mut sched, Syscall::Yield.number(), 0));
+        assert_eq!(sched.tasks[id].state, TaskState::Running);
+        assert!(sched.preempt_enable());
+    }
+}
@@ -15,7 +15,15 @@
 //! the system toward Nash equilibrium where no task benefits from unilaterally
 //! changing its strategy.
 
-use crate::config::{STACK_SIZE, DEFAULT_TIME_SLICE};
+use core::time::Duration;
+
+#[cfg(not(feature = "stack-pool"))]
+use crate::config::STACK_SIZE;
+use crate::config::{
+    DEFAULT_TIME_SLICE, MAX_TIME_SLICE, TICK_HZ, NON_YIELD_THRESHOLD, STACK_CANARY,
+    PRIORITY_LEVELS, MAX_CORES,
+};
+use crate::error::KernelError;
 
 // ---------------------------------------------------------------------------
 // Task state machine
@@ -50,6 +58,156 @@ pub enum TaskState {
     Terminated,
 }
 
+/// Why a task is in the `Blocked` state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockReason {
+    /// Sleeping until `wake_tick` (timed delay).
+    Delay,
+    /// Waiting for the predecessor task to call `signal_done()` this period.
+    Predecessor,
+    /// A bottom-half handler waiting in `wait_for_deferral()` for an ISR
+    /// to hand it work via `defer_to()`.
+    Deferral,
+    /// Waiting in `wait_notification()` for a `notify()`.
+    Notification,
+    /// Held until its next period boundary (`wake_tick`) after overrunning
+    /// its deadline; see [`TaskConfig::enforce_deadline`].
+    DeadlineOverrun,
+    /// Used up its execution budget; held until the budget replenishes
+    /// at the next budget period. See [`TaskConfig::budget_ticks`].
+    BudgetExhausted,
+    /// Waiting in `EventGroup::wait_bits()` for flag bits to be set in
+    /// the group at this address.
+    EventGroup(usize),
+}
+
+/// Why a blocked task was made Ready again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeReason {
+    /// The wait ran to its natural timeout.
+    Timeout,
+    /// Another task cancelled the wait early via `cancel_delay()`.
+    Cancelled,
+    /// An ISR woke the task directly via `wake_and_switch()`.
+    Interrupt,
+    /// The event the task was waiting for arrived (e.g. `defer_to()`).
+    Signalled,
+}
+
+/// Outcome of a blocking wait with an optional timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitResult {
+    /// The awaited event arrived.
+    Ok,
+    /// The timeout expired first.
+    TimedOut,
+}
+
+/// Drift-free release schedule for a periodic task loop, used with
+/// `kernel::delay_until_next()`.
+///
+/// Release times are absolute ticks advanced by exactly one period each
+/// iteration, so time spent working does not shift later releases the way
+/// a relative `delay_ticks(period)` would.
+///
+/// ```ignore
+/// let mut release = PeriodicRelease::new(kernel::tick_count(), 100).skip_missed();
+/// loop {
+///     control_step();
+///     let missed = kernel::delay_until_next(&mut release);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodicRelease {
+    /// Absolute tick of the next release.
+    next_wake: u64,
+    /// Period in ticks.
+    period: u32,
+    /// Whether releases already in the past are dropped rather than run
+    /// back-to-back.
+    skip_missed: bool,
+}
+
+impl PeriodicRelease {
+    /// Releases every `period` ticks (at least 1) after tick `start`.
+    pub const fn new(start: u64, period: u32) -> Self {
+        Self {
+            next_wake: start,
+            period: if period == 0 { 1 } else { period },
+            skip_missed: false,
+        }
+    }
+
+    /// When the task falls behind, jump to the first release still in the
+    /// future instead of catching up on every missed one.
+    pub const fn skip_missed(mut self) -> Self {
+        self.skip_missed = true;
+        self
+    }
+
+    /// Absolute tick of the next release.
+    pub fn next_wake(&self) -> u64 {
+        self.next_wake
+    }
+
+    /// Move to the next release, given the current tick `now`.
+    ///
+    /// # Returns
+    /// How many releases are already due, i.e. how far behind the task
+    /// has fallen (0 when on time). These are skipped if `skip_missed()`
+    /// was set; otherwise the task runs them back-to-back to catch up.
+    pub fn advance(&mut self, now: u64) -> u32 {
+        let period = self.period as u64;
+        self.next_wake += period;
+        if self.next_wake > now {
+            return 0;
+        }
+        let behind = (now - self.next_wake) / period + 1;
+        if self.skip_missed {
+            self.next_wake += behind * period;
+        }
+        behind.min(u32::MAX as u64) as u32
+    }
+}
+
+/// How strictly a task's saved stack pointer is validated on each context
+/// switch. Stricter levels cost a few cycles per switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackCheck {
+    /// Trust the stack pointer (fastest).
+    None,
+    /// Verify the stack pointer lies within the task's own stack.
+    BoundsCheck,
+    /// Bounds check, plus verify the canary word at the stack base is
+    /// intact — catches overflows even when the SP itself looks plausible.
+    ///
+    /// The canary is only sampled at switch time, so an overflow that
+    /// skips over the canary word without writing it (a large frame
+    /// reserved but only partly touched) goes unnoticed, as does one that
+    /// is only in flight between two switches.
+    CanaryCheck,
+}
+
+/// A temporary priority boost one task has given another it depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Donation {
+    /// Task that made the donation and may revoke it.
+    pub donor: usize,
+    /// Priority levels added to the recipient's base priority.
+    pub amount: u8,
+    /// Tick at which the donation lapses. `None` lasts until revoked.
+    pub expires_tick: Option<u64>,
+}
+
+/// Stack corruption detected by `TaskControlBlock::check_stack()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackFault {
+    /// The stack pointer lies outside the task's stack.
+    OutOfBounds,
+    /// The canary word at the stack base was overwritten.
+    CanaryCorrupted,
+}
+
 // ---------------------------------------------------------------------------
 // Strategy model
 // ---------------------------------------------------------------------------
@@ -59,7 +217,8 @@ pub enum TaskState {
 /// This models the task's current "move" in the iterated Prisoner's Dilemma.
 /// The scheduler observes task behavior and may override this based on
 /// actual runtime metrics (e.g., a task claiming to be cooperative but
-/// consuming excessive CPU will be reclassified).
+/// consuming excessive CPU will be reclassified, see
+/// `game::reclassify_strategies`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Strategy {
     /// Task cooperates: yields voluntarily, respects soft deadlines,
@@ -69,6 +228,74 @@ pub enum Strategy {
     /// may overrun time slices. Receives short-term priority but
     /// accumulates penalties over time.
     Selfish,
+    /// Task mirrors the system: each evaluation window it cooperates if
+    /// the majority cooperated in the last one and defects otherwise,
+    /// opening with cooperation. Its move is `tft_cooperating`; the
+    /// strategy itself is never switched by payoff hysteresis.
+    TitForTat,
+}
+
+// ---------------------------------------------------------------------------
+// Task entry point
+// ---------------------------------------------------------------------------
+
+/// A task's entry point: an `extern "C"` function that never returns.
+///
+/// The initial stack frame branches to it with the AAPCS, and there is
+/// nothing for it to return to, so both halves of the contract matter.
+/// The only safe constructors take an `extern "C" fn() -> !`, which the
+/// compiler checks at the definition: the body must diverge (typically a
+/// `loop`). Build one with [`task_entry!`](crate::task_entry) or
+/// [`TaskEntry::new`].
+///
+/// ```ignore
+/// extern "C" fn blink() -> ! {
+///     loop {
+///         toggle_led();
+///         kernel::delay(500);
+///     }
+/// }
+///
+/// kernel::create_task(task_entry!(blink), config, Strategy::Cooperative)?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TaskEntry(extern "C" fn() -> !);
+
+impl TaskEntry {
+    /// Wrap a never-returning `extern "C"` function.
+    #[inline]
+    pub const fn new(entry: extern "C" fn() -> !) -> Self {
+        Self(entry)
+    }
+
+    /// The wrapped function.
+    #[inline]
+    pub const fn as_fn(self) -> extern "C" fn() -> ! {
+        self.0
+    }
+
+    /// Address of the entry function, as loaded into the initial PC.
+    #[inline]
+    pub fn address(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Build a [`TaskEntry`] from the path of an `extern "C" fn() -> !`.
+///
+/// A function with any other signature (a Rust-ABI function, one taking
+/// arguments, or one that can return) is a compile error.
+///
+/// ```ignore
+/// use eqos::task_entry;
+///
+/// kernel::create_task(task_entry!(sensor_task), config, Strategy::Cooperative)?;
+/// ```
+#[macro_export]
+macro_rules! task_entry {
+    ($entry:path) => {
+        $crate::task::TaskEntry::new($entry)
+    };
 }
 
 // ---------------------------------------------------------------------------
@@ -82,7 +309,7 @@ pub enum Strategy {
 /// dynamic payoff metrics to make scheduling decisions.
 #[derive(Debug, Clone, Copy)]
 pub struct TaskConfig {
-    /// Base priority (higher = more important). Range: 0–255.
+    /// Base priority (higher = more important). Range: `0..PRIORITY_LEVELS`.
     /// This is the static priority before game-theory adjustments.
     pub priority: u8,
 
@@ -101,20 +328,271 @@ pub struct TaskConfig {
     /// Extensible to multi-core by setting multiple bits.
     pub affinity_mask: u32,
 
-    /// Time slice in ticks for this task. If 0, uses `DEFAULT_TIME_SLICE`.
+    /// Time slice in ticks for this task. If 0, uses `time_slice_ms`
+    /// or, failing that, `DEFAULT_TIME_SLICE`.
     pub time_slice: u32,
+
+    /// Time slice in milliseconds, converted to ticks at the scheduler's
+    /// runtime tick rate. Ignored when `time_slice` is non-zero, so the
+    /// tick-based field remains available for low-level control.
+    pub time_slice_ms: u32,
+
+    /// Marks a deadline-critical "anchor" task that must always run. The
+    /// game engine never lowers an anchor below its base priority, runnable
+    /// anchors are selected ahead of every other task regardless of
+    /// starvation boosts, and schedulability analysis treats anchors as the
+    /// mandatory load.
+    pub anchor: bool,
+
+    /// Enforce the deadline at its exact instant rather than at the next
+    /// tick. When a deadline compare timer is installed (see
+    /// `kernel::set_deadline_timer()`), it is armed for the earliest
+    /// outstanding hard deadline and a miss is recorded the moment it
+    /// fires. Ignored for tasks without a deadline.
+    pub hard_deadline: bool,
+
+    /// Stop the task from running past a missed deadline: it is Blocked
+    /// from the miss until the next period boundary, one release window
+    /// later, instead of consuming CPU other tasks need. Ignored for tasks
+    /// without a deadline.
+    pub enforce_deadline: bool,
+
+    /// Execution budget: the task may run at most this many ticks in each
+    /// `budget_period`, whatever its priority or payoff. Once it is used
+    /// up the task is Blocked until the next budget period begins. `0`
+    /// means unlimited.
+    pub budget_ticks: u32,
+
+    /// Length in ticks of the period over which `budget_ticks` applies.
+    /// Budget periods run on wall-clock time from task creation.
+    pub budget_period: u32,
+
+    /// Ticks without CPU before this task is boosted as starving. `0`
+    /// uses the scheduler's global threshold (`STARVATION_THRESHOLD`,
+    /// scaled with the number of active tasks).
+    pub starvation_threshold: u32,
+
+    /// Name shown in debug output and statistics. Empty by default; not
+    /// required to be unique.
+    pub name: &'static str,
 }
 
 impl TaskConfig {
-    /// Returns the effective time slice, falling back to the system default.
+    /// Default configuration: priority 0, no deadline or WCET, core 0,
+    /// default time slice. Use with struct update syntax
+    /// (`..TaskConfig::DEFAULT`) to fill in unused fields.
+    pub const DEFAULT: Self = Self {
+        priority: 0,
+        deadline_ticks: 0,
+        wcet_ticks: 0,
+        affinity_mask: 0x01,
+        time_slice: 0,
+        time_slice_ms: 0,
+        anchor: false,
+        hard_deadline: false,
+        enforce_deadline: false,
+        budget_ticks: 0,
+        budget_period: 0,
+        starvation_threshold: 0,
+        name: "",
+    };
+
+    /// Returns the effective time slice in ticks at the given tick rate.
+    ///
+    /// Precedence: `time_slice` (ticks) if non-zero, then `time_slice_ms`
+    /// converted at `tick_hz` (rounded up, minimum 1 tick), then
+    /// `DEFAULT_TIME_SLICE`.
     #[inline]
-    pub const fn effective_time_slice(&self) -> u32 {
+    pub const fn effective_time_slice(&self, tick_hz: u32) -> u32 {
         if self.time_slice > 0 {
             self.time_slice
+        } else if self.time_slice_ms > 0 {
+            let ticks = (self.time_slice_ms as u64 * tick_hz as u64).div_ceil(1000);
+            if ticks == 0 {
+                1
+            } else if ticks > u32::MAX as u64 {
+                u32::MAX
+            } else {
+                ticks as u32
+            }
         } else {
             DEFAULT_TIME_SLICE
         }
     }
+
+    /// Check the configuration before a task is created from it.
+    ///
+    /// # Returns
+    /// - `Err(KernelError::InvalidPriority)` — `priority` is not below
+    ///   `PRIORITY_LEVELS`
+    /// - `Err(KernelError::InvalidConfig)` — the time slice at `tick_hz`
+    ///   exceeds `MAX_TIME_SLICE`, `wcet_ticks` exceeds a non-zero
+    ///   deadline, `affinity_mask` names none of the `MAX_CORES` cores, or
+    ///   a non-zero `budget_ticks` exceeds its `budget_period`
+    pub fn validate(&self, tick_hz: u32) -> Result<(), KernelError> {
+        if self.priority as usize >= PRIORITY_LEVELS {
+            return Err(KernelError::InvalidPriority);
+        }
+        let all_cores = (1u32 << MAX_CORES) - 1;
+        if self.effective_time_slice(tick_hz) > MAX_TIME_SLICE
+            || (self.deadline_ticks > 0 && self.wcet_ticks > self.deadline_ticks)
+            || self.affinity_mask & all_cores == 0
+            || (self.budget_ticks > 0 && self.budget_ticks > self.budget_period)
+        {
+            return Err(KernelError::InvalidConfig);
+        }
+        Ok(())
+    }
+}
+
+/// Convert a duration to whole ticks at `tick_hz`, rounding up. Any
+/// non-zero duration is at least one tick; saturates at `u32::MAX`.
+pub fn duration_to_ticks(duration: Duration, tick_hz: u32) -> u32 {
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        return 0;
+    }
+    let ticks = (nanos * tick_hz as u128).div_ceil(1_000_000_000);
+    ticks.clamp(1, u32::MAX as u128) as u32
+}
+
+impl TaskConfig {
+    /// Start building a configuration from real-time `Duration`s instead
+    /// of raw ticks. See [`TaskConfigBuilder`].
+    pub const fn builder() -> TaskConfigBuilder {
+        TaskConfigBuilder {
+            priority: 0,
+            affinity_mask: 0x01,
+            deadline: None,
+            period: None,
+            wcet: None,
+            time_slice: None,
+            anchor: false,
+            hard_deadline: false,
+            enforce_deadline: false,
+            starvation_threshold: None,
+            name: "",
+        }
+    }
+}
+
+/// Builds a tick-based [`TaskConfig`] from `Duration` inputs, so timing is
+/// written in real time and converted at the actual tick rate rather than
+/// assuming 1 tick = 1 ms.
+///
+/// ```ignore
+/// let config = TaskConfig::builder()
+///     .priority(3)
+///     .period(Duration::from_millis(100))
+///     .wcet(Duration::from_millis(5))
+///     .build(TICK_HZ);
+/// ```
+///
+/// Durations are rounded up to whole ticks (minimum one tick).
+#[derive(Debug, Clone, Copy)]
+pub struct TaskConfigBuilder {
+    priority: u8,
+    affinity_mask: u32,
+    deadline: Option<Duration>,
+    period: Option<Duration>,
+    wcet: Option<Duration>,
+    time_slice: Option<Duration>,
+    anchor: bool,
+    hard_deadline: bool,
+    enforce_deadline: bool,
+    starvation_threshold: Option<Duration>,
+    name: &'static str,
+}
+
+impl TaskConfigBuilder {
+    /// Base priority. See [`TaskConfig::priority`].
+    pub const fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// CPU affinity bitmask. See [`TaskConfig::affinity_mask`].
+    pub const fn affinity_mask(mut self, mask: u32) -> Self {
+        self.affinity_mask = mask;
+        self
+    }
+
+    /// Relative deadline within each period.
+    pub const fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Activation period. The scheduler measures periods by the deadline,
+    /// so this is used as the deadline when no explicit `deadline()` is
+    /// given (implicit-deadline task).
+    pub const fn period(mut self, period: Duration) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Worst-case execution time.
+    pub const fn wcet(mut self, wcet: Duration) -> Self {
+        self.wcet = Some(wcet);
+        self
+    }
+
+    /// Time slice. Without it the task uses `DEFAULT_TIME_SLICE`.
+    pub const fn time_slice(mut self, slice: Duration) -> Self {
+        self.time_slice = Some(slice);
+        self
+    }
+
+    /// Mark the task as an anchor. See [`TaskConfig::anchor`].
+    pub const fn anchor(mut self) -> Self {
+        self.anchor = true;
+        self
+    }
+
+    /// Enforce the deadline at its exact instant. See
+    /// [`TaskConfig::hard_deadline`].
+    pub const fn hard_deadline(mut self) -> Self {
+        self.hard_deadline = true;
+        self
+    }
+
+    /// Block the task from a missed deadline to its next period. See
+    /// [`TaskConfig::enforce_deadline`].
+    pub const fn enforce_deadline(mut self) -> Self {
+        self.enforce_deadline = true;
+        self
+    }
+
+    /// Longest wait for the CPU before the task is boosted. See
+    /// [`TaskConfig::starvation_threshold`].
+    pub const fn starvation_threshold(mut self, threshold: Duration) -> Self {
+        self.starvation_threshold = Some(threshold);
+        self
+    }
+
+    /// Debug name. See [`TaskConfig::name`].
+    pub const fn name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Produce the tick-based configuration at `tick_hz`.
+    pub fn build(self, tick_hz: u32) -> TaskConfig {
+        let ticks = |d: Option<Duration>| d.map_or(0, |d| duration_to_ticks(d, tick_hz));
+        TaskConfig {
+            priority: self.priority,
+            deadline_ticks: ticks(self.deadline.or(self.period)),
+            wcet_ticks: ticks(self.wcet),
+            affinity_mask: self.affinity_mask,
+            time_slice: ticks(self.time_slice),
+            anchor: self.anchor,
+            hard_deadline: self.hard_deadline,
+            enforce_deadline: self.enforce_deadline,
+            starvation_threshold: ticks(self.starvation_threshold),
+            name: self.name,
+            ..TaskConfig::DEFAULT
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -153,6 +631,30 @@ pub struct PayoffMetrics {
     /// Consecutive overruns incur escalating penalties.
     pub consecutive_overruns: u32,
 
+    /// WCET overruns on jobs that still met their deadline, recorded
+    /// instead of `overruns` under `OverrunPolicy::SoftIfMet`. Carries no
+    /// payoff penalty.
+    pub soft_overruns: u32,
+
+    /// Times this task, by becoming runnable or being boosted, forced a
+    /// still-running task off the CPU.
+    pub preemptions_caused: u32,
+
+    /// Blocking waits on an event that ended by timing out rather than by
+    /// the event arriving. Plain delays do not count.
+    pub timeouts: u32,
+
+    /// Deadlines met, decayed at every game evaluation (see
+    /// `decay_recent()`) so that the payoff follows recent behaviour. The
+    /// counters above keep the lifetime totals.
+    pub recent_deadlines_met: u32,
+
+    /// Deadlines missed, decayed like `recent_deadlines_met`.
+    pub recent_deadlines_missed: u32,
+
+    /// Voluntary yields, decayed like `recent_deadlines_met`.
+    pub recent_yields: u32,
+
     /// Cooperation score in fixed-point (×100).
     /// Starts at 100 (neutral). Increases for cooperative behavior,
     /// decreases for selfish behavior. Range: 0–500.
@@ -172,6 +674,38 @@ pub struct PayoffMetrics {
     /// Ticks since this task last received any CPU time.
     /// Used for starvation detection.
     pub ticks_since_last_run: u32,
+
+    /// CPU ticks consumed since this task last yielded voluntarily.
+    /// Used for non-yielding (pure selfish) detection.
+    pub ticks_since_last_yield: u32,
+
+    /// Consecutive ticks the task has held the CPU without being switched
+    /// out, yielding, or blocking.
+    pub run_streak: u32,
+
+    /// Longest `run_streak` seen in the current evaluation window
+    /// (including any streak still in progress). A high value with no
+    /// yields is the signature of a CPU monopolizer.
+    pub max_run_streak: u32,
+
+    /// Strategy switches in the current oscillation-detection window.
+    pub recent_switches: u32,
+
+    /// Strategy updates elapsed in the current oscillation-detection window.
+    pub switch_window: u32,
+
+    /// Remaining strategy updates for which the strategy is locked after
+    /// oscillation was detected. `0` means the task adapts freely.
+    pub strategy_lock: u32,
+
+    /// Times the game engine reclassified the task's declared strategy
+    /// from its observed behaviour.
+    pub reclassifications: u32,
+
+    /// Processor cycles spent Running, measured with the DWT cycle counter
+    /// between context switches. Only accumulated with the
+    /// `cycle-accounting` feature; otherwise stays 0.
+    pub cpu_cycles_used: u64,
 }
 
 impl PayoffMetrics {
@@ -184,20 +718,63 @@ impl PayoffMetrics {
             voluntary_yields: 0,
             overruns: 0,
             consecutive_overruns: 0,
+            soft_overruns: 0,
+            preemptions_caused: 0,
+            timeouts: 0,
+            recent_deadlines_met: 0,
+            recent_deadlines_missed: 0,
+            recent_yields: 0,
             cooperation_score: 100,
             payoff: 0,
             previous_payoff: 0,
             decline_streak: 0,
             ticks_since_last_run: 0,
+            ticks_since_last_yield: 0,
+            run_streak: 0,
+            max_run_streak: 0,
+            recent_switches: 0,
+            switch_window: 0,
+            strategy_lock: 0,
+            reclassifications: 0,
+            cpu_cycles_used: 0,
         }
     }
 
+    /// Zero the lifetime CPU usage counters.
+    pub fn reset_cpu_accounting(&mut self) {
+        self.cpu_ticks_used = 0;
+        self.cpu_cycles_used = 0;
+    }
+
+    /// Age the recent counters the payoff is computed from, dividing them
+    /// by `2^shift` (rounding down).
+    pub fn decay_recent(&mut self, shift: u32) {
+        self.recent_deadlines_met >>= shift;
+        self.recent_deadlines_missed >>= shift;
+        self.recent_yields >>= shift;
+    }
+
+    /// Forget the payoff trend and cooperation history, returning them to
+    /// their neutral starting values. Event counters are kept.
+    pub fn reset_payoff_trend(&mut self) {
+        self.payoff = 0;
+        self.previous_payoff = 0;
+        self.decline_streak = 0;
+        self.cooperation_score = 100;
+    }
+
     /// Reset all metrics to initial values. Called on task restart.
     pub fn reset(&mut self) {
         *self = Self::new();
     }
 }
 
+impl Default for PayoffMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Task Control Block
 // ---------------------------------------------------------------------------
@@ -210,9 +787,10 @@ impl PayoffMetrics {
 ///
 /// ## Memory Layout
 ///
-/// Each TCB includes an inline stack (`[u8; STACK_SIZE]`). The
-/// `stack_pointer` field points into this stack and is updated on
-/// every context switch.
+/// Each TCB includes an inline stack (`[u8; STACK_SIZE]`), or with the
+/// `stack-pool` feature a reference to a stack carved from an external
+/// pool. The `stack_pointer` field points into this stack and is updated
+/// on every context switch.
 pub struct TaskControlBlock {
     /// Unique task identifier (index in the scheduler's task array).
     pub id: usize,
@@ -226,27 +804,159 @@ pub struct TaskControlBlock {
     /// Current game-theory strategy.
     pub strategy: Strategy,
 
+    /// A `TitForTat` task's move for the current evaluation window:
+    /// whether it cooperates. Unused by other strategies.
+    pub tft_cooperating: bool,
+
+    /// Whether the strategy was pinned with `set_strategy()`, so the game
+    /// engine never switches it.
+    pub strategy_locked: bool,
+
     /// Runtime payoff metrics for the game engine.
     pub payoff: PayoffMetrics,
 
+    /// Entry function the task's stack frame is built from. Kept so the
+    /// task can be restarted (possibly at a new entry, see `set_entry`).
+    pub entry: Option<TaskEntry>,
+
     /// Saved stack pointer (PSP). Updated on context switch.
     /// Points into `self.stack`.
     pub stack_pointer: *mut u32,
 
-    /// Per-task stack memory. Aligned to 8 bytes as required by ARM AAPCS.
-    #[repr(align(8))]
+    /// Per-task stack memory. `init_task_stack()` aligns the initial frame
+    /// to 8 bytes as required by ARM AAPCS.
+    #[cfg(not(feature = "stack-pool"))]
     pub stack: [u8; STACK_SIZE],
 
+    /// Base and length of the task's stack in the external pool. Null
+    /// until the task is created.
+    #[cfg(feature = "stack-pool")]
+    pub stack: (*mut u8, usize),
+
     /// Remaining ticks in the current time slice.
     pub ticks_remaining: u32,
 
-    /// Total ticks this task has been in the Running state.
+    /// Total ticks this task has been in the Running state. For the idle
+    /// task, the ticks no task was running.
     pub total_ticks: u32,
 
-    /// Period tracking: ticks since the start of the current period.
-    /// Used for deadline evaluation on periodic tasks.
+    /// Like `total_ticks`, but only since the last game evaluation.
+    pub window_ticks_used: u32,
+
+    /// Voluntary yields since the last game evaluation.
+    pub window_yields: u32,
+
+    /// Ticks run, decayed like `metrics.recent_ticks`: the usage the
+    /// payoff's fairness term judges.
+    pub recent_ticks_used: u32,
+
+    /// Like `recent_ticks_used`, but in processor cycles. Only accumulated
+    /// with the `cycle-accounting` feature.
+    pub recent_cycles_used: u64,
+
+    /// Ticks run in the current budget period.
+    pub budget_used: u32,
+
+    /// Ticks elapsed in the current budget period.
+    pub budget_elapsed: u32,
+
+    /// Ticks run since the start of the current period: the job's
+    /// execution time, checked against `config.wcet_ticks`.
     pub period_ticks: u32,
 
+    /// Absolute tick of the current job's deadline: its release tick plus
+    /// the release window, and the next release. Moves on only at a
+    /// release, whatever the task's state, so the deadline holds still
+    /// while the task waits for the CPU.
+    pub deadline_tick: u64,
+
+    /// Why the task is blocked, if it is.
+    pub block_reason: Option<BlockReason>,
+
+    /// Absolute tick at which a timed wait expires. `None` when the task
+    /// is not in a timed wait.
+    pub wake_tick: Option<u64>,
+
+    /// Why the task last left a timed wait.
+    pub wake_reason: WakeReason,
+
+    /// Task that must signal completion before this one may run each
+    /// period (precedence constraint). `None` for independent tasks.
+    pub predecessor: Option<usize>,
+
+    /// Whether the predecessor has signalled completion for the current
+    /// period.
+    pub predecessor_signalled: bool,
+
+    /// Whether the current period's job has been reported complete.
+    pub job_done: bool,
+
+    /// Whether the outstanding job already missed its deadline, so its
+    /// completion is measured from the start of the following period.
+    pub job_late: bool,
+
+    /// Whether this period's hard deadline miss was already recorded by
+    /// the deadline compare interrupt, so the tick does not count it again.
+    pub deadline_enforced: bool,
+
+    /// Whether the outstanding job exceeded its WCET, pending the deadline
+    /// outcome that decides how the overrun is penalized.
+    pub job_overran: bool,
+
+    /// Consecutive periods that ended with the job still unfinished.
+    /// Reset by an on-time completion.
+    pub overload_streak: u32,
+
+    /// Number of periods each release spans. Starts at 1 and is raised by
+    /// the `SkipReleases` degradation policy.
+    pub release_stride: u32,
+
+    /// Earliest and latest job completion relative to the deadline, in
+    /// ticks (negative = early, positive = late). `None` until a job
+    /// completes. Cleared by `reset_deadline_jitter()`.
+    pub completion_jitter: Option<(i32, i32)>,
+
+    /// Priority donated to this task by another, if any.
+    pub donation: Option<Donation>,
+
+    /// Deferrals from `defer_to()` not yet consumed by
+    /// `wait_for_deferral()`.
+    pub deferrals_pending: u32,
+
+    /// Notifications from `notify()` not yet taken by
+    /// `wait_notification()`.
+    pub notification: u32,
+
+    /// Whether `notification` holds a value the task has not yet taken.
+    pub notify_pending: bool,
+
+    /// Address of the event group the task blocked in and has not yet
+    /// collected the outcome of. The group's record of a wait is stale
+    /// unless this points back at it, e.g. after a restart mid-wait.
+    pub event_wait: Option<usize>,
+
+    /// Tick at which the outstanding event arrived, set by
+    /// `event_arrived()` and cleared by `event_handled()`.
+    pub event_arrival: Option<u64>,
+
+    /// Response time of the most recently handled event, in ticks.
+    pub last_response: Option<u32>,
+
+    /// Longest event response time seen, in ticks.
+    pub max_response: u32,
+
+    /// Response time in ticks above which the response alarm hook fires.
+    /// `0` disables the alarm.
+    pub response_threshold: u32,
+
+    /// Tick at which `schedule()` last selected this task to run. `None`
+    /// if it has not run yet.
+    pub last_scheduled_at: Option<u64>,
+
+    /// Whether this is the kernel's idle task: selected only when nothing
+    /// else is runnable, and left out of the game and CPU accounting.
+    pub idle: bool,
+
     /// Whether this task is allocated (true) or a free slot (false).
     pub active: bool,
 }
@@ -258,42 +968,121 @@ unsafe impl Send for TaskControlBlock {}
 unsafe impl Sync for TaskControlBlock {}
 
 impl TaskControlBlock {
+    /// An empty (unallocated) TCB, for initializing TCB arrays.
+    pub const EMPTY: Self = Self::empty();
+
     /// Create an empty (unallocated) TCB. Used to initialize the static array.
     pub const fn empty() -> Self {
         Self {
             id: 0,
             state: TaskState::Suspended,
-            config: TaskConfig {
-                priority: 0,
-                deadline_ticks: 0,
-                wcet_ticks: 0,
-                affinity_mask: 0x01,
-                time_slice: 0,
-            },
+            config: TaskConfig::DEFAULT,
             strategy: Strategy::Cooperative,
+            tft_cooperating: true,
+            strategy_locked: false,
             payoff: PayoffMetrics::new(),
+            entry: None,
             stack_pointer: core::ptr::null_mut(),
+            #[cfg(not(feature = "stack-pool"))]
             stack: [0u8; STACK_SIZE],
+            #[cfg(feature = "stack-pool")]
+            stack: (core::ptr::null_mut(), 0),
             ticks_remaining: 0,
             total_ticks: 0,
+            window_ticks_used: 0,
+            window_yields: 0,
+            recent_ticks_used: 0,
+            recent_cycles_used: 0,
+            budget_used: 0,
+            budget_elapsed: 0,
             period_ticks: 0,
+            deadline_tick: 0,
+            block_reason: None,
+            wake_tick: None,
+            wake_reason: WakeReason::Timeout,
+            predecessor: None,
+            predecessor_signalled: false,
+            job_done: false,
+            job_late: false,
+            deadline_enforced: false,
+            job_overran: false,
+            overload_streak: 0,
+            release_stride: 1,
+            completion_jitter: None,
+            donation: None,
+            deferrals_pending: 0,
+            notification: 0,
+            notify_pending: false,
+            event_wait: None,
+            event_arrival: None,
+            last_response: None,
+            max_response: 0,
+            response_threshold: 0,
+            last_scheduled_at: None,
+            idle: false,
             active: false,
         }
     }
 
+    /// Whether this task takes part in the scheduling game: allocated and
+    /// not the idle task.
+    pub fn is_player(&self) -> bool {
+        self.active && !self.idle
+    }
+
+    /// Whether the task's current move is to cooperate: its strategy, or
+    /// for tit-for-tat the move chosen for this window.
+    pub fn is_cooperating(&self) -> bool {
+        match self.strategy {
+            Strategy::Cooperative => true,
+            Strategy::Selfish => false,
+            Strategy::TitForTat => self.tft_cooperating,
+        }
+    }
+
     /// Initialize a TCB for a new task with the given configuration and strategy.
     ///
-    /// This sets the task to Ready state and initializes its time slice.
+    /// This sets the task to Ready state and initializes its time slice at
+    /// the default `TICK_HZ`; the scheduler reloads it at its runtime rate.
     /// The stack must be separately initialized by `arch::init_stack()`.
     pub fn init(&mut self, id: usize, config: TaskConfig, strategy: Strategy) {
         self.id = id;
         self.state = TaskState::Ready;
         self.config = config;
         self.strategy = strategy;
+        self.tft_cooperating = true;
         self.payoff = PayoffMetrics::new();
-        self.ticks_remaining = config.effective_time_slice();
+        self.ticks_remaining = config.effective_time_slice(TICK_HZ);
         self.total_ticks = 0;
+        self.window_ticks_used = 0;
+        self.window_yields = 0;
+        self.recent_ticks_used = 0;
+        self.recent_cycles_used = 0;
+        self.budget_used = 0;
+        self.budget_elapsed = 0;
         self.period_ticks = 0;
+        self.deadline_tick = 0;
+        self.block_reason = None;
+        self.wake_tick = None;
+        self.wake_reason = WakeReason::Timeout;
+        self.predecessor = None;
+        self.predecessor_signalled = false;
+        self.job_done = false;
+        self.job_late = false;
+        self.deadline_enforced = false;
+        self.job_overran = false;
+        self.overload_streak = 0;
+        self.release_stride = 1;
+        self.completion_jitter = None;
+        self.donation = None;
+        self.deferrals_pending = 0;
+        self.notification = 0;
+        self.notify_pending = false;
+        self.event_wait = None;
+        self.event_arrival = None;
+        self.last_response = None;
+        self.max_response = 0;
+        self.last_scheduled_at = None;
         self.active = true;
     }
 
@@ -301,19 +1090,68 @@ impl TaskControlBlock {
     /// Increments the yield counter and boosts cooperation score.
     pub fn record_yield(&mut self) {
         self.payoff.voluntary_yields += 1;
+        self.payoff.recent_yields += 1;
+        self.window_yields += 1;
+        self.payoff.ticks_since_last_yield = 0;
+        self.payoff.run_streak = 0;
         // Boost cooperation score (capped at 500)
         self.payoff.cooperation_score = (self.payoff.cooperation_score + 10).min(500);
     }
 
+    /// Record that this task blocked of its own accord, in a delay or a
+    /// wait. It gave up the CPU as a yield does, so its non-yielding count
+    /// starts over, but it is not credited with a yield.
+    pub fn record_voluntary_block(&mut self) {
+        self.payoff.ticks_since_last_yield = 0;
+        self.payoff.run_streak = 0;
+    }
+
     /// Record that this task met its deadline for the current period.
     pub fn record_deadline_met(&mut self) {
         self.payoff.deadlines_met += 1;
+        self.payoff.recent_deadlines_met += 1;
         self.payoff.consecutive_overruns = 0;
     }
 
+    /// Fold a job completion offset (ticks relative to the deadline) into
+    /// the recorded jitter range.
+    pub fn record_completion_offset(&mut self, offset: i32) {
+        self.completion_jitter = match self.completion_jitter {
+            Some((min, max)) => Some((min.min(offset), max.max(offset))),
+            None => Some((offset, offset)),
+        };
+    }
+
+    /// Ticks of execution each release of this task gets before its
+    /// deadline: the deadline scaled by `release_stride`. `0` for tasks
+    /// without a deadline.
+    #[inline]
+    pub fn release_window(&self) -> u32 {
+        self.config.deadline_ticks.saturating_mul(self.release_stride)
+    }
+
+    /// Ticks from `now` until the outstanding job's deadline, for
+    /// deadline-driven scheduling. `None` for tasks without a deadline.
+    ///
+    /// A job that already missed its deadline is maximally urgent
+    /// (`i64::MIN`). Once this period's job is done, the next release's
+    /// deadline one window later counts instead.
+    pub fn ticks_to_deadline(&self, now: u64) -> Option<i64> {
+        let window = self.release_window() as i64;
+        if window == 0 {
+            return None;
+        }
+        if self.job_late {
+            return Some(i64::MIN);
+        }
+        let left = self.deadline_tick as i64 - now as i64;
+        Some(if self.job_done { left + window } else { left })
+    }
+
     /// Record that this task missed its deadline.
     pub fn record_deadline_missed(&mut self) {
         self.payoff.deadlines_missed += 1;
+        self.payoff.recent_deadlines_missed += 1;
     }
 
     /// Record a time-slice overrun.
@@ -324,6 +1162,72 @@ impl TaskControlBlock {
         self.payoff.cooperation_score = (self.payoff.cooperation_score - 20).max(0);
     }
 
+    /// Record a WCET overrun on a job that met its deadline anyway. Only
+    /// counted: the WCET estimate was pessimistic, not the task harmful.
+    pub fn record_soft_overrun(&mut self) {
+        self.payoff.soft_overruns += 1;
+    }
+
+    /// Lowest address of the task's stack.
+    #[inline]
+    pub fn stack_base(&self) -> *const u8 {
+        #[cfg(not(feature = "stack-pool"))]
+        return self.stack.as_ptr();
+        #[cfg(feature = "stack-pool")]
+        return self.stack.0;
+    }
+
+    /// Mutable pointer to the lowest address of the task's stack.
+    #[inline]
+    pub fn stack_base_mut(&mut self) -> *mut u8 {
+        #[cfg(not(feature = "stack-pool"))]
+        return self.stack.as_mut_ptr();
+        #[cfg(feature = "stack-pool")]
+        return self.stack.0;
+    }
+
+    /// Size of the task's stack in bytes.
+    #[inline]
+    pub fn stack_size(&self) -> usize {
+        #[cfg(not(feature = "stack-pool"))]
+        return STACK_SIZE;
+        #[cfg(feature = "stack-pool")]
+        return self.stack.1;
+    }
+
+    /// Validate a saved stack pointer against this task's stack.
+    ///
+    /// `BoundsCheck` requires `sp` to lie above the canary word and below
+    /// the top of the stack; `CanaryCheck` additionally requires the canary
+    /// at the stack base to hold `STACK_CANARY`.
+    pub fn check_stack(&self, sp: *const u32, level: StackCheck) -> Result<(), StackFault> {
+        if level == StackCheck::None {
+            return Ok(());
+        }
+
+        let base = self.stack_base() as usize;
+        let top = base + self.stack_size();
+        let sp = sp as usize;
+        if sp < base + 4 || sp >= top {
+            return Err(StackFault::OutOfBounds);
+        }
+
+        if level == StackCheck::CanaryCheck {
+            let canary = unsafe { core::ptr::read_unaligned(self.stack_base() as *const u32) };
+            if canary != STACK_CANARY {
+                return Err(StackFault::CanaryCorrupted);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if this task has run for more than `NON_YIELD_THRESHOLD`
+    /// ticks without a voluntary yield or block.
+    #[inline]
+    pub fn is_non_yielding(&self) -> bool {
+        self.active && self.payoff.ticks_since_last_yield > NON_YIELD_THRESHOLD
+    }
+
     /// Check if this task is runnable (Ready and active).
     #[inline]
     pub fn is_runnable(&self) -> bool {
@@ -336,15 +1240,26 @@ impl TaskControlBlock {
         (self.config.affinity_mask & (1 << core_id)) != 0
     }
 
+    /// Base priority plus any donated priority.
+    #[inline]
+    pub fn boosted_priority(&self) -> i32 {
+        let donated = self.donation.map_or(0, |d| d.amount as i32);
+        self.config.priority as i32 + donated
+    }
+
     /// Get the effective priority after game-theory payoff adjustment.
     ///
-    /// The payoff is scaled and added to the base priority. A task with
-    /// high payoff gets a scheduling boost; one with negative payoff
-    /// gets deprioritized (but never below 0).
+    /// The payoff is scaled and added to the base priority (including any
+    /// donation). A task with high payoff gets a scheduling boost; one with
+    /// negative payoff gets deprioritized (but never below 0). Anchor
+    /// tasks are never deprioritized below their base.
     pub fn effective_priority(&self) -> i32 {
-        let base = self.config.priority as i32;
+        let base = self.boosted_priority();
         // Scale payoff: divide by 100 to convert from fixed-point
         let payoff_adjustment = self.payoff.payoff / 100;
+        if self.config.anchor {
+            return base + payoff_adjustment.max(0);
+        }
         (base + payoff_adjustment).max(0)
     }
 }
@@ -357,6 +1272,58 @@ impl TaskControlBlock {
 mod tests {
     use super::*;
 
+    extern "C" fn spin() -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    #[test]
+    fn test_periodic_release_does_not_drift() {
+        let mut release = PeriodicRelease::new(0, 100);
+        // However late in the period the work finishes, releases stay on
+        // multiples of the period
+        for (k, done_at) in [(1u64, 7), (2, 180), (3, 299)] {
+            assert_eq!(release.advance(done_at), 0);
+            assert_eq!(release.next_wake(), k * 100);
+        }
+    }
+
+    #[test]
+    fn test_periodic_release_detects_and_skips_missed() {
+        // Catch up: the overdue releases are kept, so waits return at once
+        let mut catch_up = PeriodicRelease::new(0, 100);
+        assert_eq!(catch_up.advance(350), 3);
+        assert_eq!(catch_up.next_wake(), 100);
+        assert_eq!(catch_up.advance(350), 2);
+        assert_eq!(catch_up.next_wake(), 200);
+
+        // Skip: jump straight to the first release still ahead
+        let mut skip = PeriodicRelease::new(0, 100).skip_missed();
+        assert_eq!(skip.advance(350), 3);
+        assert_eq!(skip.next_wake(), 400);
+        assert_eq!(skip.advance(410), 0);
+        assert_eq!(skip.next_wake(), 500);
+
+        // A release exactly at `now` is already due
+        let mut exact = PeriodicRelease::new(0, 100).skip_missed();
+        assert_eq!(exact.advance(100), 1);
+        assert_eq!(exact.next_wake(), 200);
+    }
+
+    #[test]
+    fn test_task_entry_wraps_noreturn_fn() {
+        const ENTRY: TaskEntry = crate::task_entry!(spin);
+        assert_eq!(ENTRY.address(), spin as *const () as usize);
+        assert_eq!(TaskEntry::new(spin).address(), ENTRY.address());
+        assert_eq!(ENTRY.as_fn() as usize, spin as *const () as usize);
+
+        // The TCB keeps the entry for restarts
+        let mut tcb = TaskControlBlock::empty();
+        tcb.entry = Some(ENTRY);
+        assert_eq!(tcb.entry.map(TaskEntry::address), Some(spin as *const () as usize));
+    }
+
     #[test]
     fn test_tcb_initialization() {
         let mut tcb = TaskControlBlock::empty();
@@ -369,6 +1336,7 @@ mod tests {
             wcet_ticks: 20,
             affinity_mask: 0x01,
             time_slice: 15,
+            ..TaskConfig::DEFAULT
         };
         tcb.init(0, config, Strategy::Cooperative);
 
@@ -389,6 +1357,7 @@ mod tests {
             wcet_ticks: 10,
             affinity_mask: 0x01,
             time_slice: 0,
+            ..TaskConfig::DEFAULT
         };
         tcb.init(1, config, Strategy::Cooperative);
 
@@ -411,6 +1380,7 @@ mod tests {
             wcet_ticks: 10,
             affinity_mask: 0x01,
             time_slice: 0,
+            ..TaskConfig::DEFAULT
         };
         tcb.init(2, config, Strategy::Selfish);
 
@@ -434,6 +1404,7 @@ mod tests {
             wcet_ticks: 10,
             affinity_mask: 0x01,
             time_slice: 0,
+            ..TaskConfig::DEFAULT
         };
         tcb.init(3, config, Strategy::Cooperative);
 
@@ -455,6 +1426,7 @@ mod tests {
             wcet_ticks: 10,
             affinity_mask: 0b0101, // cores 0 and 2
             time_slice: 0,
+            ..TaskConfig::DEFAULT
         };
         tcb.init(4, config, Strategy::Cooperative);
 
@@ -471,7 +1443,82 @@ mod tests {
             wcet_ticks: 10,
             affinity_mask: 0x01,
             time_slice: 0,
+            ..TaskConfig::DEFAULT
+        };
+        assert_eq!(config.effective_time_slice(TICK_HZ), DEFAULT_TIME_SLICE);
+    }
+
+    #[test]
+    fn test_builder_converts_durations_at_tick_rate() {
+        let builder = TaskConfig::builder()
+            .priority(3)
+            .period(Duration::from_millis(100))
+            .wcet(Duration::from_millis(5))
+            .time_slice(Duration::from_micros(2500));
+
+        let at_1k = builder.build(1000);
+        assert_eq!(at_1k.priority, 3);
+        assert_eq!(at_1k.deadline_ticks, 100);
+        assert_eq!(at_1k.wcet_ticks, 5);
+        assert_eq!(at_1k.time_slice, 3); // 2.5 ticks rounded up
+
+        let at_100 = builder.build(100);
+        assert_eq!(at_100.deadline_ticks, 10);
+        assert_eq!(at_100.wcet_ticks, 1);
+        assert_eq!(at_100.time_slice, 1);
+
+        let at_10k = builder.build(10_000);
+        assert_eq!(at_10k.deadline_ticks, 1000);
+        assert_eq!(at_10k.wcet_ticks, 50);
+        assert_eq!(at_10k.time_slice, 25);
+
+        // An explicit deadline takes precedence over the period
+        let constrained = builder.deadline(Duration::from_millis(40)).build(1000);
+        assert_eq!(constrained.deadline_ticks, 40);
+
+        // Unset fields stay at their tick defaults
+        let bare = TaskConfig::builder().build(1000);
+        assert_eq!(bare.deadline_ticks, 0);
+        assert_eq!(bare.time_slice, 0);
+        assert_eq!(bare.affinity_mask, 0x01);
+    }
+
+    #[test]
+    fn test_anchor_never_deprioritized() {
+        let mut tcb = TaskControlBlock::empty();
+        let config = TaskConfig::builder().priority(4).anchor().build(1000);
+        assert!(config.anchor);
+        tcb.init(0, config, Strategy::Selfish);
+
+        tcb.payoff.payoff = -5000;
+        assert_eq!(tcb.effective_priority(), 4);
+        tcb.payoff.payoff = 300;
+        assert_eq!(tcb.effective_priority(), 7);
+
+        tcb.config.anchor = false;
+        tcb.payoff.payoff = -5000;
+        assert_eq!(tcb.effective_priority(), 0);
+    }
+
+    #[test]
+    fn test_time_slice_ms_conversion() {
+        let config = TaskConfig {
+            time_slice_ms: 10,
+            ..TaskConfig::DEFAULT
+        };
+        assert_eq!(config.effective_time_slice(1000), 10);
+        assert_eq!(config.effective_time_slice(100), 1);
+        assert_eq!(config.effective_time_slice(10_000), 100);
+
+        // Sub-tick slices round up to one tick
+        assert_eq!(config.effective_time_slice(50), 1);
+
+        // Tick-based field takes precedence when both are set
+        let config = TaskConfig {
+            time_slice: 7,
+            time_slice_ms: 10,
+            ..TaskConfig::DEFAULT
         };
-        assert_eq!(config.effective_time_slice(), DEFAULT_TIME_SLICE);
+        assert_eq!(config.effective_time_slice(10_000), 7);
     }
 }
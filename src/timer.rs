@@ -0,0 +1,172 @@
+//! # Software Timers
+//!
+//! One-shot and periodic callbacks driven by the scheduler tick, without a
+//! task or stack per timer. Timers live in a fixed table of `MAX_TIMERS`
+//! slots inside the scheduler and are managed through
+//! `kernel::create_timer()`, `start_timer()` and `stop_timer()`.
+//!
+//! ## Callback Context
+//!
+//! Expired callbacks are invoked from `Scheduler::tick()`, i.e. in the
+//! tick interrupt with the scheduler borrowed. They must be short, must
+//! not block, and must not call back into the kernel; to hand off real
+//! work, signal a task (e.g. with `sync::Signal`) and return.
+
+/// One software timer slot.
+#[derive(Debug, Clone, Copy)]
+pub struct TimerControlBlock {
+    /// Whether this slot is allocated.
+    pub active: bool,
+    /// Whether the timer is counting down.
+    pub running: bool,
+    /// Reload value in ticks (at least 1).
+    pub period: u32,
+    /// Ticks left until the timer fires.
+    pub remaining: u32,
+    /// Periodic timers reload after firing; one-shots stop.
+    pub repeating: bool,
+    /// Function called when the timer fires.
+    pub callback: Option<fn()>,
+}
+
+impl TimerControlBlock {
+    /// An unallocated slot.
+    pub const EMPTY: Self = Self {
+        active: false,
+        running: false,
+        period: 0,
+        remaining: 0,
+        repeating: false,
+        callback: None,
+    };
+
+    /// Start (or restart) counting a full period from now.
+    pub fn start(&mut self) {
+        self.remaining = self.period;
+        self.running = true;
+    }
+
+    /// Advance one tick.
+    ///
+    /// # Returns
+    /// The callback to invoke if the timer expired on this tick. A periodic
+    /// timer reloads its period; a one-shot stops.
+    pub fn tick(&mut self) -> Option<fn()> {
+        if !self.active || !self.running {
+            return None;
+        }
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            return None;
+        }
+        if self.repeating {
+            self.remaining = self.period;
+        } else {
+            self.running = false;
+        }
+        self.callback
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use crate::config::MAX_TIMERS;
+    use crate::error::KernelError;
+    use crate::scheduler::Scheduler;
+
+    static ONE_SHOT_FIRED: AtomicU32 = AtomicU32::new(0);
+    static PERIODIC_FIRED: AtomicU32 = AtomicU32::new(0);
+    static STOPPED_FIRED: AtomicU32 = AtomicU32::new(0);
+
+    fn one_shot() {
+        ONE_SHOT_FIRED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn periodic() {
+        PERIODIC_FIRED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn stopped() {
+        STOPPED_FIRED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[test]
+    fn test_timers_fire_at_the_right_ticks() {
+        let mut sched = Scheduler::new();
+        let once = sched.create_timer(5, false, one_shot).unwrap();
+        let every = sched.create_timer(3, true, periodic).unwrap();
+        sched.start_timer(once).unwrap();
+        sched.start_timer(every).unwrap();
+
+        let mut one_shot_at = [0u64; 4];
+        let mut periodic_at = [0u64; 4];
+        for _ in 0..12 {
+            let o = ONE_SHOT_FIRED.load(Ordering::Relaxed);
+            let p = PERIODIC_FIRED.load(Ordering::Relaxed);
+            sched.tick();
+            if ONE_SHOT_FIRED.load(Ordering::Relaxed) > o {
+                one_shot_at[o as usize] = sched.tick_count;
+            }
+            if PERIODIC_FIRED.load(Ordering::Relaxed) > p {
+                periodic_at[p as usize] = sched.tick_count;
+            }
+        }
+
+        // The one-shot fires once, the periodic timer every period
+        assert_eq!(ONE_SHOT_FIRED.load(Ordering::Relaxed), 1);
+        assert_eq!(one_shot_at[0], 5);
+        assert!(!sched.timers[once].running);
+        assert_eq!(PERIODIC_FIRED.load(Ordering::Relaxed), 4);
+        assert_eq!(periodic_at, [3, 6, 9, 12]);
+        assert!(sched.timers[every].running);
+    }
+
+    #[test]
+    fn test_stopped_timer_does_not_fire() {
+        let mut sched = Scheduler::new();
+        let id = sched.create_timer(4, true, stopped).unwrap();
+
+        // Created stopped
+        for _ in 0..10 {
+            sched.tick();
+        }
+        assert_eq!(STOPPED_FIRED.load(Ordering::Relaxed), 0);
+
+        sched.start_timer(id).unwrap();
+        for _ in 0..3 {
+            sched.tick();
+        }
+        sched.stop_timer(id).unwrap();
+        for _ in 0..10 {
+            sched.tick();
+        }
+        assert_eq!(STOPPED_FIRED.load(Ordering::Relaxed), 0);
+
+        // Restarting counts a full period again
+        sched.start_timer(id).unwrap();
+        for _ in 0..3 {
+            sched.tick();
+        }
+        assert_eq!(STOPPED_FIRED.load(Ordering::Relaxed), 0);
+        sched.tick();
+        assert_eq!(STOPPED_FIRED.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_timer_table_limits() {
+        let mut sched = Scheduler::new();
+        assert_eq!(sched.create_timer(0, false, one_shot), Err(KernelError::InvalidState));
+        for _ in 0..MAX_TIMERS {
+            sched.create_timer(10, false, one_shot).unwrap();
+        }
+        assert_eq!(sched.create_timer(10, false, one_shot), Err(KernelError::TooManyTimers));
+        assert_eq!(sched.start_timer(MAX_TIMERS), Err(KernelError::InvalidTimerId));
+        assert_eq!(sched.stop_timer(MAX_TIMERS), Err(KernelError::InvalidTimerId));
+    }
+}
@@ -0,0 +1,135 @@
+//! # Stall Watchdog
+//!
+//! Safety net for a critical section that never ends. While interrupts are
+//! masked SysTick cannot run, so a task stuck inside
+//! `sync::critical_section` freezes the whole system silently. The tick
+//! handler bumps a heartbeat counter; an independent, always-on time source
+//! that the critical section does not mask (an NMI-routed timer, or the
+//! early-warning interrupt of a window watchdog) periodically feeds the
+//! heartbeat to a [`StallDetector`]. When the heartbeat stops advancing for
+//! too long, the handler invokes an emergency action such as
+//! [`recover`].
+//!
+//! ```ignore
+//! static mut DETECTOR: StallDetector = StallDetector::new(50);
+//!
+//! #[exception]
+//! fn NonMaskableInt() {
+//!     let now = always_on_timer_ms();
+//!     if unsafe { DETECTOR.observe(watchdog::heartbeat(), now) } {
+//!         watchdog::recover();
+//!     }
+//! }
+//! ```
+//!
+//! Pausing the scheduler with `kernel::pause()` also stops the heartbeat,
+//! so stop observing while paused.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Incremented on every scheduler tick interrupt.
+static HEARTBEAT: AtomicU32 = AtomicU32::new(0);
+
+/// Record one tick. Called from the tick interrupt handler.
+#[inline]
+pub fn beat() {
+    #[cfg(not(feature = "cortex-m0"))]
+    HEARTBEAT.fetch_add(1, Ordering::Relaxed);
+    // ARMv6-M has no atomic read-modify-write; the tick is the only writer
+    #[cfg(feature = "cortex-m0")]
+    HEARTBEAT.store(HEARTBEAT.load(Ordering::Relaxed).wrapping_add(1), Ordering::Relaxed);
+}
+
+/// Current heartbeat count. Wraps on overflow; only changes matter.
+#[inline]
+pub fn heartbeat() -> u32 {
+    HEARTBEAT.load(Ordering::Relaxed)
+}
+
+/// Detects that the tick heartbeat has not advanced for longer than a
+/// maximum critical-section duration, measured by an external time source.
+///
+/// Time is in whatever unit the external source counts (e.g. milliseconds
+/// of an always-on timer) and may wrap.
+pub struct StallDetector {
+    /// Longest allowed gap between heartbeats, in external time units.
+    max_stall: u32,
+    /// Heartbeat value at the last observation.
+    last_beat: u32,
+    /// External time at which the heartbeat last advanced. `None` until
+    /// the first observation.
+    last_progress: Option<u32>,
+}
+
+impl StallDetector {
+    /// Create a detector that trips once no tick has occurred for more
+    /// than `max_stall` time units.
+    pub const fn new(max_stall: u32) -> Self {
+        Self {
+            max_stall,
+            last_beat: 0,
+            last_progress: None,
+        }
+    }
+
+    /// Feed the current heartbeat and external time.
+    ///
+    /// # Returns
+    /// `true` if the heartbeat has been stuck for more than `max_stall`,
+    /// i.e. a critical section (or anything else masking the tick) has
+    /// exceeded its maximum duration.
+    pub fn observe(&mut self, beat: u32, now: u32) -> bool {
+        match self.last_progress {
+            Some(since) if beat == self.last_beat => now.wrapping_sub(since) > self.max_stall,
+            _ => {
+                self.last_beat = beat;
+                self.last_progress = Some(now);
+                false
+            }
+        }
+    }
+}
+
+/// Default emergency action: reset the system.
+pub fn recover() -> ! {
+    cortex_m::peripheral::SCB::sys_reset()
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests (host-only)
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advancing_heartbeat_never_trips() {
+        let mut detector = StallDetector::new(10);
+        for t in 0..100u32 {
+            assert!(!detector.observe(t / 4, t * 2));
+        }
+    }
+
+    #[test]
+    fn test_stuck_heartbeat_trips_after_max_stall() {
+        let mut detector = StallDetector::new(10);
+        assert!(!detector.observe(7, 100));
+        assert!(!detector.observe(7, 105));
+        assert!(!detector.observe(7, 110));
+        assert!(detector.observe(7, 111));
+
+        // Ticks resume: detector re-arms
+        assert!(!detector.observe(8, 112));
+        assert!(!detector.observe(8, 122));
+        assert!(detector.observe(8, 123));
+    }
+
+    #[test]
+    fn test_external_time_wraps() {
+        let mut detector = StallDetector::new(10);
+        assert!(!detector.observe(1, u32::MAX - 4));
+        assert!(!detector.observe(1, 5));
+        assert!(detector.observe(1, 6));
+    }
+}